@@ -0,0 +1,50 @@
+//! Locate the Noto Sans SC subset compiled in by the `embedded-noto` and `bundled` features
+
+use std::env;
+use std::path::PathBuf;
+
+/// Build-time environment variable naming a different font file to embed
+const EMBED_PATH_ENV: &str = "EGUI_CHINESE_FONT_EMBED_PATH";
+
+fn main() {
+    println!("cargo:rerun-if-env-changed={}", EMBED_PATH_ENV);
+    let embedded = env::var_os("CARGO_FEATURE_EMBEDDED_NOTO").is_some();
+    let bundled = env::var_os("CARGO_FEATURE_BUNDLED").is_some();
+    if !embedded && !bundled {
+        return;
+    }
+
+    let font = match env::var_os(EMBED_PATH_ENV) {
+        Some(path) => PathBuf::from(path),
+        None => PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap()).join("fonts/NotoSansSC-Subset.otf"),
+    };
+    println!("cargo:rerun-if-changed={}", font.display());
+    if !font.is_file() {
+        panic!(
+            "{} is missing; run scripts/subset-noto.sh to generate it, or set {} to the font file to embed",
+            font.display(),
+            EMBED_PATH_ENV
+        );
+    }
+
+    if embedded {
+        println!("cargo:rustc-env=EGUI_CHINESE_FONT_EMBEDDED={}", font.display());
+    }
+    #[cfg(feature = "bundled")]
+    compress(&font);
+}
+
+/// Gzip `font` into `OUT_DIR` for the `bundled` feature, which decompresses it on first use
+#[cfg(feature = "bundled")]
+fn compress(font: &std::path::Path) {
+    use std::fs::File;
+    use std::io;
+
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    let out = PathBuf::from(env::var_os("OUT_DIR").unwrap()).join("NotoSansSC-Subset.otf.gz");
+    let mut encoder = GzEncoder::new(File::create(&out).unwrap(), Compression::best());
+    io::copy(&mut File::open(font).unwrap(), &mut encoder).unwrap();
+    encoder.finish().unwrap();
+}
@@ -3,7 +3,9 @@
 //! This example demonstrates how to set up Chinese fonts for an egui application
 //! and displays various Chinese text samples.
 
-use egui_chinese_font::setup_chinese_fonts;
+use egui::{FontFamily, FontTweak, RichText};
+use egui_chinese_font::prelude::*;
+use egui_chinese_font::{register_chinese_families, ChineseStyle};
 
 fn main() -> Result<(), eframe::Error> {
     let options = eframe::NativeOptions {
@@ -18,18 +20,40 @@ fn main() -> Result<(), eframe::Error> {
         options,
         Box::new(|cc| {
             // Setup Chinese fonts - this is the key line!
-            if let Err(e) = setup_chinese_fonts(&cc.egui_ctx) {
+            // The emoji fallback lets the emoji sample below render where the system has an emoji font
+            if let Err(e) = cc.egui_ctx.setup_chinese_fonts_with(|config| config.with_emoji_fallback(true)) {
                 eprintln!("Failed to load Chinese fonts: {}", e);
             }
 
+            // Named families for the style comparison; missing styles fall back to the default font
+            if let Err(e) = register_chinese_families(
+                &cc.egui_ctx,
+                &[
+                    (ChineseStyle::Hei, "ChineseSans"),
+                    (ChineseStyle::Song, "ChineseSerif"),
+                    (ChineseStyle::Kai, "ChineseKai"),
+                ],
+            ) {
+                eprintln!("Some Chinese font styles are not installed: {}", e);
+            }
+
             Ok(Box::new(ChineseFontDemo::default()))
         }),
     )
 }
 
-#[derive(Default)]
 struct ChineseFontDemo {
     text_input: String,
+    recommended_tweak: bool,
+}
+
+impl Default for ChineseFontDemo {
+    fn default() -> Self {
+        Self {
+            text_input: String::new(),
+            recommended_tweak: true,
+        }
+    }
 }
 
 impl eframe::App for ChineseFontDemo {
@@ -70,6 +94,40 @@ impl eframe::App for ChineseFontDemo {
 
             ui.separator();
 
+            ui.group(|ui| {
+                ui.heading("Alignment - 对齐");
+                if ui.checkbox(&mut self.recommended_tweak, "Recommended size and baseline correction").changed() {
+                    // An explicit default tweak turns the correction off
+                    let recommended_tweak = self.recommended_tweak;
+                    let result = ctx.setup_chinese_fonts_with(|config| {
+                        if recommended_tweak {
+                            config
+                        } else {
+                            config.with_tweak(FontTweak::default())
+                        }
+                    });
+                    if let Err(e) = result {
+                        eprintln!("Failed to reload Chinese fonts: {}", e);
+                    }
+                }
+                ui.label(RichText::new("Hxg 汉字 Hxg 漢字 Hxg").size(24.0));
+                ui.label("Version 2.0 版本说明 (beta) 测试");
+            });
+
+            ui.separator();
+
+            ui.group(|ui| {
+                ui.heading("Font Styles - 字体风格");
+                for (label, family) in [("黑体 Hei", "ChineseSans"), ("宋体 Song", "ChineseSerif"), ("楷体 Kai", "ChineseKai")] {
+                    ui.horizontal(|ui| {
+                        ui.label(label);
+                        ui.label(RichText::new("学而时习之，不亦说乎。").family(FontFamily::Name(family.into())));
+                    });
+                }
+            });
+
+            ui.separator();
+
             ui.group(|ui| {
                 ui.heading("Interactive Text - 交互文本");
                 ui.label("Type some Chinese text:");
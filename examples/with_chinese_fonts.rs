@@ -0,0 +1,49 @@
+//! Example using the `with_chinese_fonts` wrapper of the `eframe` feature.
+//!
+//! Fonts are set up before the app is constructed, and a failure is shown as
+//! a warning in the window instead of being printed to the console.
+//!
+//! Run with: `cargo run --example with_chinese_fonts --features eframe`
+
+use egui::RichText;
+use egui_chinese_font::{with_chinese_fonts, FontError, LoadedFont};
+
+fn main() -> Result<(), eframe::Error> {
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_inner_size([420.0, 240.0])
+            .with_title("Chinese Font Wrapper - 中文字体"),
+        ..Default::default()
+    };
+
+    eframe::run_native(
+        "Chinese Font Wrapper",
+        options,
+        with_chinese_fonts(|_cc, fonts| Ok(Box::new(WrapperDemo { fonts }))),
+    )
+}
+
+struct WrapperDemo {
+    fonts: Result<LoadedFont, FontError>,
+}
+
+impl eframe::App for WrapperDemo {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            match &self.fonts {
+                Ok(loaded) => {
+                    ui.label(format!("Using font \"{}\" ({:?})", loaded.name(), loaded.origin()));
+                }
+                Err(err) => {
+                    ui.colored_label(ui.visuals().warn_fg_color, "⚠ No Chinese font could be loaded");
+                    ui.label(err.to_string());
+                    ui.label("Install a Chinese font, e.g. Noto Sans CJK, and restart the application.");
+                }
+            }
+
+            ui.separator();
+            ui.label(RichText::new("你好，世界！").size(28.0));
+            ui.label("繁體中文：歡迎使用");
+        });
+    }
+}
@@ -0,0 +1,50 @@
+/*
+ * C interface of egui-chinese-font, available with the `ffi` feature.
+ *
+ * `ctx` is an opaque handle to an egui context. The Rust side of the host's
+ * bindings creates it with `egui_chinese_font::ffi::ContextHandle::create`;
+ * it stays valid until it is passed to egui_chinese_font_context_free(),
+ * even if the Rust side drops its `egui::Context` first.
+ * Every setup function returns EGUI_CHINESE_FONT_OK or an error code;
+ * egui_chinese_font_last_error() describes the last failure on the calling
+ * thread and stays valid until the next call into the library on that thread.
+ */
+
+#ifndef EGUI_CHINESE_FONT_H
+#define EGUI_CHINESE_FONT_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define EGUI_CHINESE_FONT_OK 0
+#define EGUI_CHINESE_FONT_NOT_FOUND 1
+#define EGUI_CHINESE_FONT_READ_ERROR 2
+#define EGUI_CHINESE_FONT_INVALID_FONT 3
+#define EGUI_CHINESE_FONT_UNSUPPORTED_PLATFORM 4
+#define EGUI_CHINESE_FONT_OTHER_ERROR 5
+#define EGUI_CHINESE_FONT_INVALID_ARGUMENT (-1)
+#define EGUI_CHINESE_FONT_PANIC (-2)
+
+/* Opaque handle to an egui context */
+typedef struct EguiChineseFontContext EguiChineseFontContext;
+
+/* Free a context handle; does nothing for NULL */
+void egui_chinese_font_context_free(EguiChineseFontContext *ctx);
+
+/* Find an installed Chinese font and register it with the context */
+int egui_chinese_font_setup(const EguiChineseFontContext *ctx);
+
+/* Register face `face_index` of the font file at `path` (UTF-8) with the context */
+int egui_chinese_font_setup_from_path(const EguiChineseFontContext *ctx, const char *path, uint32_t face_index);
+
+/* Description of the last failure on this thread, or NULL */
+const char *egui_chinese_font_last_error(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* EGUI_CHINESE_FONT_H */
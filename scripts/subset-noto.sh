@@ -0,0 +1,35 @@
+#!/usr/bin/env sh
+# Regenerate fonts/NotoSansSC-Subset.otf for the `embedded-noto` and `bundled`
+# features, and copy the font's license to fonts/OFL.txt.
+#
+# Requires curl and fonttools (`pip install fonttools`).
+set -eu
+
+cd "$(dirname "$0")/.."
+
+# A release tag, so the subset can be regenerated byte for byte
+TAG="Sans2.004"
+URL="https://github.com/notofonts/noto-cjk/raw/$TAG/Sans/SubsetOTF/SC/NotoSansSC-Regular.otf"
+LICENSE_URL="https://github.com/notofonts/noto-cjk/raw/$TAG/Sans/LICENSE"
+TMP="$(mktemp -d)"
+trap 'rm -rf "$TMP"' EXIT
+
+curl -fL "$URL" -o "$TMP/NotoSansSC-Regular.otf"
+
+# The `download` feature pins the same file by its checksum
+EXPECTED="$(sed -n 's/^pub const NOTO_SANS_SC_SHA256: &str = "\(.*\)";$/\1/p' src/download.rs)"
+ACTUAL="$(sha256sum "$TMP/NotoSansSC-Regular.otf" | cut -d' ' -f1)"
+if [ "$ACTUAL" != "$EXPECTED" ]; then
+    echo "NotoSansSC-Regular.otf has checksum $ACTUAL, but NOTO_SANS_SC_SHA256 is $EXPECTED" >&2
+    exit 1
+fi
+
+curl -fL "$LICENSE_URL" -o fonts/OFL.txt
+
+# Keep Latin, CJK punctuation, the CJK Unified Ideographs block and full-width forms
+pyftsubset "$TMP/NotoSansSC-Regular.otf" \
+    --unicodes="U+0020-007E,U+00A0-00FF,U+2000-206F,U+3000-303F,U+4E00-9FFF,U+FF00-FFEF" \
+    --layout-features='*' \
+    --output-file=fonts/NotoSansSC-Subset.otf
+
+ls -l fonts/NotoSansSC-Subset.otf
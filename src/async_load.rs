@@ -0,0 +1,164 @@
+//! Non-blocking font loading
+//!
+//! Enabled with the `async` feature. The font is read on a background thread
+//! so large `.ttc` collections don't stall the first frame; the returned
+//! future works with any executor, or can be polled or joined without one.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context as TaskContext, Poll, Waker};
+
+use egui::Context;
+
+use crate::{load_chinese_font, FontConfig, FontError, LoadedFont};
+
+/// Future returned by [`setup_chinese_fonts_async`]
+///
+/// Loading starts immediately and completes even if the future is dropped;
+/// awaiting it only reports the outcome. Without an executor, check it from
+/// the UI loop with [`is_finished`](Self::is_finished) and
+/// [`try_result`](Self::try_result), or block on it with [`wait`](Self::wait).
+#[must_use = "the font loads regardless, but the result is only observable by awaiting this future"]
+pub struct FontLoadFuture {
+    shared: Arc<(Mutex<Shared>, Condvar)>,
+}
+
+#[derive(Default)]
+struct Shared {
+    result: Option<Result<LoadedFont, FontError>>,
+    /// Set once the font was applied or loading failed, even after the result was taken
+    finished: bool,
+    waker: Option<Waker>,
+}
+
+impl Future for FontLoadFuture {
+    type Output = Result<LoadedFont, FontError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Self::Output> {
+        let mut shared = self.lock();
+        match shared.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl FontLoadFuture {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Shared> {
+        self.shared.0.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Whether loading has finished, successfully or not
+    ///
+    /// Once this is `true`, the font is registered with the context.
+    pub fn is_finished(&self) -> bool {
+        self.lock().finished
+    }
+
+    /// Take the result if loading has finished, without blocking
+    ///
+    /// Returns `None` while loading, and after the result was taken.
+    pub fn try_result(&mut self) -> Option<Result<LoadedFont, FontError>> {
+        self.lock().result.take()
+    }
+
+    /// Block the calling thread until loading has finished
+    ///
+    /// # Panics
+    /// If the result was already taken with [`try_result`](Self::try_result).
+    pub fn wait(self) -> Result<LoadedFont, FontError> {
+        let (lock, finished) = &*self.shared;
+        let shared = lock.lock().unwrap_or_else(|err| err.into_inner());
+        let mut shared = finished
+            .wait_while(shared, |shared| !shared.finished)
+            .unwrap_or_else(|err| err.into_inner());
+        shared.result.take().expect("font loading result was already taken")
+    }
+}
+
+/// Setup Chinese fonts for egui context without blocking the calling thread
+///
+/// The font is read on a background thread and applied with
+/// [`Context::set_fonts`] once ready; until then the context keeps rendering
+/// with its current fonts. A repaint is requested when the font arrives.
+///
+/// Frames rendered before that show Chinese text as empty boxes. egui only
+/// picks up new fonts at the start of a frame, so the frame that is running
+/// when loading finishes still shows boxes; the requested repaint runs the
+/// next frame, which draws the text correctly.
+///
+/// # Arguments
+/// * `ctx` - The egui context to configure
+///
+/// # Returns
+/// A [`FontLoadFuture`] resolving to the loaded font, or the
+/// [`FontError`] that prevented loading.
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::setup_chinese_fonts_async;
+///
+/// # let path = std::env::temp_dir().join("egui-chinese-font-async-example.ttf");
+/// # std::fs::write(&path, &*egui::FontDefinitions::default().font_data["Ubuntu-Light"].font)?;
+/// # std::env::set_var(egui_chinese_font::FONT_PATH_ENV, &path);
+/// let ctx = egui::Context::default();
+/// let loading = setup_chinese_fonts_async(&ctx);
+///
+/// // In a real app the UI keeps running meanwhile; here, wait for the font
+/// let font = loading.wait()?;
+/// assert_eq!(font.name(), "chinese");
+///
+/// // The font arrived with a repaint request, and the next frame uses it
+/// assert!(ctx.has_requested_repaint());
+/// let _ = ctx.run(Default::default(), |_| {});
+/// assert!(ctx.fonts(|fonts| fonts.definitions().font_data.contains_key("chinese")));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn setup_chinese_fonts_async(ctx: &Context) -> FontLoadFuture {
+    setup_chinese_fonts_async_with_config(ctx, &FontConfig::default())
+}
+
+/// Setup Chinese fonts for egui context using a custom [`FontConfig`], without blocking
+///
+/// See [`setup_chinese_fonts_async`].
+pub fn setup_chinese_fonts_async_with_config(ctx: &Context, config: &FontConfig) -> FontLoadFuture {
+    spawn_setup(ctx, config, |ctx, config| {
+        load_chinese_font(config.variant().resolve(), config).map(|font_data| crate::register_chinese_font(ctx, font_data, config))
+    })
+}
+
+/// Run `setup` on a background thread, requesting a repaint once it registered a font
+pub(crate) fn spawn_setup(
+    ctx: &Context,
+    config: &FontConfig,
+    setup: impl FnOnce(&Context, &FontConfig) -> Result<LoadedFont, FontError> + Send + 'static,
+) -> FontLoadFuture {
+    let shared = Arc::new((Mutex::new(Shared::default()), Condvar::new()));
+
+    let ctx = ctx.clone();
+    let config = config.clone();
+    let thread_shared = Arc::clone(&shared);
+    std::thread::spawn(move || {
+        let result = setup(&ctx, &config);
+        if result.is_ok() {
+            ctx.request_repaint();
+        }
+        config.notify_loaded(result.as_ref());
+
+        let (lock, finished) = &*thread_shared;
+        let mut shared = lock.lock().unwrap_or_else(|err| err.into_inner());
+        shared.result = Some(result);
+        shared.finished = true;
+        finished.notify_all();
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+    });
+
+    FontLoadFuture { shared }
+}
@@ -0,0 +1,48 @@
+//! Compressed Noto Sans SC fallback compiled into the binary
+//!
+//! Enabled with the `bundled` feature.
+
+use std::io::Read;
+use std::sync::OnceLock;
+
+use egui::FontData;
+use flate2::read::GzDecoder;
+
+use crate::platform::FoundFont;
+use crate::FontError;
+
+/// Gzip-compressed subset of Noto Sans SC, compressed by the build script; see `fonts/README.md`
+static COMPRESSED: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/NotoSansSC-Subset.otf.gz"));
+
+/// The decompressed font, kept for the lifetime of the program once needed
+static DECOMPRESSED: OnceLock<Vec<u8>> = OnceLock::new();
+
+/// The bundled font, decompressing it on first use
+pub(crate) fn font_data() -> Result<FontData, FontError> {
+    if let Some(font) = DECOMPRESSED.get() {
+        return Ok(FontData::from_static(font));
+    }
+
+    let mut font = Vec::new();
+    GzDecoder::new(COMPRESSED)
+        .read_to_end(&mut font)
+        .map_err(|err| FontError::InvalidFont {
+            path: None,
+            reason: format!("bundled font could not be decompressed ({})", err),
+        })?;
+    crate::faces::validate_font(&font, 0).map_err(|reason| FontError::InvalidFont { path: None, reason })?;
+
+    Ok(FontData::from_static(DECOMPRESSED.get_or_init(|| font)))
+}
+
+/// Use the bundled font when discovery found nothing, or only the last-resort font without Chinese glyphs
+pub(crate) fn or_bundled(result: Result<FoundFont, FontError>) -> Result<FoundFont, FontError> {
+    match &result {
+        Ok(found) if crate::coverage::check_candidate(&found.font_data.font, found.font_data.index, "").is_ok() => result,
+        Ok(_) | Err(FontError::NotFound { .. } | FontError::UnsupportedPlatform) => {
+            log!(info, "no installed font covers Chinese, using the bundled Noto Sans SC");
+            font_data().map(|font_data| FoundFont::new(font_data, None))
+        }
+        Err(_) => result,
+    }
+}
@@ -0,0 +1,400 @@
+//! Process-wide cache of loaded font files
+//!
+//! Setting up fonts again, e.g. for a second window or after a hot reload,
+//! reuses the bytes of files that were loaded successfully before instead of
+//! reading them from disk again. An entry is only used while the file's size
+//! and modification time are unchanged, like the path cache does.
+//!
+//! With the `mmap` feature, files are memory-mapped instead of read, so only
+//! the pages egui actually touches are loaded into memory.
+
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+#[cfg(feature = "timeout")]
+use std::time::Duration;
+use std::time::{Instant, SystemTime};
+
+use egui::FontData;
+
+use crate::{ChineseVariant, FontConfig, FontError, FontStorage};
+
+/// Bytes of successfully loaded font files, keyed by canonical path
+///
+/// Each hit hands egui its own copy, so forgetting an entry frees its memory
+/// once the contexts using the font drop it.
+static FONT_CACHE: OnceLock<Mutex<HashMap<PathBuf, CachedFile>>> = OnceLock::new();
+
+/// Memory-mapped font files, keyed by canonical path
+///
+/// `FontData` can only borrow `'static` bytes, so a mapping has to outlive
+/// every context that may use it and is never unmapped. Mappings are kept
+/// across [`clear_font_cache`] and reused while the file is unchanged, so
+/// reloading maps each version of a file once; the mapped pages belong to
+/// the page cache rather than the heap, and the kernel can reclaim them.
+#[cfg(feature = "mmap")]
+static MAPPINGS: OnceLock<Mutex<HashMap<PathBuf, Mapping>>> = OnceLock::new();
+
+/// Reads a font file given its path, in place of the filesystem
+pub(crate) type Reader = dyn Fn(&str) -> std::io::Result<Vec<u8>> + Send + Sync;
+
+/// The size and modification time of a file, to notice when it was replaced
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileVersion {
+    size: u64,
+    modified: Option<SystemTime>,
+}
+
+impl FileVersion {
+    fn of(path: &Path) -> std::io::Result<Self> {
+        let metadata = std::fs::metadata(path)?;
+        Ok(FileVersion {
+            size: metadata.len(),
+            modified: metadata.modified().ok(),
+        })
+    }
+}
+
+/// The bytes of a cached font file
+struct CachedFile {
+    bytes: Arc<[u8]>,
+    version: FileVersion,
+}
+
+/// A memory-mapped font file
+#[cfg(feature = "mmap")]
+#[derive(Clone, Copy)]
+struct Mapping {
+    bytes: &'static [u8],
+    version: FileVersion,
+}
+
+thread_local! {
+    /// Set while loading a font whose file shouldn't be kept in the cache
+    static SKIP_STORE: Cell<bool> = const { Cell::new(false) };
+    /// Replaces the filesystem, see [`with_reader`]
+    static READER: RefCell<Option<Arc<Reader>>> = const { RefCell::new(None) };
+    /// How long reading a file may take, see [`with_read_timeout`]
+    #[cfg(feature = "timeout")]
+    static READ_TIMEOUT: Cell<Option<Duration>> = const { Cell::new(None) };
+}
+
+/// Fonts found by [`load_chinese_font_shared`], per variant
+static SHARED_FONTS: OnceLock<Mutex<HashMap<ChineseVariant, Arc<FontData>>>> = OnceLock::new();
+
+fn cache() -> MutexGuard<'static, HashMap<PathBuf, CachedFile>> {
+    FONT_CACHE
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+}
+
+#[cfg(feature = "mmap")]
+fn mappings() -> MutexGuard<'static, HashMap<PathBuf, Mapping>> {
+    MAPPINGS.get_or_init(Default::default).lock().unwrap_or_else(|err| err.into_inner())
+}
+
+fn shared_fonts() -> MutexGuard<'static, HashMap<ChineseVariant, Arc<FontData>>> {
+    SHARED_FONTS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+}
+
+/// Find the Chinese font once per process and share it
+///
+/// The first call searches like [`setup_chinese_fonts`](crate::setup_chinese_fonts)
+/// and applies the recommended tweak; later calls for the same preferred
+/// variant return the same `Arc` without touching the disk. Register it with
+/// [`setup_custom_chinese_font_arc`](crate::setup_custom_chinese_font_arc) in
+/// every context, e.g. a main window and offscreen render contexts.
+///
+/// Safe to call from several threads; concurrent first calls search only
+/// once. [`clear_font_cache`] forgets the shared font, e.g. in tests or after
+/// fonts were installed. Errors are not cached.
+///
+/// # Example
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use egui_chinese_font::{clear_font_cache, load_chinese_font_shared, setup_custom_chinese_font_arc};
+///
+/// # let path = std::env::temp_dir().join("egui-chinese-font-shared-example.ttf");
+/// # std::fs::write(&path, &*egui::FontDefinitions::default().font_data["Ubuntu-Light"].font)?;
+/// # std::env::set_var(egui_chinese_font::FONT_PATH_ENV, &path);
+/// let font_data = load_chinese_font_shared()?;
+/// for ctx in [egui::Context::default(), egui::Context::default()] {
+///     setup_custom_chinese_font_arc(&ctx, Arc::clone(&font_data), None)?;
+/// }
+/// assert!(Arc::ptr_eq(&font_data, &load_chinese_font_shared()?));
+///
+/// clear_font_cache();
+/// assert!(!Arc::ptr_eq(&font_data, &load_chinese_font_shared()?));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn load_chinese_font_shared() -> Result<Arc<FontData>, FontError> {
+    let config = FontConfig::default();
+    let variant = config.variant.resolve();
+
+    // Holding the lock while searching makes concurrent first calls wait for one search
+    let mut shared = shared_fonts();
+    if let Some(font_data) = shared.get(&variant) {
+        return Ok(Arc::clone(font_data));
+    }
+    let found = crate::find_chinese_font_with_config(&config)?;
+    let font_data = Arc::new(config.configure(found.font_data));
+    shared.insert(variant, Arc::clone(&font_data));
+    Ok(font_data)
+}
+
+/// Read the font file at `path`, copying the cached bytes if it was loaded before and is unchanged
+///
+/// Inside [`with_read_timeout`], fails with [`std::io::ErrorKind::TimedOut`]
+/// if reading takes too long.
+pub(crate) fn read(path: &Path) -> std::io::Result<Cow<'static, [u8]>> {
+    if let Some(reader) = READER.with(|reader| reader.borrow().clone()) {
+        return reader(&path.to_string_lossy()).map(Cow::Owned);
+    }
+    #[cfg(feature = "timeout")]
+    if let Some(timeout) = read_timeout() {
+        return read_with_timeout(path, timeout);
+    }
+    read_now(path)
+}
+
+/// Run `load`, reading font files with `reader` instead of from the filesystem
+///
+/// Files read this way bypass the cache in both directions.
+pub(crate) fn with_reader<T>(reader: Arc<Reader>, load: impl FnOnce() -> T) -> T {
+    struct Restore(Option<Arc<Reader>>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            READER.with(|reader| *reader.borrow_mut() = self.0.take());
+        }
+    }
+
+    let _restore = Restore(READER.with(|current| current.borrow_mut().replace(reader)));
+    without_storing(load)
+}
+
+/// Run `load`, giving up on every file read that takes longer than `timeout`
+#[cfg(feature = "timeout")]
+pub(crate) fn with_read_timeout<T>(timeout: Duration, load: impl FnOnce() -> T) -> T {
+    struct Restore(Option<Duration>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            READ_TIMEOUT.with(|limit| limit.set(self.0));
+        }
+    }
+
+    let _restore = Restore(READ_TIMEOUT.with(|limit| limit.replace(Some(timeout))));
+    load()
+}
+
+/// The timeout set by [`with_read_timeout`] on this thread
+#[cfg(feature = "timeout")]
+pub(crate) fn read_timeout() -> Option<Duration> {
+    READ_TIMEOUT.with(Cell::get)
+}
+
+/// Read the file on a helper thread that is abandoned if it takes longer than `timeout`
+#[cfg(feature = "timeout")]
+fn read_with_timeout(path: &Path, timeout: Duration) -> std::io::Result<Cow<'static, [u8]>> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let thread_path = path.to_path_buf();
+    std::thread::spawn(move || {
+        let _ = sender.send(read_now(&thread_path));
+    });
+
+    receiver.recv_timeout(timeout).unwrap_or_else(|err| {
+        log!(warn, "{}: gave up reading after {:?}", path.display(), timeout);
+        let kind = match err {
+            std::sync::mpsc::RecvTimeoutError::Timeout => std::io::ErrorKind::TimedOut,
+            std::sync::mpsc::RecvTimeoutError::Disconnected => std::io::ErrorKind::Other,
+        };
+        Err(std::io::Error::new(kind, format!("reading {} did not finish", path.display())))
+    })
+}
+
+fn read_now(path: &Path) -> std::io::Result<Cow<'static, [u8]>> {
+    let key = std::fs::canonicalize(path)?;
+    let version = FileVersion::of(&key)?;
+    {
+        let mut cache = cache();
+        match cache.get(&key) {
+            Some(cached) if cached.version == version => {
+                log!(debug, "{}: using cached font data", path.display());
+                return Ok(Cow::Owned(cached.bytes.to_vec()));
+            }
+            Some(_) => {
+                log!(debug, "{}: changed on disk, reading it again", path.display());
+                cache.remove(&key);
+            }
+            None => {}
+        }
+    }
+
+    #[cfg(feature = "mmap")]
+    {
+        if let Some(mapping) = mappings().get(&key).filter(|mapping| mapping.version == version) {
+            log!(debug, "{}: using the existing mapping", path.display());
+            return Ok(Cow::Borrowed(mapping.bytes));
+        }
+        match map(path) {
+            Ok(bytes) => {
+                // Another thread may have mapped the file meanwhile; keep its mapping
+                let mut mappings = mappings();
+                let mapping = mappings.entry(key).or_insert(Mapping { bytes, version });
+                if mapping.version != version {
+                    *mapping = Mapping { bytes, version };
+                }
+                return Ok(Cow::Borrowed(mapping.bytes));
+            }
+            Err(err) => log!(debug, "{}: can't be memory-mapped ({}), reading it instead", path.display(), err),
+        }
+    }
+
+    let start = Instant::now();
+    let font_data = std::fs::read(path)?;
+    crate::stats::record_read(path, start.elapsed(), font_data.len());
+    Ok(Cow::Owned(font_data))
+}
+
+/// Memory-map the file at `path` for the rest of the process, see [`MAPPINGS`]
+#[cfg(feature = "mmap")]
+fn map(path: &Path) -> std::io::Result<&'static [u8]> {
+    let file = std::fs::File::open(path)?;
+    let start = Instant::now();
+    // SAFETY: the mapping is only unsound if the file is truncated or modified
+    // while mapped. Installed fonts are replaced rather than written in place
+    // by package managers, which keeps the old file's pages valid; the `mmap`
+    // feature documents the remaining risk.
+    let mapping = unsafe { memmap2::Mmap::map(&file)? };
+    crate::stats::record_read(path, start.elapsed(), mapping.len());
+
+    let mapping: &'static memmap2::Mmap = Box::leak(Box::new(mapping));
+    Ok(mapping)
+}
+
+/// Run `load` without adding the files it reads to the cache
+///
+/// Files cached before are still reused.
+pub(crate) fn without_storing<T>(load: impl FnOnce() -> T) -> T {
+    struct Restore(bool);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SKIP_STORE.with(|skip| skip.set(self.0));
+        }
+    }
+
+    let _restore = Restore(SKIP_STORE.with(|skip| skip.replace(true)));
+    load()
+}
+
+/// Cache `font_data` as the contents of `path`
+///
+/// Only call this for fonts that passed validation.
+pub(crate) fn store(path: &Path, font_data: FontData) -> FontData {
+    if SKIP_STORE.with(Cell::get) {
+        return font_data;
+    }
+    // Mapped or static data needs no caching, and cached bytes are only ever copied out
+    let Cow::Owned(bytes) = &font_data.font else {
+        return font_data;
+    };
+    let Ok(key) = std::fs::canonicalize(path) else {
+        return font_data;
+    };
+    let Ok(version) = FileVersion::of(&key) else {
+        return font_data;
+    };
+
+    cache().insert(key, CachedFile {
+        bytes: Arc::from(bytes.as_slice()),
+        version,
+    });
+    font_data
+}
+
+/// How the bytes of `font_data` are held in memory
+pub(crate) fn storage(font_data: &FontData) -> FontStorage {
+    let Cow::Borrowed(_bytes) = font_data.font else {
+        return FontStorage::Heap;
+    };
+    #[cfg(feature = "mmap")]
+    if mappings().values().any(|mapping| mapping.bytes.as_ptr() == _bytes.as_ptr()) {
+        return FontStorage::Mapped;
+    }
+    FontStorage::Static
+}
+
+/// Forget all cached font files
+///
+/// Later setup calls read the font files from disk again, and
+/// [`load_chinese_font_shared`] searches again, e.g. after fonts were
+/// installed, or to make tests independent of each other. A font file that
+/// changed on disk is read again even without clearing the cache. The memory
+/// of forgotten files is freed once no context uses them anymore.
+///
+/// With the `mmap` feature, mapped files stay mapped, since egui may still
+/// borrow them; an unchanged file reuses its mapping when loaded again.
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::{clear_font_cache, setup_chinese_fonts};
+///
+/// // E.g. from a "reload fonts" menu entry, after the user installed a font
+/// clear_font_cache();
+/// let ctx = egui::Context::default();
+/// let _ = setup_chinese_fonts(&ctx);
+/// ```
+pub fn clear_font_cache() {
+    cache().clear();
+    shared_fonts().clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_file(name: &str, contents: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("egui-chinese-font-cache-test-{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn cached_files_are_copied_out() {
+        let path = temp_file("copied", b"font bytes");
+        let font_data = store(&path, FontData::from_owned(read(&path).unwrap().into_owned()));
+        assert!(matches!(font_data.font, Cow::Owned(_)));
+
+        let key = std::fs::canonicalize(&path).unwrap();
+        assert!(cache().contains_key(&key));
+        let again = read(&path).unwrap();
+        #[cfg(not(feature = "mmap"))]
+        assert!(matches!(again, Cow::Owned(_)));
+        assert_eq!(&*again, b"font bytes");
+    }
+
+    #[test]
+    fn changed_files_are_read_again() {
+        let path = temp_file("changed", b"old font");
+        store(&path, FontData::from_owned(read(&path).unwrap().into_owned()));
+
+        std::fs::write(&path, b"replaced font").unwrap();
+        assert_eq!(&*read(&path).unwrap(), b"replaced font");
+    }
+
+    #[test]
+    fn clearing_drops_the_entries() {
+        let path = temp_file("cleared", b"font bytes");
+        store(&path, FontData::from_owned(read(&path).unwrap().into_owned()));
+
+        clear_font_cache();
+        assert!(!cache().contains_key(&std::fs::canonicalize(&path).unwrap()));
+    }
+}
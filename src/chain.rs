@@ -0,0 +1,123 @@
+//! Registering several fonts as one ordered fallback chain
+
+use std::borrow::Cow;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use egui::{Context, FontData};
+
+use crate::{load_chinese_font, FontConfig, FontError, FontOrigin, LoadedFont, VariantPreference};
+
+/// A font to register with [`setup_font_chain`]
+#[derive(Debug, Clone)]
+pub enum FontSource {
+    /// A Chinese font discovered on the system, as by [`setup_chinese_fonts`](crate::setup_chinese_fonts)
+    SystemChinese,
+    /// A font file, registered under its file stem
+    Path(PathBuf),
+    /// Font data in memory, e.g. from `include_bytes!`, registered as `font-<position>`
+    Bytes(Cow<'static, [u8]>),
+}
+
+/// Outcome of [`setup_font_chain`]
+#[derive(Debug)]
+pub struct FontChain {
+    /// Registered fonts, highest priority first
+    pub loaded: Vec<LoadedFont>,
+    /// Entries that could not be loaded, by position in the requested chain
+    pub failed: Vec<(usize, FontError)>,
+}
+
+/// Register several fonts as an ordered fallback chain
+///
+/// Each source is loaded and registered under its own key ahead of the
+/// context's current fonts, in the given order, so egui tries the first font
+/// for every glyph and falls back to the next for glyphs it lacks. Entries
+/// that fail to load are skipped and reported; the rest are still applied.
+///
+/// # Arguments
+/// * `ctx` - The egui context to configure
+/// * `sources` - Fonts to register, highest priority first
+///
+/// # Returns
+/// * `Ok(FontChain)` with the registered fonts and any failed entries
+/// * `Err(FontError)` with the first entry's error if no font could be loaded
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::{setup_font_chain, FontSource};
+///
+/// # let latin_font = egui::FontDefinitions::default().font_data["Ubuntu-Light"].font.to_vec();
+/// let ctx = egui::Context::default();
+/// let chain = setup_font_chain(
+///     &ctx,
+///     &[
+///         FontSource::Bytes(latin_font.into()),
+///         FontSource::Path("fonts/NotoEmoji-Missing.ttf".into()),
+///     ],
+/// )?;
+///
+/// assert_eq!(chain.loaded[0].name(), "font-0");
+/// assert_eq!(chain.failed[0].0, 1);
+/// # Ok::<(), egui_chinese_font::FontError>(())
+/// ```
+pub fn setup_font_chain(ctx: &Context, sources: &[FontSource]) -> Result<FontChain, FontError> {
+    let mut loaded: Vec<LoadedFont> = Vec::new();
+    let mut failed = Vec::new();
+
+    for (position, source) in sources.iter().enumerate() {
+        match load_source(source, position) {
+            Ok((mut name, origin, font_data)) => {
+                if loaded.iter().any(|font| font.name() == name) {
+                    name = format!("{}-{}", name, position);
+                }
+                loaded.push(LoadedFont::new(name, origin, Arc::new(font_data)));
+            }
+            Err(err) => failed.push((position, err)),
+        }
+    }
+
+    if loaded.is_empty() && !failed.is_empty() {
+        return Err(failed.remove(0).1);
+    }
+
+    crate::apply_fonts(
+        ctx,
+        loaded
+            .iter()
+            .map(|font| (font.name().to_owned(), Arc::clone(font.font_data())))
+            .collect(),
+    );
+
+    Ok(FontChain { loaded, failed })
+}
+
+/// Load one chain entry, returning its key, origin and data
+fn load_source(source: &FontSource, position: usize) -> Result<(String, FontOrigin, FontData), FontError> {
+    match source {
+        FontSource::SystemChinese => {
+            let config = FontConfig::default();
+            let font_data = config.configure(load_chinese_font(VariantPreference::Auto.resolve(), &config)?);
+            Ok(("chinese".to_owned(), FontOrigin::System, font_data))
+        }
+        FontSource::Path(path) => {
+            let font_data = crate::load_font_from_path(path)?;
+            let name = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .map(str::to_owned)
+                .unwrap_or_else(|| format!("font-{}", position));
+            Ok((name, FontOrigin::File(path.clone()), font_data))
+        }
+        FontSource::Bytes(bytes) => {
+            crate::faces::validate_font(bytes, 0)
+                .map_err(|reason| FontError::InvalidFont { path: None, reason })?;
+            let font_data = match bytes {
+                Cow::Borrowed(bytes) => FontData::from_static(bytes),
+                Cow::Owned(bytes) => FontData::from_owned(bytes.clone()),
+            };
+            Ok((format!("font-{}", position), FontOrigin::Memory, font_data))
+        }
+    }
+}
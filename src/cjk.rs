@@ -0,0 +1,262 @@
+//! Chinese, Japanese and Korean font loading with language-specific priority
+
+use std::sync::Arc;
+
+use egui::{Context, FontData};
+
+use crate::{load_chinese_font, ChineseVariant, FontConfig, FontError};
+
+/// Japanese font candidates as `(path, face index)`, in priority order
+///
+/// The index is only used when no face of a collection is named for the language.
+#[cfg(target_os = "windows")]
+const JAPANESE_FONTS: &[(&str, u32)] = &[
+    (r"C:\Windows\Fonts\YuGothM.ttc", 0),  // Yu Gothic Medium
+    (r"C:\Windows\Fonts\YuGothR.ttc", 0),  // Yu Gothic Regular
+    (r"C:\Windows\Fonts\meiryo.ttc", 0),   // Meiryo
+    (r"C:\Windows\Fonts\msgothic.ttc", 0), // MS Gothic
+];
+
+#[cfg(target_os = "macos")]
+const JAPANESE_FONTS: &[(&str, u32)] = &[
+    ("/System/Library/Fonts/ヒラギノ角ゴシック W3.ttc", 0),  // Hiragino Sans
+    ("/System/Library/Fonts/ヒラギノ丸ゴ ProN W4.ttc", 0), // Hiragino Maru Gothic
+];
+
+#[cfg(target_os = "linux")]
+const JAPANESE_FONTS: &[(&str, u32)] = &[
+    // Noto Sans CJK collections start with the JP face
+    ("/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc", 0),
+    ("/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc", 0),
+    ("/usr/share/fonts/google-noto-cjk/NotoSansCJK-Regular.ttc", 0),
+    ("/usr/share/fonts/opentype/noto/NotoSansCJKjp-Regular.otf", 0),
+    ("/usr/share/fonts/noto-cjk/NotoSansCJKjp-Regular.otf", 0),
+    ("/usr/share/fonts/opentype/ipafont-gothic/ipag.ttf", 0),
+    ("/usr/share/fonts/truetype/takao-gothic/TakaoGothic.ttf", 0),
+];
+
+#[cfg(target_os = "android")]
+const JAPANESE_FONTS: &[(&str, u32)] = &[("/system/fonts/NotoSansCJK-Regular.ttc", 0)];
+
+#[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+const JAPANESE_FONTS: &[(&str, u32)] = &[
+    ("/usr/local/share/fonts/noto/NotoSansCJK-Regular.ttc", 0),
+    ("/usr/pkg/share/fonts/X11/TTF/NotoSansCJK-Regular.ttc", 0),
+];
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux", target_os = "android", target_os = "freebsd", target_os = "openbsd", target_os = "netbsd")))]
+const JAPANESE_FONTS: &[(&str, u32)] = &[];
+
+/// Korean font candidates as `(path, face index)`, in priority order
+#[cfg(target_os = "windows")]
+const KOREAN_FONTS: &[(&str, u32)] = &[
+    (r"C:\Windows\Fonts\malgun.ttf", 0), // Malgun Gothic
+    (r"C:\Windows\Fonts\gulim.ttc", 0),  // Gulim
+];
+
+#[cfg(target_os = "macos")]
+const KOREAN_FONTS: &[(&str, u32)] = &[
+    ("/System/Library/Fonts/AppleSDGothicNeo.ttc", 0), // Apple SD Gothic Neo
+    ("/System/Library/Fonts/Supplemental/AppleGothic.ttf", 0),
+];
+
+#[cfg(target_os = "linux")]
+const KOREAN_FONTS: &[(&str, u32)] = &[
+    // The KR face follows the JP face in Noto Sans CJK collections
+    ("/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc", 1),
+    ("/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc", 1),
+    ("/usr/share/fonts/google-noto-cjk/NotoSansCJK-Regular.ttc", 1),
+    ("/usr/share/fonts/opentype/noto/NotoSansCJKkr-Regular.otf", 0),
+    ("/usr/share/fonts/noto-cjk/NotoSansCJKkr-Regular.otf", 0),
+    ("/usr/share/fonts/truetype/nanum/NanumGothic.ttf", 0),
+    ("/usr/share/fonts/truetype/unfonts-core/UnDotum.ttf", 0),
+];
+
+#[cfg(target_os = "android")]
+const KOREAN_FONTS: &[(&str, u32)] = &[("/system/fonts/NotoSansCJK-Regular.ttc", 1)];
+
+#[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+const KOREAN_FONTS: &[(&str, u32)] = &[
+    ("/usr/local/share/fonts/noto/NotoSansCJK-Regular.ttc", 1),
+    ("/usr/pkg/share/fonts/X11/TTF/NotoSansCJK-Regular.ttc", 1),
+];
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux", target_os = "android", target_os = "freebsd", target_os = "openbsd", target_os = "netbsd")))]
+const KOREAN_FONTS: &[(&str, u32)] = &[];
+
+/// A CJK UI language
+///
+/// Han characters are shared between these languages but drawn with
+/// different glyph shapes, so the font for the selected language is
+/// registered first and the others only act as fallbacks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CjkLanguage {
+    /// Simplified Chinese
+    SimplifiedChinese,
+    /// Traditional Chinese
+    TraditionalChinese,
+    /// Japanese
+    Japanese,
+    /// Korean
+    Korean,
+}
+
+impl CjkLanguage {
+    /// Parse a locale identifier or BCP 47 language tag into a CJK language
+    ///
+    /// Chinese locales are split into Simplified and Traditional like
+    /// [`ChineseVariant::from_locale`]. Returns `None` for other languages.
+    ///
+    /// ```
+    /// use egui_chinese_font::CjkLanguage;
+    ///
+    /// assert_eq!(CjkLanguage::from_locale("zh-Hans"), Some(CjkLanguage::SimplifiedChinese));
+    /// assert_eq!(CjkLanguage::from_locale("zh_TW.UTF-8"), Some(CjkLanguage::TraditionalChinese));
+    /// assert_eq!(CjkLanguage::from_locale("ja"), Some(CjkLanguage::Japanese));
+    /// assert_eq!(CjkLanguage::from_locale("ja_JP.UTF-8"), Some(CjkLanguage::Japanese));
+    /// assert_eq!(CjkLanguage::from_locale("ko-KR"), Some(CjkLanguage::Korean));
+    /// assert_eq!(CjkLanguage::from_locale("en-US"), None);
+    /// ```
+    pub fn from_locale(locale: &str) -> Option<Self> {
+        let language = locale.split(['-', '_', '.', '@']).next().unwrap_or_default().to_ascii_lowercase();
+        match language.as_str() {
+            "ja" | "jpn" => Some(CjkLanguage::Japanese),
+            "ko" | "kor" => Some(CjkLanguage::Korean),
+            _ => ChineseVariant::from_locale(locale).map(CjkLanguage::from),
+        }
+    }
+
+    /// The BCP 47 language tag, e.g. for font database queries
+    ///
+    /// ```
+    /// use egui_chinese_font::CjkLanguage;
+    ///
+    /// assert_eq!(CjkLanguage::TraditionalChinese.tag(), "zh-Hant");
+    /// assert_eq!(CjkLanguage::from_locale(CjkLanguage::Korean.tag()), Some(CjkLanguage::Korean));
+    /// ```
+    pub fn tag(self) -> &'static str {
+        match self {
+            CjkLanguage::SimplifiedChinese => "zh-Hans",
+            CjkLanguage::TraditionalChinese => "zh-Hant",
+            CjkLanguage::Japanese => "ja",
+            CjkLanguage::Korean => "ko",
+        }
+    }
+}
+
+impl From<ChineseVariant> for CjkLanguage {
+    fn from(variant: ChineseVariant) -> Self {
+        match variant {
+            ChineseVariant::Simplified => CjkLanguage::SimplifiedChinese,
+            ChineseVariant::Traditional => CjkLanguage::TraditionalChinese,
+        }
+    }
+}
+
+/// Detect the CJK language of the user's preferred locale
+///
+/// Inspects the system's preferred locales in order and returns the first
+/// Chinese, Japanese or Korean one, or `None` if there is none.
+pub fn detect_cjk_language() -> Option<CjkLanguage> {
+    sys_locale::get_locales().find_map(|locale| CjkLanguage::from_locale(&locale))
+}
+
+/// Setup CJK fonts for egui context, preferring glyphs for `language`
+///
+/// Loads a separate font for Chinese, Japanese and Korean and registers them
+/// as `"chinese"`, `"japanese"` and `"korean"` at the front of the
+/// proportional and monospace families. The font for `language` comes first;
+/// the others follow in Chinese, Japanese, Korean order, so kana and Hangul
+/// missing from the primary font still render instead of showing boxes.
+///
+/// Scripts without an installed font are skipped.
+///
+/// # Arguments
+/// * `ctx` - The egui context to configure
+/// * `language` - The UI language whose glyph shapes should win
+///
+/// # Returns
+/// * `Ok(())` if the font for `language` was loaded
+/// * `Err(FontError)` if no font for `language` could be found
+pub fn setup_cjk_fonts(ctx: &Context, language: CjkLanguage) -> Result<(), FontError> {
+    setup_cjk_fonts_with_config(ctx, language, &FontConfig::default())
+}
+
+/// Setup CJK fonts for egui context using a custom [`FontConfig`]
+///
+/// The config's variant preference selects the fallback Chinese font when
+/// `language` is Japanese or Korean, and its tweak is applied to every font.
+///
+/// # Arguments
+/// * `ctx` - The egui context to configure
+/// * `language` - The UI language whose glyph shapes should win
+/// * `config` - Options controlling how the fonts are registered
+pub fn setup_cjk_fonts_with_config(
+    ctx: &Context,
+    language: CjkLanguage,
+    config: &FontConfig,
+) -> Result<(), FontError> {
+    let chinese_variant = match language {
+        CjkLanguage::SimplifiedChinese => ChineseVariant::Simplified,
+        CjkLanguage::TraditionalChinese => ChineseVariant::Traditional,
+        CjkLanguage::Japanese | CjkLanguage::Korean => config.variant().resolve(),
+    };
+
+    let order = match language {
+        CjkLanguage::SimplifiedChinese | CjkLanguage::TraditionalChinese => ["chinese", "japanese", "korean"],
+        CjkLanguage::Japanese => ["japanese", "chinese", "korean"],
+        CjkLanguage::Korean => ["korean", "chinese", "japanese"],
+    };
+
+    let mut fonts = Vec::new();
+    for (position, name) in order.into_iter().enumerate() {
+        let font_data = match name {
+            "chinese" => load_chinese_font(chinese_variant, config),
+            "japanese" => load_japanese_font(),
+            _ => load_korean_font(),
+        };
+
+        // The primary font is required; fallbacks are best effort
+        match font_data {
+            Ok(font_data) => {
+                let tweak = config.tweak_for(&font_data);
+                let name = if name == "chinese" { config.font_name() } else { name };
+                fonts.push((name.to_owned(), Arc::new(font_data.tweak(tweak))));
+            }
+            Err(err) if position == 0 => return Err(err),
+            Err(_) => {}
+        }
+    }
+
+    crate::apply_configured_fonts(ctx, fonts, config);
+
+    Ok(())
+}
+
+/// Load a Japanese font from the system
+fn load_japanese_font() -> Result<FontData, FontError> {
+    read_first_face(JAPANESE_FONTS, CjkLanguage::Japanese)
+        .ok_or_else(|| FontError::not_found("No Japanese font found"))
+}
+
+/// Load a Korean font from the system
+fn load_korean_font() -> Result<FontData, FontError> {
+    read_first_face(KOREAN_FONTS, CjkLanguage::Korean)
+        .ok_or_else(|| FontError::not_found("No Korean font found"))
+}
+
+/// Read the first existing, valid font face in `candidates`, preferring the face named for `language`
+fn read_first_face(candidates: &[(&str, u32)], language: CjkLanguage) -> Option<FontData> {
+    candidates.iter().find_map(|(path, index)| {
+        let font_data = crate::cache::read(path.as_ref()).ok()?;
+        let index = crate::faces::select_language_face(&font_data, language).unwrap_or(*index);
+        crate::faces::validate_font(&font_data, index).ok()?;
+        let font_data = FontData {
+            font: font_data,
+            index,
+            tweak: Default::default(),
+        };
+        Some(crate::cache::store(path.as_ref(), font_data))
+    })
+}
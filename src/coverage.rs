@@ -0,0 +1,173 @@
+//! Glyph coverage checks based on a font's character map
+
+use egui::FontData;
+use ttf_parser::Face;
+
+use crate::FontError;
+
+/// Which characters of a sample a font can display
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CoverageReport {
+    /// Number of distinct characters checked
+    pub checked: usize,
+    /// Characters without a glyph, in order of first appearance
+    pub missing: Vec<char>,
+}
+
+impl CoverageReport {
+    /// Whether every checked character has a glyph
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+
+    /// Number of checked characters that have a glyph
+    pub fn covered(&self) -> usize {
+        self.checked - self.missing.len()
+    }
+
+    /// Share of checked characters that have a glyph, from `0.0` to `1.0`
+    ///
+    /// An empty sample counts as fully covered.
+    pub fn ratio(&self) -> f32 {
+        if self.checked == 0 {
+            1.0
+        } else {
+            self.covered() as f32 / self.checked as f32
+        }
+    }
+
+    /// The missing characters formatted as `U+XXXX` codepoints, for logging
+    pub fn missing_codepoints(&self) -> Vec<String> {
+        self.missing.iter().map(|c| format!("U+{:04X}", *c as u32)).collect()
+    }
+}
+
+/// Report which characters of `sample` the font has glyphs for
+///
+/// Only the font's `cmap` is consulted; nothing is rasterized. Whitespace and
+/// control characters are ignored, and repeated characters are checked once.
+/// If the font data cannot be parsed, every character is reported missing.
+///
+/// # Arguments
+/// * `font_data` - The font to check, using its face index
+/// * `sample` - Characters the font is expected to display
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::font_covers;
+///
+/// # let font_data = egui::FontDefinitions::default().font_data["Ubuntu-Light"].as_ref().clone();
+/// let report = font_covers(&font_data, "Hello 你好");
+/// assert_eq!(report.checked, 6);
+/// assert_eq!(report.missing, ['你', '好']);
+/// assert_eq!(report.missing_codepoints(), ["U+4F60", "U+597D"]);
+/// ```
+pub fn font_covers(font_data: &FontData, sample: &str) -> CoverageReport {
+    let face = Face::parse(&font_data.font, font_data.index).ok();
+    report(face.as_ref(), sample)
+}
+
+/// Report which characters of `text` have no glyph in a font file
+///
+/// Like [`font_covers`], but takes raw font bytes (the first face of a
+/// collection) and fails instead of reporting everything missing when the
+/// data is not a font. Characters outside the Basic Multilingual Plane, such
+/// as CJK Extension B, are looked up through the font's format 12 `cmap`
+/// subtable.
+///
+/// # Arguments
+/// * `font_bytes` - Contents of a `.ttf`, `.otf` or `.ttc` file
+/// * `text` - Text the font is expected to display
+///
+/// # Returns
+/// * `Ok(CoverageReport)` listing the characters without a glyph
+/// * `Err(FontError::InvalidFont)` if `font_bytes` is not a valid font
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::coverage_report;
+///
+/// # let emoji_font = egui::FontDefinitions::default().font_data["NotoEmoji-Regular"].font.to_vec();
+/// // U+1F600 is found through the supplementary-plane cmap; U+20000 is not in this font
+/// let report = coverage_report(&emoji_font, "😀\t𠀀\n")?;
+/// assert_eq!(report.checked, 2);
+/// assert_eq!(report.missing, ['𠀀']);
+///
+/// assert!(coverage_report(&[0; 16], "你好").is_err());
+/// # Ok::<(), egui_chinese_font::FontError>(())
+/// ```
+pub fn coverage_report(font_bytes: &[u8], text: &str) -> Result<CoverageReport, FontError> {
+    let face = Face::parse(font_bytes, 0).map_err(|err| FontError::InvalidFont {
+        path: None,
+        reason: format!("not a valid font file ({})", err),
+    })?;
+
+    Ok(report(Some(&face), text))
+}
+
+/// Build a coverage report for `sample` against an already parsed face
+pub(crate) fn report(face: Option<&Face<'_>>, sample: &str) -> CoverageReport {
+    let mut checked = Vec::new();
+    let mut missing = Vec::new();
+
+    for c in sample.chars() {
+        if c.is_whitespace() || c.is_control() || checked.contains(&c) {
+            continue;
+        }
+        checked.push(c);
+
+        if face.and_then(|face| face.glyph_index(c)).is_none() {
+            missing.push(c);
+        }
+    }
+
+    CoverageReport {
+        checked: checked.len(),
+        missing,
+    }
+}
+
+/// Characters a font must display to count as a Chinese font
+///
+/// The most frequent Han characters, written the same in Simplified and
+/// Traditional Chinese, plus common CJK punctuation. Candidate fonts are
+/// checked against it before use; see [`font_has_chinese_coverage`](crate::font_has_chinese_coverage).
+pub const CHINESE_SAMPLE: &str = "的一是不了人我在有他中大上子和你地出也年得就要下以生自去之家可她小心多天而能好都然日起手，。、《》？";
+
+/// Minimum share of [`CHINESE_SAMPLE`] a font must cover to count as a Chinese font
+pub const MIN_CHINESE_COVERAGE: f32 = 0.95;
+
+/// Share of [`CHINESE_SAMPLE`] face `index` of `data` covers, for logging
+#[cfg(feature = "logging")]
+pub(crate) fn chinese_coverage(data: &[u8], index: u32) -> f32 {
+    report(Face::parse(data, index).ok().as_ref(), CHINESE_SAMPLE).ratio()
+}
+
+/// Check that the face covers common Chinese characters and every character in `required_chars`
+///
+/// Returns a human-readable reason on failure.
+pub(crate) fn check_candidate(data: &[u8], index: u32, required_chars: &str) -> Result<(), String> {
+    let face = Face::parse(data, index).map_err(|err| format!("not a valid font file ({})", err))?;
+
+    let chinese = report(Some(&face), CHINESE_SAMPLE);
+    if chinese.ratio() < MIN_CHINESE_COVERAGE {
+        return Err(format!(
+            "covers only {} of {} common Chinese characters",
+            chinese.covered(),
+            chinese.checked
+        ));
+    }
+
+    let required = report(Some(&face), required_chars);
+    if !required.is_complete() {
+        return Err(format!(
+            "missing required characters {}",
+            required.missing_codepoints().join(", ")
+        ));
+    }
+
+    Ok(())
+}
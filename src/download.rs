@@ -0,0 +1,362 @@
+//! Network download fallback for systems without a Chinese font
+//!
+//! Enabled with the `download` feature.
+
+use std::io::Read;
+use std::path::Path;
+
+use egui::{Context, FontData};
+use sha2::{Digest, Sha256};
+
+use crate::{faces, load_chinese_font, FontConfig, FontError, FontOrigin, LoadedFont};
+
+/// Noto Sans SC Regular from the `Sans2.004` release of the upstream `noto-cjk` repository
+///
+/// The release tag is the one `scripts/subset-noto.sh` uses, so the file
+/// doesn't change under a pinned checksum.
+pub const NOTO_SANS_SC_URL: &str =
+    "https://github.com/notofonts/noto-cjk/raw/Sans2.004/Sans/SubsetOTF/SC/NotoSansSC-Regular.otf";
+
+/// Hex-encoded SHA-256 checksum of the file at [`NOTO_SANS_SC_URL`]
+///
+/// `scripts/subset-noto.sh` downloads the same file and fails if it doesn't
+/// match this checksum.
+// TODO: confirm against the release file with `scripts/subset-noto.sh` before publishing
+pub const NOTO_SANS_SC_SHA256: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Largest font accepted from a download, well above the size of a complete CJK collection
+const MAX_DOWNLOAD_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Where to download a fallback font from, and how to verify it
+///
+/// The default downloads Noto Sans SC from [`NOTO_SANS_SC_URL`], verified
+/// against [`NOTO_SANS_SC_SHA256`].
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FontDownload {
+    url: String,
+    sha256: String,
+}
+
+impl FontDownload {
+    /// Download from `url`, expecting the given hex-encoded SHA-256 checksum
+    pub fn new(url: impl Into<String>, sha256: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            sha256: sha256.into().to_ascii_lowercase(),
+        }
+    }
+
+    /// The URL the font is downloaded from
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// The expected hex-encoded SHA-256 checksum
+    pub fn sha256(&self) -> &str {
+        &self.sha256
+    }
+
+    /// File name used inside the cache directory
+    ///
+    /// The checksum is part of the name, so downloads of different files
+    /// that share a name don't replace each other.
+    fn file_name(&self) -> String {
+        let path = self.url.split(['?', '#']).next().unwrap_or_default();
+        let name = path.rsplit('/').next().unwrap_or_default();
+        if name.is_empty() {
+            format!("{}.font", self.sha256)
+        } else {
+            format!("{}-{}", self.sha256, name)
+        }
+    }
+}
+
+impl Default for FontDownload {
+    fn default() -> Self {
+        Self::new(NOTO_SANS_SC_URL, NOTO_SANS_SC_SHA256)
+    }
+}
+
+/// Setup Chinese fonts, downloading a fallback font if none is installed
+///
+/// System fonts are tried first, like [`setup_chinese_fonts`](crate::setup_chinese_fonts)
+/// does. Only if no installed font covers Chinese is Noto Sans SC (see
+/// [`FontDownload::default`]) loaded from `cache_dir`, or downloaded into it on first use;
+/// other discovery errors, such as a broken [`FONT_PATH_ENV`](crate::FONT_PATH_ENV)
+/// override, are returned as they are. The file's SHA-256 checksum is verified
+/// both after downloading and when loading from the cache, where a file that
+/// fails verification is downloaded again.
+///
+/// # Arguments
+/// * `ctx` - The egui context to configure
+/// * `cache_dir` - Directory used to store the downloaded font
+///
+/// # Returns
+/// * `Ok(LoadedFont)` describing the font and where it came from
+/// * `Err(FontError::Download)` if the font could not be downloaded, or is
+///   larger than any font should be
+/// * `Err(FontError::ChecksumMismatch)` if the downloaded font failed verification
+/// * `Err(FontError::InvalidFont)` if the downloaded file is not a usable font
+/// * `Err(FontError::CacheDir)` if the cache directory could not be written
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use egui_chinese_font::setup_chinese_fonts_or_download;
+///
+/// let ctx = egui::Context::default();
+/// let cache_dir = std::env::temp_dir().join("my-app-fonts");
+/// if let Err(err) = setup_chinese_fonts_or_download(&ctx, &cache_dir) {
+///     eprintln!("Chinese text may not display: {}", err);
+/// }
+/// ```
+pub fn setup_chinese_fonts_or_download(ctx: &Context, cache_dir: &Path) -> Result<LoadedFont, FontError> {
+    setup_chinese_fonts_or_download_with_config(ctx, cache_dir, &FontDownload::default(), &FontConfig::default())
+}
+
+/// Setup Chinese fonts using a custom [`FontConfig`], downloading the font described by `download` if none is installed
+///
+/// See [`setup_chinese_fonts_or_download`]. The configuration applies to the
+/// downloaded font as well as to installed ones.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use egui_chinese_font::{setup_chinese_fonts_or_download_with_config, FontConfig, FontDownload, VariantPreference};
+///
+/// let ctx = egui::Context::default();
+/// let cache_dir = std::env::temp_dir().join("my-app-fonts");
+///
+/// // Traditional Chinese, from a mirror the application trusts
+/// let download = FontDownload::new(
+///     "https://fonts.example.com/NotoSansTC-Regular.otf",
+///     "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+/// );
+/// let config = FontConfig::new().with_variant(VariantPreference::Traditional);
+/// let loaded = setup_chinese_fonts_or_download_with_config(&ctx, &cache_dir, &download, &config)?;
+/// println!("using {:?}", loaded.origin());
+/// # Ok::<(), egui_chinese_font::FontError>(())
+/// ```
+pub fn setup_chinese_fonts_or_download_with_config(
+    ctx: &Context,
+    cache_dir: &Path,
+    download: &FontDownload,
+    config: &FontConfig,
+) -> Result<LoadedFont, FontError> {
+    let result = load_or_download(ctx, cache_dir, download, config);
+    config.notify_loaded(result.as_ref());
+    result
+}
+
+/// Setup Chinese fonts without blocking the calling thread, downloading a fallback font if none is installed
+///
+/// Enabled with the `async` feature alongside `download`. Searching, reading
+/// and downloading happen on a background thread like
+/// [`setup_chinese_fonts_async`](crate::setup_chinese_fonts_async) does; see
+/// [`setup_chinese_fonts_or_download`] for where the font comes from.
+///
+/// # Returns
+/// A [`FontLoadFuture`](crate::FontLoadFuture) resolving to the loaded font,
+/// or the [`FontError`] that prevented loading.
+#[cfg(feature = "async")]
+pub fn setup_chinese_fonts_or_download_async(ctx: &Context, cache_dir: &Path) -> crate::FontLoadFuture {
+    let cache_dir = cache_dir.to_path_buf();
+    let download = FontDownload::default();
+    crate::async_load::spawn_setup(ctx, &FontConfig::default(), move |ctx, config| {
+        load_or_download(ctx, &cache_dir, &download, config)
+    })
+}
+
+/// Register an installed Chinese font, or else the cached or downloaded one
+fn load_or_download(
+    ctx: &Context,
+    cache_dir: &Path,
+    download: &FontDownload,
+    config: &FontConfig,
+) -> Result<LoadedFont, FontError> {
+    match load_chinese_font(config.variant().resolve(), config) {
+        // A last-resort font without Chinese glyphs is what the download replaces
+        Ok(font_data) if crate::coverage::check_candidate(&font_data.font, font_data.index, "").is_ok() => {
+            return Ok(crate::register_chinese_font(ctx, font_data, config));
+        }
+        Ok(_) => log!(info, "no installed font covers Chinese, using {}", download.url),
+        Err(FontError::NotFound { .. } | FontError::UnsupportedPlatform) => {
+            log!(info, "no Chinese font installed, using {}", download.url)
+        }
+        Err(err) => return Err(err),
+    }
+
+    let path = cache_dir.join(download.file_name());
+    let (bytes, downloaded) = match std::fs::read(&path) {
+        Ok(bytes) if sha256_hex(&bytes) == download.sha256 => (bytes, false),
+        _ => {
+            let bytes = fetch(download)?;
+            store(cache_dir, &path, &bytes)?;
+            (bytes, true)
+        }
+    };
+
+    let font_data = validated_font_data(bytes, &path, config)?;
+    let origin = if downloaded { FontOrigin::Downloaded(path) } else { FontOrigin::Cached(path) };
+    Ok(crate::register_chinese_font(ctx, font_data, config).with_origin(origin))
+}
+
+/// Check that downloaded bytes hold a usable face, selecting it like installed fonts
+fn validated_font_data(bytes: Vec<u8>, path: &Path, config: &FontConfig) -> Result<FontData, FontError> {
+    let index = config.face_index.unwrap_or_else(|| faces::select_face_index(&bytes, config.variant().resolve()));
+    faces::validate_font(&bytes, index).map_err(|reason| FontError::InvalidFont {
+        path: Some(path.to_path_buf()),
+        reason,
+    })?;
+    Ok(FontData {
+        index,
+        ..FontData::from_owned(bytes)
+    })
+}
+
+/// Download the font and verify its checksum
+fn fetch(download: &FontDownload) -> Result<Vec<u8>, FontError> {
+    let response = ureq::get(&download.url)
+        .call()
+        .map_err(|err| FontError::Download(err.to_string()))?;
+
+    let bytes = read_limited(response.into_reader(), MAX_DOWNLOAD_SIZE)?;
+    let actual = sha256_hex(&bytes);
+    if actual != download.sha256 {
+        return Err(FontError::ChecksumMismatch {
+            expected: download.sha256.clone(),
+            actual,
+        });
+    }
+
+    Ok(bytes)
+}
+
+/// Read a download of at most `limit` bytes
+fn read_limited(reader: impl Read, limit: u64) -> Result<Vec<u8>, FontError> {
+    let mut bytes = Vec::new();
+    reader
+        .take(limit + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|err| FontError::Download(err.to_string()))?;
+    if bytes.len() as u64 > limit {
+        return Err(FontError::Download(format!("download is larger than {} bytes", limit)));
+    }
+    Ok(bytes)
+}
+
+/// Write the font into the cache directory
+fn store(cache_dir: &Path, path: &Path, bytes: &[u8]) -> Result<(), FontError> {
+    std::fs::create_dir_all(cache_dir).map_err(FontError::CacheDir)?;
+
+    // Write to a temporary file first so an interrupted write never leaves a truncated font behind
+    let tmp = path.with_extension("part");
+    std::fs::write(&tmp, bytes).map_err(FontError::CacheDir)?;
+    std::fs::rename(&tmp, path).map_err(FontError::CacheDir)
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{chinese_font, MockFontProvider};
+
+    /// Serve `body` over HTTP on a local port, returning its URL
+    fn serve(body: Vec<u8>) -> String {
+        use std::io::Write;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/NotoSansSC-Regular.otf", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    match stream.read(&mut buf) {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let _ = write!(stream, "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len());
+                let _ = stream.write_all(&body);
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn downloads_once_then_loads_from_the_cache() {
+        let font = chinese_font();
+        let url = serve(font.clone());
+        let dir = std::env::temp_dir().join(format!("egui-chinese-font-download-test-{}", std::process::id()));
+        let cache_dir = dir.join("cache");
+        let _ = std::fs::remove_dir_all(&dir);
+        // An installed font without Chinese glyphs, which the download replaces
+        let config = FontConfig::new().with_provider(Box::new(MockFontProvider::new()));
+        let ctx = Context::default();
+
+        // A download that doesn't match its checksum is rejected and not cached
+        let wrong = FontDownload::new(&url, "0".repeat(64));
+        let result = setup_chinese_fonts_or_download_with_config(&ctx, &cache_dir, &wrong, &config);
+        assert!(matches!(result, Err(FontError::ChecksumMismatch { actual, .. }) if actual == sha256_hex(&font)));
+        assert!(!cache_dir.exists());
+
+        let download = FontDownload::new(&url, sha256_hex(&font));
+        let loaded = setup_chinese_fonts_or_download_with_config(&ctx, &cache_dir, &download, &config).unwrap();
+        assert!(matches!(loaded.origin(), FontOrigin::Downloaded(path) if path.starts_with(&cache_dir)));
+        let loaded = setup_chinese_fonts_or_download_with_config(&ctx, &cache_dir, &download, &config).unwrap();
+        assert!(matches!(loaded.origin(), FontOrigin::Cached(path) if path.starts_with(&cache_dir)));
+
+        // A cached file that fails verification is downloaded again
+        let path = cache_dir.join(download.file_name());
+        std::fs::write(&path, b"truncated").unwrap();
+        let loaded = setup_chinese_fonts_or_download_with_config(&ctx, &cache_dir, &download, &config).unwrap();
+        assert!(matches!(loaded.origin(), FontOrigin::Downloaded(_)));
+        assert_eq!(std::fs::read(&path).unwrap(), font);
+
+        // A cache directory that can't be created is reported
+        let not_a_dir = dir.join("file");
+        std::fs::write(&not_a_dir, b"").unwrap();
+        let result = setup_chinese_fonts_or_download_with_config(&ctx, &not_a_dir, &download, &config);
+        assert!(matches!(result, Err(FontError::CacheDir(_))));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn installed_fonts_covering_chinese_skip_the_download() {
+        let (path, config) = crate::testing::chinese_font_config("download-installed-test");
+        let cache_dir = std::env::temp_dir().join(format!("egui-chinese-font-unused-cache-{}", std::process::id()));
+        // Never contacted
+        let download = FontDownload::new("http://127.0.0.1:9/font.otf", "0".repeat(64));
+
+        let ctx = Context::default();
+        let loaded = setup_chinese_fonts_or_download_with_config(&ctx, &cache_dir, &download, &config).unwrap();
+        assert!(matches!(loaded.origin(), FontOrigin::System));
+        assert!(!cache_dir.exists());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn file_names_hold_the_checksum() {
+        let sha256 = "ab".repeat(32);
+        let download = FontDownload::new("https://example.com/fonts/Font.otf?raw=true#top", &sha256);
+        assert_eq!(download.file_name(), format!("{}-Font.otf", sha256));
+        let download = FontDownload::new("https://example.com/fonts/", &sha256);
+        assert_eq!(download.file_name(), format!("{}.font", sha256));
+    }
+
+    #[test]
+    fn downloads_are_capped() {
+        assert_eq!(read_limited(&[1u8; 8][..], 8).unwrap().len(), 8);
+        assert!(matches!(read_limited(&[1u8; 9][..], 8), Err(FontError::Download(_))));
+    }
+}
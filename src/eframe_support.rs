@@ -0,0 +1,122 @@
+//! Setup from an eframe [`CreationContext`], the usual place to configure fonts
+
+use eframe::{App, AppCreator, CreationContext};
+use egui::{Context, Id};
+
+use crate::{ChineseFontExt, FontError, LoadedFont};
+
+/// Setup Chinese fonts from the creation context passed to an eframe app creator
+///
+/// Delegates to [`setup_chinese_fonts`](crate::setup_chinese_fonts) with
+/// `cc.egui_ctx`, and remembers the name of the registered font for
+/// [`get_loaded_font_name`]. eframe only lends the app creator read access
+/// to [`CreationContext::storage`], so the name is kept in the context's
+/// memory instead, for the lifetime of the context.
+///
+/// # Returns
+/// * `Ok(LoadedFont)` describing the registered font
+/// * `Err(FontError)` if font loading failed
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use egui_chinese_font::setup_chinese_fonts_from_cc;
+///
+/// # struct MyApp;
+/// # impl eframe::App for MyApp {
+/// #     fn update(&mut self, _ctx: &egui::Context, _frame: &mut eframe::Frame) {}
+/// # }
+/// eframe::run_native(
+///     "My App",
+///     eframe::NativeOptions::default(),
+///     Box::new(|cc| {
+///         if let Err(err) = setup_chinese_fonts_from_cc(cc) {
+///             eprintln!("Chinese text may not display: {}", err);
+///         }
+///         Ok(Box::new(MyApp))
+///     }),
+/// )
+/// # .unwrap();
+/// ```
+pub fn setup_chinese_fonts_from_cc(cc: &CreationContext<'_>) -> Result<LoadedFont, FontError> {
+    let loaded = cc.egui_ctx.setup_chinese_fonts()?;
+    let name = loaded.name().to_owned();
+    cc.egui_ctx.data_mut(|data| data.insert_temp(loaded_font_name_id(), name));
+    Ok(loaded)
+}
+
+/// Wrap an app creator so Chinese fonts are set up before the app is constructed
+///
+/// Runs [`setup_chinese_fonts_from_cc`] and hands its result to
+/// `app_creator` along with the creation context. Keep the error in the app
+/// to show a warning in the UI, where users see it, rather than printing it
+/// to a console that is usually hidden.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use egui_chinese_font::{with_chinese_fonts, FontError};
+///
+/// struct MyApp {
+///     font_error: Option<FontError>,
+/// }
+///
+/// impl eframe::App for MyApp {
+///     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+///         egui::CentralPanel::default().show(ctx, |ui| {
+///             if let Some(err) = &self.font_error {
+///                 ui.colored_label(ui.visuals().warn_fg_color, format!("Chinese text may not display: {}", err));
+///             }
+///             ui.label("你好世界");
+///         });
+///     }
+/// }
+///
+/// eframe::run_native(
+///     "My App",
+///     eframe::NativeOptions::default(),
+///     with_chinese_fonts(|_cc, fonts| Ok(Box::new(MyApp { font_error: fonts.err() }))),
+/// )
+/// # .unwrap();
+/// ```
+pub fn with_chinese_fonts<'app, F>(app_creator: F) -> AppCreator<'app>
+where
+    F: 'app
+        + FnOnce(
+            &CreationContext<'_>,
+            Result<LoadedFont, FontError>,
+        ) -> Result<Box<dyn 'app + App>, Box<dyn std::error::Error + Send + Sync>>,
+{
+    Box::new(move |cc| {
+        let fonts = setup_chinese_fonts_from_cc(cc);
+        app_creator(cc, fonts)
+    })
+}
+
+/// The name of the font registered by [`setup_chinese_fonts_from_cc`]
+///
+/// # Returns
+/// * `Some(name)`, the key of the font in [`egui::FontDefinitions::font_data`]
+/// * `None` if [`setup_chinese_fonts_from_cc`] wasn't called or failed
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::{get_loaded_font_name, setup_chinese_fonts_from_cc};
+///
+/// # let path = std::env::temp_dir().join("egui-chinese-font-cc-example.ttf");
+/// # std::fs::write(&path, &*egui::FontDefinitions::default().font_data["Ubuntu-Light"].font)?;
+/// # std::env::set_var(egui_chinese_font::FONT_PATH_ENV, &path);
+/// # let cc = eframe::CreationContext::_new_kittest(egui::Context::default());
+/// assert_eq!(get_loaded_font_name(&cc.egui_ctx), None);
+/// setup_chinese_fonts_from_cc(&cc)?;
+/// assert_eq!(get_loaded_font_name(&cc.egui_ctx).as_deref(), Some("chinese"));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn get_loaded_font_name(ctx: &Context) -> Option<String> {
+    ctx.data(|data| data.get_temp(loaded_font_name_id()))
+}
+
+fn loaded_font_name_id() -> Id {
+    Id::new("egui_chinese_font::loaded_font_name")
+}
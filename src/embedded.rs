@@ -0,0 +1,54 @@
+//! Noto Sans SC embedded into the binary
+//!
+//! Enabled with the `embedded-noto` feature.
+
+use std::sync::Arc;
+
+use egui::{Context, FontData};
+
+use crate::{FontError, FontOrigin, LoadedFont};
+
+/// Subset of Noto Sans SC covering Latin, CJK punctuation and the CJK Unified Ideographs block
+///
+/// See `fonts/README.md` for how the subset is generated, and for embedding
+/// a different font with the `EGUI_CHINESE_FONT_EMBED_PATH` build-time
+/// environment variable.
+pub static NOTO_SANS_SC: &[u8] = include_bytes!(env!("EGUI_CHINESE_FONT_EMBEDDED"));
+
+/// Setup the embedded Noto Sans SC font for egui context
+///
+/// Registers the font compiled into the binary, without any filesystem
+/// access, so rendering is identical on every platform including wasm.
+///
+/// Embedding adds the size of the font file to the binary: roughly 8 MB for
+/// the bundled Simplified Chinese subset, compared with about 16 MB for the
+/// full Noto Sans CJK SC and over 20 MB for the full CJK collection.
+///
+/// # Arguments
+/// * `ctx` - The egui context to configure
+///
+/// # Returns
+/// * `Ok(LoadedFont)` describing the registered font
+/// * `Err(FontError::InvalidFont)` if the embedded file is not a usable font
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::{setup_embedded_chinese_font, FontStorage};
+///
+/// let ctx = egui::Context::default();
+/// let loaded = setup_embedded_chinese_font(&ctx)?;
+/// assert_eq!(loaded.name(), "chinese");
+///
+/// // Registered straight from the binary, without a copy
+/// assert_eq!(loaded.storage(), FontStorage::Static);
+/// # Ok::<(), egui_chinese_font::FontError>(())
+/// ```
+pub fn setup_embedded_chinese_font(ctx: &Context) -> Result<LoadedFont, FontError> {
+    crate::faces::validate_font(NOTO_SANS_SC, 0).map_err(|reason| FontError::InvalidFont { path: None, reason })?;
+
+    let font_data = Arc::new(FontData::from_static(NOTO_SANS_SC));
+    crate::apply_chinese_font(ctx, "chinese", Arc::clone(&font_data));
+
+    Ok(LoadedFont::new("chinese", FontOrigin::Memory, font_data))
+}
@@ -0,0 +1,126 @@
+//! System emoji fonts, registered as a fallback after the Chinese font
+
+use std::path::PathBuf;
+
+use egui::FontData;
+use ttf_parser::Face;
+
+use crate::faces;
+
+/// Emoji font files probed, in priority order
+///
+/// egui only draws glyph outlines, in a single color. Color emoji fonts that
+/// store bitmaps instead, such as Noto Color Emoji and Apple Color Emoji,
+/// can't be drawn and are not listed; Segoe UI Emoji has outlines under its
+/// colors.
+#[cfg(target_os = "windows")]
+const EMOJI_FONT_CANDIDATES: &[&str] = &[
+    r"C:\Windows\Fonts\seguiemj.ttf", // Segoe UI Emoji
+    r"C:\Windows\Fonts\seguisym.ttf", // Segoe UI Symbol
+];
+
+#[cfg(target_os = "macos")]
+const EMOJI_FONT_CANDIDATES: &[&str] = &[
+    // Apple Color Emoji is bitmap-only; the monochrome Noto Emoji has to be installed
+    "/Library/Fonts/NotoEmoji-Regular.ttf",
+    "/Library/Fonts/NotoEmoji-VariableFont_wght.ttf",
+    "/Library/Fonts/Symbola.ttf",
+];
+
+#[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+const EMOJI_FONT_CANDIDATES: &[&str] = &[
+    // Noto Emoji, the monochrome sibling of Noto Color Emoji
+    "/usr/share/fonts/truetype/noto/NotoEmoji-Regular.ttf", // Debian, Ubuntu
+    "/usr/share/fonts/noto/NotoEmoji-Regular.ttf", // Arch
+    "/usr/share/fonts/google-noto-emoji/NotoEmoji-Regular.ttf", // Fedora
+    "/usr/local/share/fonts/noto/NotoEmoji-Regular.ttf", // FreeBSD
+    // Symbola
+    "/usr/share/fonts/truetype/ancient-scripts/Symbola_hint.ttf", // Debian, Ubuntu
+    "/usr/share/fonts/TTF/Symbola.ttf", // Arch
+    "/usr/share/fonts/gdouros-symbola/Symbola.ttf", // Fedora
+];
+
+#[cfg(not(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "linux",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd"
+)))]
+const EMOJI_FONT_CANDIDATES: &[&str] = &[];
+
+/// Emoji an emoji font must have glyphs for
+const EMOJI_SAMPLE: &str = "😀😂👍🚀🎉❤";
+
+/// Find an emoji font egui can draw
+///
+/// Searches the known candidates, then per-user fonts with "emoji" or
+/// "symbola" in their name.
+pub(crate) fn load_emoji_font() -> Option<FontData> {
+    let mut paths: Vec<PathBuf> = EMOJI_FONT_CANDIDATES.iter().map(PathBuf::from).collect();
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "windows",
+        target_os = "macos",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd"
+    ))]
+    paths.extend(crate::user_fonts::user_font_dirs().iter().flat_map(|dir| emoji_fonts_in(dir)));
+
+    paths.into_iter().find_map(|path| {
+        let font = crate::cache::read(&path).ok()?;
+        if let Err(reason) = check_emoji_font(&font) {
+            log!(debug, "{}: not used for emoji ({})", path.display(), reason);
+            return None;
+        }
+        log!(info, "using {} for emoji", path.display());
+        let font_data = FontData {
+            font,
+            index: 0,
+            tweak: Default::default(),
+        };
+        Some(crate::cache::store(&path, font_data))
+    })
+}
+
+/// The font files directly in `dir` whose names suggest an emoji font
+#[cfg(any(
+    target_os = "linux",
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd"
+))]
+fn emoji_fonts_in(dir: &std::path::Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut fonts: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| faces::is_font_file(path))
+        .filter(|path| {
+            let name = path.file_name().map(|name| name.to_string_lossy().to_ascii_lowercase()).unwrap_or_default();
+            name.contains("emoji") || name.contains("symbola")
+        })
+        .collect();
+    fonts.sort();
+    fonts
+}
+
+/// Check that `data` is a font with drawable glyphs for common emoji
+fn check_emoji_font(data: &[u8]) -> Result<(), String> {
+    faces::validate_font(data, 0)?;
+    if !faces::has_outlines(data, 0) {
+        return Err("font has only bitmap glyphs".to_owned());
+    }
+    let face = Face::parse(data, 0).map_err(|err| format!("not a valid font file ({})", err))?;
+    let report = crate::coverage::report(Some(&face), EMOJI_SAMPLE);
+    if !report.is_complete() {
+        return Err(format!("missing emoji {}", report.missing_codepoints().join(", ")));
+    }
+    Ok(())
+}
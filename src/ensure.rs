@@ -0,0 +1,104 @@
+//! Setting up Chinese fonts only where the context can't display Chinese yet
+
+use egui::{Context, FontFamily};
+use ttf_parser::Face;
+
+use crate::coverage::{CHINESE_SAMPLE, MIN_CHINESE_COVERAGE};
+use crate::{FontConfig, FontError, LoadedFont};
+
+/// What [`ensure_chinese_fonts`] did
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum EnsureOutcome {
+    /// The context's proportional fonts already display Chinese; nothing was changed
+    AlreadyCovered,
+    /// A Chinese font was found and registered
+    Installed(LoadedFont),
+}
+
+/// Setup Chinese fonts unless the context can already display Chinese
+///
+/// Meant for library code that needs Chinese text but shouldn't replace
+/// fonts the application configured itself. The fonts of the
+/// [`FontFamily::Proportional`] family, including ones set earlier in the
+/// same pass, are checked against a sample of common Chinese characters; only
+/// if they fall short is a font searched for and registered like
+/// [`setup_chinese_fonts`](crate::setup_chinese_fonts).
+///
+/// # Returns
+/// * `Ok(EnsureOutcome::AlreadyCovered)` if the existing fonts suffice
+/// * `Ok(EnsureOutcome::Installed(LoadedFont))` if a font was registered
+/// * `Err(FontError)` if a font was needed but loading it failed
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::{ensure_chinese_fonts, EnsureOutcome};
+///
+/// let ctx = egui::Context::default();
+/// match ensure_chinese_fonts(&ctx) {
+///     Ok(EnsureOutcome::Installed(loaded)) => println!("registered {}", loaded.name()),
+///     Ok(_) => println!("the application already set up Chinese fonts"),
+///     Err(err) => eprintln!("Chinese text may not display: {}", err),
+/// }
+/// ```
+pub fn ensure_chinese_fonts(ctx: &Context) -> Result<EnsureOutcome, FontError> {
+    ensure_chinese_fonts_with_config(ctx, &FontConfig::default())
+}
+
+/// Like [`ensure_chinese_fonts`], using a custom [`FontConfig`]
+///
+/// The existing fonts must also cover [`FontConfig::required_chars`] to count
+/// as sufficient.
+pub fn ensure_chinese_fonts_with_config(ctx: &Context, config: &FontConfig) -> Result<EnsureOutcome, FontError> {
+    if covers_chinese(ctx, config.required_chars()) {
+        log!(debug, "context fonts already cover Chinese, skipping setup");
+        return Ok(EnsureOutcome::AlreadyCovered);
+    }
+
+    crate::setup_chinese_fonts_with_config(ctx, config).map(EnsureOutcome::Installed)
+}
+
+/// Whether the proportional fonts of `ctx` together display common Chinese and `required_chars`
+fn covers_chinese(ctx: &Context, required_chars: &str) -> bool {
+    let definitions = crate::current_font_definitions(ctx);
+    let Some(names) = definitions.families.get(&FontFamily::Proportional) else {
+        return false;
+    };
+    let faces: Vec<Face<'_>> = names
+        .iter()
+        .filter_map(|name| definitions.font_data.get(name))
+        .filter_map(|font_data| Face::parse(&font_data.font, font_data.index).ok())
+        .collect();
+    // egui falls back through the family per character, so a character counts if any font has it
+    let covered = |c: char| faces.iter().any(|face| face.glyph_index(c).is_some());
+
+    let sample: Vec<char> = CHINESE_SAMPLE.chars().collect();
+    let chinese = sample.iter().filter(|c| covered(**c)).count();
+    chinese as f32 / sample.len() as f32 >= MIN_CHINESE_COVERAGE
+        && required_chars.chars().filter(|c| !c.is_whitespace() && !c.is_control()).all(covered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::chinese_font_config;
+
+    #[test]
+    fn installs_only_once_covered() {
+        let (path, config) = chinese_font_config("ensure-test");
+        let ctx = Context::default();
+        assert!(!covers_chinese(&ctx, ""));
+
+        let outcome = ensure_chinese_fonts_with_config(&ctx, &config).unwrap();
+        assert!(matches!(outcome, EnsureOutcome::Installed(_)));
+        let outcome = ensure_chinese_fonts_with_config(&ctx, &config).unwrap();
+        assert!(matches!(outcome, EnsureOutcome::AlreadyCovered));
+
+        // Characters outside the font's ranges still need a font
+        assert!(covers_chinese(&ctx, "你好"));
+        assert!(!covers_chinese(&ctx, "你好𠀀"));
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,58 @@
+//! Method-call forms of the setup functions on [`egui::Context`]
+
+use egui::Context;
+
+use crate::{FontConfig, FontError, LoadedFont};
+
+/// Chinese font setup as methods on [`egui::Context`]
+///
+/// Reads like egui's own `ctx.set_fonts(..)`; each method delegates to the
+/// free function of the same name and returns the registered font. Import it
+/// with `use egui_chinese_font::prelude::*;`.
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::prelude::*;
+///
+/// # let font_data = egui::FontDefinitions::default().font_data["Ubuntu-Light"].font.to_vec();
+/// # let path = std::env::temp_dir().join("egui-chinese-font-ext-example.ttf");
+/// # std::fs::write(&path, &font_data)?;
+/// # std::env::set_var(egui_chinese_font::FONT_PATH_ENV, &path);
+/// let ctx = egui::Context::default();
+/// let loaded = ctx.setup_chinese_fonts()?;
+/// assert_eq!(loaded.name(), "chinese");
+///
+/// let loaded = ctx.setup_chinese_fonts_with(|config| config.with_variant(VariantPreference::Traditional))?;
+/// assert_eq!(loaded.name(), "chinese");
+///
+/// let loaded = ctx.setup_custom_chinese_font(font_data)?;
+/// assert_eq!(loaded.origin(), &FontOrigin::Memory);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub trait ChineseFontExt {
+    /// Setup Chinese fonts, see [`setup_chinese_fonts`](crate::setup_chinese_fonts)
+    fn setup_chinese_fonts(&self) -> Result<LoadedFont, FontError>;
+
+    /// Setup Chinese fonts with a [`FontConfig`] adjusted by `configure`,
+    /// see [`setup_chinese_fonts_with_config`](crate::setup_chinese_fonts_with_config)
+    fn setup_chinese_fonts_with(&self, configure: impl FnOnce(FontConfig) -> FontConfig) -> Result<LoadedFont, FontError>;
+
+    /// Setup `font_data` as the Chinese font, registered as `"chinese"`,
+    /// see [`setup_custom_chinese_font`](crate::setup_custom_chinese_font)
+    fn setup_custom_chinese_font(&self, font_data: Vec<u8>) -> Result<LoadedFont, FontError>;
+}
+
+impl ChineseFontExt for Context {
+    fn setup_chinese_fonts(&self) -> Result<LoadedFont, FontError> {
+        crate::setup_chinese_fonts(self)
+    }
+
+    fn setup_chinese_fonts_with(&self, configure: impl FnOnce(FontConfig) -> FontConfig) -> Result<LoadedFont, FontError> {
+        crate::setup_chinese_fonts_with_config(self, &configure(FontConfig::default()))
+    }
+
+    fn setup_custom_chinese_font(&self, font_data: Vec<u8>) -> Result<LoadedFont, FontError> {
+        crate::setup_custom_chinese_font(self, font_data, None)
+    }
+}
@@ -0,0 +1,436 @@
+//! Face enumeration and selection for font collections (`.ttc`)
+
+use std::borrow::Cow;
+use std::path::Path;
+
+use ttf_parser::name::Names;
+use ttf_parser::{name_id, Face};
+
+use crate::{ChineseVariant, CjkLanguage, FontError};
+
+/// Description of a single face inside a font file
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FaceInfo {
+    /// Index of the face within the file, as used by [`egui::FontData::index`]
+    pub index: u32,
+    /// English family name, e.g. `"PingFang TC"`
+    pub family: Option<String>,
+    /// English subfamily (style) name, e.g. `"Regular"`
+    pub subfamily: Option<String>,
+}
+
+/// List the faces contained in a font file
+///
+/// Font collections such as `PingFang.ttc` or `NotoSansCJK-Regular.ttc` hold
+/// several faces, for example separate Simplified and Traditional Chinese
+/// designs. Plain `.ttf` / `.otf` files report a single face at index 0.
+///
+/// # Arguments
+/// * `path` - Path to a `.ttf`, `.otf` or `.ttc` file
+///
+/// # Returns
+/// * `Ok(Vec<FaceInfo>)` with one entry per face that could be parsed
+/// * `Err(FontError::PermissionDenied)` if the file is not readable by this process
+/// * `Err(FontError::ReadError)` if the file could not be read otherwise
+pub fn list_ttc_faces(path: impl AsRef<Path>) -> Result<Vec<FaceInfo>, FontError> {
+    let path = path.as_ref();
+    let data = std::fs::read(path).map_err(|err| FontError::from_read(path, err))?;
+    Ok(faces(&data).map(|(index, face)| face_info(index, &face)).collect())
+}
+
+/// Extract one face of a font collection into a standalone font
+///
+/// egui keeps the whole file in memory even though it only uses one face,
+/// so a face of a large collection such as `PingFang.ttc` costs as much as
+/// all of them. This copies the tables of face `index` into a new font file,
+/// rewriting the table offsets and the `head` checksum adjustment; the
+/// result is used with face index `0`. A plain `.ttf` / `.otf` is returned
+/// unchanged. See [`FontConfig::with_extract_single_face`](crate::FontConfig::with_extract_single_face).
+///
+/// # Returns
+/// * `Ok(Vec<u8>)` with the standalone font
+/// * `Err(FontError::InvalidFont)` if the collection is malformed or has no face `index`
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::{extract_ttc_face, font_covers};
+///
+/// # fn collection(fonts: &[&[u8]]) -> Vec<u8> {
+/// #     let read = |data: &[u8], at: usize, len: usize| data[at..at + len].iter().fold(0u32, |acc, b| acc << 8 | *b as u32);
+/// #     let mut out = b"ttcf\0\x01\0\0".to_vec();
+/// #     out.extend((fonts.len() as u32).to_be_bytes());
+/// #     let dirs_start = out.len() + 4 * fonts.len();
+/// #     let dir_len = |font: &[u8]| 12 + 16 * read(font, 4, 2) as usize;
+/// #     let mut dir_at = dirs_start;
+/// #     for font in fonts {
+/// #         out.extend((dir_at as u32).to_be_bytes());
+/// #         dir_at += dir_len(font);
+/// #     }
+/// #     let mut tables = Vec::new();
+/// #     for font in fonts {
+/// #         out.extend(&font[..12]);
+/// #         for record in font[12..dir_len(font)].chunks(16) {
+/// #             let (offset, len) = (read(record, 8, 4) as usize, read(record, 12, 4) as usize);
+/// #             out.extend(&record[..8]);
+/// #             out.extend(((dir_at + tables.len()) as u32).to_be_bytes());
+/// #             out.extend(&record[12..]);
+/// #             tables.extend(&font[offset..offset + len]);
+/// #             tables.resize((tables.len() + 3) & !3, 0);
+/// #         }
+/// #     }
+/// #     out.extend(tables);
+/// #     out
+/// # }
+/// let defaults = egui::FontDefinitions::default();
+/// let ubuntu = &defaults.font_data["Ubuntu-Light"].font;
+/// let hack = &defaults.font_data["Hack"].font;
+/// // A collection with the faces of both fonts
+/// let ttc = collection(&[ubuntu, hack]);
+///
+/// let face = extract_ttc_face(&ttc, 1)?;
+/// assert!(face.len() < ttc.len());
+///
+/// let sample = "Hello, 你好 0O1l";
+/// let mut from_collection = egui::FontData::from_owned(ttc);
+/// from_collection.index = 1;
+/// let extracted = egui::FontData::from_owned(face);
+/// assert_eq!(font_covers(&extracted, sample).missing, font_covers(&from_collection, sample).missing);
+/// # Ok::<(), egui_chinese_font::FontError>(())
+/// ```
+pub fn extract_ttc_face(data: &[u8], index: u32) -> Result<Vec<u8>, FontError> {
+    let invalid = |reason: &str| FontError::InvalidFont {
+        path: None,
+        reason: reason.to_owned(),
+    };
+    if data.get(..4) != Some(b"ttcf") {
+        validate_font(data, index).map_err(|reason| invalid(&reason))?;
+        return Ok(data.to_vec());
+    }
+
+    let face = extract_face(data, index).ok_or_else(|| invalid(&format!("collection has no readable face {}", index)))?;
+    validate_font(&face, 0).map_err(|reason| invalid(&reason))?;
+    Ok(face)
+}
+
+/// Copy the tables of face `index` of a collection into a standalone font
+fn extract_face(data: &[u8], index: u32) -> Option<Vec<u8>> {
+    let (version, tables) = face_tables(data, index)?;
+    Some(write_font(version, tables.into_iter().map(|(tag, table)| (tag, Cow::Borrowed(table))).collect()))
+}
+
+/// The tag and data of a font table
+pub(crate) type Table<'a> = ([u8; 4], &'a [u8]);
+
+/// The sfnt version and every table of face `index`
+///
+/// `data` is a single font (then `index` must be `0`) or a collection.
+pub(crate) fn face_tables(data: &[u8], index: u32) -> Option<([u8; 4], Vec<Table<'_>>)> {
+    let u16_at = |at: usize| Some(u16::from_be_bytes(data.get(at..at + 2)?.try_into().ok()?));
+    let u32_at = |at: usize| Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?));
+
+    let face_offset = if data.get(..4)? == b"ttcf" {
+        if index >= u32_at(8)? {
+            return None;
+        }
+        u32_at(12 + 4 * usize::try_from(index).ok()?)? as usize
+    } else if index == 0 {
+        0
+    } else {
+        return None;
+    };
+    let version = data.get(face_offset..face_offset + 4)?.try_into().ok()?;
+    let num_tables = usize::from(u16_at(face_offset + 4)?);
+
+    let mut tables = Vec::with_capacity(num_tables);
+    for record in 0..num_tables {
+        let at = face_offset + 12 + 16 * record;
+        let (offset, length) = (u32_at(at + 8)? as usize, u32_at(at + 12)? as usize);
+        tables.push((data.get(at..at + 4)?.try_into().ok()?, data.get(offset..offset.checked_add(length)?)?));
+    }
+    Some((version, tables))
+}
+
+/// Assemble a standalone font from its tables, computing offsets and checksums
+pub(crate) fn write_font(version: [u8; 4], mut tables: Vec<([u8; 4], Cow<'_, [u8]>)>) -> Vec<u8> {
+    // Binary searches over the table directory need it sorted by tag
+    tables.sort_by_key(|(tag, _)| *tag);
+
+    let num_tables = tables.len() as u16;
+    let entry_selector = num_tables.max(1).ilog2() as u16;
+    let search_range = 16 << entry_selector;
+    let mut font = version.to_vec();
+    for field in [num_tables, search_range, entry_selector, (num_tables * 16).saturating_sub(search_range)] {
+        font.extend(field.to_be_bytes());
+    }
+
+    // Records are completed once the table data is in place
+    let directory = font.len();
+    font.resize(directory + 16 * tables.len(), 0);
+    let mut ranges = Vec::with_capacity(tables.len());
+    for (tag, table) in &tables {
+        let start = font.len();
+        font.extend(&**table);
+        if tag == b"head" && table.len() >= 12 {
+            // `checkSumAdjustment` counts as zero in all checksums
+            font[start + 8..start + 12].fill(0);
+        }
+        ranges.push((start, table.len()));
+        font.resize((font.len() + 3) & !3, 0);
+    }
+    for (record, ((tag, _), (start, len))) in tables.iter().zip(ranges.iter().copied()).enumerate() {
+        let checksum = checksum(&font[start..(start + len + 3) & !3]);
+        let at = directory + 16 * record;
+        font[at..at + 4].copy_from_slice(tag);
+        font[at + 4..at + 8].copy_from_slice(&checksum.to_be_bytes());
+        font[at + 8..at + 12].copy_from_slice(&(start as u32).to_be_bytes());
+        font[at + 12..at + 16].copy_from_slice(&(len as u32).to_be_bytes());
+    }
+
+    // `checkSumAdjustment` makes the checksum of the whole font 0xB1B0AFBA
+    let head = tables.iter().zip(&ranges).find(|((tag, _), (_, len))| tag == b"head" && *len >= 12);
+    if let Some((_, (start, _))) = head {
+        let adjustment = 0xB1B0_AFBAu32.wrapping_sub(checksum(&font));
+        font[start + 8..start + 12].copy_from_slice(&adjustment.to_be_bytes());
+    }
+    font
+}
+
+/// The OpenType checksum of `data`, whose length is a multiple of four
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4)
+        .map(|word| u32::from_be_bytes(word.try_into().unwrap_or_default()))
+        .fold(0, u32::wrapping_add)
+}
+
+/// Check that `data` holds a usable font face at `index`
+///
+/// Only the table directory and `cmap` are parsed, so this is cheap even for
+/// large collections. Returns a human-readable reason on failure.
+pub(crate) fn validate_font(data: &[u8], index: u32) -> Result<(), String> {
+    check_magic(data)?;
+
+    let face = Face::parse(data, index).map_err(|err| format!("not a valid font file ({})", err))?;
+
+    match face.tables().cmap {
+        Some(cmap) if !cmap.subtables.is_empty() => Ok(()),
+        _ => Err("font has no character map (cmap)".to_string()),
+    }
+}
+
+/// Whether face `index` of `data` has outline glyphs egui can rasterize
+///
+/// Some old CJK fonts only embed bitmaps (`EBDT`/`CBDT`) for a few pixel
+/// sizes; egui draws nothing for those.
+pub(crate) fn has_outlines(data: &[u8], index: u32) -> bool {
+    Face::parse(data, index).is_ok_and(|face| {
+        let tables = face.tables();
+        tables.glyf.is_some() || tables.cff.is_some() || tables.cff2.is_some()
+    })
+}
+
+/// Check the file signature, giving a clearer reason than the parser for common mistakes
+///
+/// `OTTO` marks an OpenType font with CFF outlines, e.g. Source Han Sans; a
+/// collection (`ttcf`) may hold either kind. WOFF and WOFF2 web fonts wrap
+/// the same tables compressed, which egui can't read; see [`convert_woff_hint`].
+fn check_magic(data: &[u8]) -> Result<(), String> {
+    match data.get(..4) {
+        Some([0x00, 0x01, 0x00, 0x00] | b"OTTO" | b"true" | b"ttcf") => Ok(()),
+        Some(b"wOFF" | b"wOF2") => Err("WOFF/WOFF2 formats are not supported; convert to TTF/OTF first".to_string()),
+        Some(_) => Err("not a TrueType, OpenType or collection file (unknown signature)".to_string()),
+        None => Err("file is too short to be a font".to_string()),
+    }
+}
+
+/// A shell command converting the WOFF or WOFF2 font at `path` to a plain font egui can load
+///
+/// Web fonts are rejected with [`FontError::InvalidFont`]. The command uses
+/// [fontTools](https://github.com/fonttools/fonttools) (`pip install fonttools brotli`,
+/// brotli being needed for WOFF2) and writes the result next to `path`, with
+/// a `.ttf` extension. It is meant to be shown to users, not run by the
+/// application.
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::{convert_woff_hint, setup_custom_chinese_font, FontError};
+///
+/// let ctx = egui::Context::default();
+/// let woff = b"wOF2\0\x01\0\0".to_vec();
+/// match setup_custom_chinese_font(&ctx, woff, None) {
+///     Err(FontError::InvalidFont { reason, .. }) => assert!(reason.contains("WOFF")),
+///     other => panic!("unexpected {:?}", other),
+/// }
+///
+/// let hint = convert_woff_hint("fonts/NotoSansSC.woff2");
+/// assert_eq!(
+///     hint,
+///     r#"python3 -c 'from fontTools.ttLib import TTFont; f = TTFont("fonts/NotoSansSC.woff2"); f.flavor = None; f.save("fonts/NotoSansSC.ttf")'"#
+/// );
+/// ```
+pub fn convert_woff_hint(path: impl AsRef<Path>) -> String {
+    let path = path.as_ref();
+    format!(
+        "python3 -c 'from fontTools.ttLib import TTFont; f = TTFont({:?}); f.flavor = None; f.save({:?})'",
+        path.display().to_string(),
+        path.with_extension("ttf").display().to_string()
+    )
+}
+
+/// Pick the face in `data` that best matches `variant`
+///
+/// Returns `0` for single-face files and when no face name indicates a variant.
+pub(crate) fn select_face_index(data: &[u8], variant: ChineseVariant) -> u32 {
+    if ttf_parser::fonts_in_collection(data).is_none() {
+        return 0;
+    }
+
+    let variants: Vec<(u32, Option<ChineseVariant>)> = faces(data)
+        .map(|(index, face)| (index, english_family(&face).and_then(|family| family_variant(&family))))
+        .collect();
+
+    // Exact variant first, then any Chinese face rather than e.g. a Japanese one
+    variants
+        .iter()
+        .find(|(_, face_variant)| *face_variant == Some(variant))
+        .or_else(|| variants.iter().find(|(_, face_variant)| face_variant.is_some()))
+        .map(|(index, _)| *index)
+        .unwrap_or(0)
+}
+
+/// The face of a pan-CJK collection, such as Noto Sans CJK, named for `language`
+///
+/// Returns `None` for single-face files and when no face name indicates the language.
+pub(crate) fn select_language_face(data: &[u8], language: CjkLanguage) -> Option<u32> {
+    ttf_parser::fonts_in_collection(data)?;
+    faces(data)
+        .find(|(_, face)| english_family(face).and_then(|family| family_language(&family)) == Some(language))
+        .map(|(index, _)| index)
+}
+
+/// Iterate over the faces in `data` that parse successfully
+pub(crate) fn faces(data: &[u8]) -> impl Iterator<Item = (u32, Face<'_>)> {
+    let count = ttf_parser::fonts_in_collection(data).unwrap_or(1);
+    (0..count).filter_map(move |index| Face::parse(data, index).ok().map(|face| (index, face)))
+}
+
+fn face_info(index: u32, face: &Face<'_>) -> FaceInfo {
+    FaceInfo {
+        index,
+        family: english_family(face),
+        subfamily: names_subfamily(face.names()),
+    }
+}
+
+pub(crate) fn english_family(face: &Face<'_>) -> Option<String> {
+    names_family(face.names())
+}
+
+/// The English family name in a `name` table
+pub(crate) fn names_family(names: Names<'_>) -> Option<String> {
+    english_name(names, name_id::TYPOGRAPHIC_FAMILY).or_else(|| english_name(names, name_id::FAMILY))
+}
+
+/// The English subfamily (style) name in a `name` table
+pub(crate) fn names_subfamily(names: Names<'_>) -> Option<String> {
+    english_name(names, name_id::TYPOGRAPHIC_SUBFAMILY).or_else(|| english_name(names, name_id::SUBFAMILY))
+}
+
+/// Look up a name, preferring the US English record
+fn english_name(names: Names<'_>, id: u16) -> Option<String> {
+    const ENGLISH_US: u16 = 0x0409;
+
+    let mut fallback = None;
+    for name in names {
+        if name.name_id != id || !name.is_unicode() {
+            continue;
+        }
+        if name.language_id == ENGLISH_US {
+            return name.to_string();
+        }
+        if fallback.is_none() {
+            fallback = name.to_string();
+        }
+    }
+    fallback
+}
+
+/// File and family name fragments that identify CJK fonts, compared without spaces, `-` or `_`
+const CJK_IDENTIFIERS: &[&str] = &[
+    "cjk",
+    "wqy",
+    "wenquanyi",
+    "droidsansfallback",
+    "sourcehan",
+    "notosanssc",
+    "notosanstc",
+    "notosanshk",
+    "notoserifsc",
+    "notoseriftc",
+    "uming",
+    "ukai",
+    "msyh",
+    "msjh",
+    "simsun",
+    "simhei",
+    "simkai",
+    "simfang",
+    "pingfang",
+    "yahei",
+    "jhenghei",
+    "lxgw",
+    "sarasa",
+    "heiti",
+    "songti",
+    "kaiti",
+    "fangsong",
+    "mingliu",
+    "hiraginosansgb",
+];
+
+/// Whether a file or family name contains a known CJK font identifier, such as `wqy-microhei` or `Noto Sans CJK SC`
+pub(crate) fn is_cjk_name(name: &str) -> bool {
+    let normalized: String = name
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '_'))
+        .collect::<String>()
+        .to_ascii_lowercase();
+
+    CJK_IDENTIFIERS.iter().any(|id| normalized.contains(id))
+}
+
+/// Whether `path` has a font file extension
+///
+/// OpenType fonts (`.otf`, and `.otc` collections) may have CFF outlines
+/// rather than TrueType ones; both load the same way.
+pub(crate) fn is_font_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| matches!(ext.to_ascii_lowercase().as_str(), "ttf" | "ttc" | "otf" | "otc"))
+}
+
+/// Infer the CJK language a face targets from its family name, e.g. `Noto Sans CJK JP`
+pub(crate) fn family_language(family: &str) -> Option<CjkLanguage> {
+    let suffix = family.rsplit(' ').next().unwrap_or_default().to_ascii_lowercase();
+    match suffix.as_str() {
+        "jp" | "j" => Some(CjkLanguage::Japanese),
+        "kr" | "k" => Some(CjkLanguage::Korean),
+        _ => family_variant(family).map(CjkLanguage::from),
+    }
+}
+
+/// Infer the Chinese variant a face targets from its family name
+pub(crate) fn family_variant(family: &str) -> Option<ChineseVariant> {
+    let family = family.to_ascii_lowercase();
+    let suffix = family.rsplit(' ').next().unwrap_or_default();
+
+    match suffix {
+        "sc" | "gb" | "cn" => Some(ChineseVariant::Simplified),
+        "tc" | "hk" | "tw" | "mo" => Some(ChineseVariant::Traditional),
+        _ if family.contains("jhenghei") || family.contains("mingliu") => Some(ChineseVariant::Traditional),
+        _ if family.contains("yahei") || family.contains("simsun") => Some(ChineseVariant::Simplified),
+        _ => None,
+    }
+}
@@ -0,0 +1,186 @@
+//! Registering Chinese styles as named egui font families
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use egui::{Context, FontData, FontFamily};
+
+use crate::platform::FontQuery;
+use crate::{load_chinese_font, ChineseStyle, FontConfig, FontError};
+
+/// Where the Chinese font for one font family comes from, see [`setup_multiple_chinese_fonts`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FontPreference {
+    /// Search the installed fonts; for `Monospace`, a monospaced CJK font is preferred
+    System,
+    /// Use these font bytes
+    Custom(Vec<u8>),
+    /// Read this font file; for collections, the face for the preferred variant is used
+    Path(PathBuf),
+}
+
+/// Load one Chinese font per style and register each under a named font family
+///
+/// Each `(style, family)` pair becomes `FontFamily::Name(family)`, holding the
+/// font for `style` followed by the proportional fonts, so Latin glyphs still
+/// render. Use the families with e.g. `RichText::family` to show several
+/// Chinese styles at once. The fonts are registered as `"chinese-hei"`,
+/// `"chinese-song"` and so on.
+///
+/// Every family is registered even when its style isn't installed, falling
+/// back to the proportional fonts, so using the family never panics.
+///
+/// # Arguments
+/// * `ctx` - The egui context to configure
+/// * `families` - `(style, family name)` pairs
+///
+/// # Returns
+/// * `Ok(())` if a font was found for every style
+/// * `Err(FontError)` for the first style that couldn't be loaded; the families are still registered
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use egui::{FontFamily, RichText};
+/// use egui_chinese_font::{register_chinese_families, ChineseStyle};
+///
+/// let ctx = egui::Context::default();
+/// register_chinese_families(&ctx, &[(ChineseStyle::Song, "ChineseSerif"), (ChineseStyle::Kai, "ChineseKai")])?;
+///
+/// let quote = RichText::new("学而时习之").family(FontFamily::Name("ChineseKai".into()));
+/// # Ok::<(), egui_chinese_font::FontError>(())
+/// ```
+pub fn register_chinese_families(ctx: &Context, families: &[(ChineseStyle, &str)]) -> Result<(), FontError> {
+    let mut definitions = crate::current_font_definitions(ctx);
+    let proportional = definitions
+        .families
+        .get(&FontFamily::Proportional)
+        .cloned()
+        .unwrap_or_default();
+
+    let mut first_error = None;
+    for (style, family) in families {
+        let name = font_name(*style);
+        let mut names = proportional.clone();
+
+        match load_chinese_font(crate::VariantPreference::Auto.resolve(), &FontConfig::new().with_style(*style)) {
+            Ok(font_data) => {
+                definitions.font_data.insert(name.clone(), Arc::new(font_data));
+                names.retain(|existing| *existing != name);
+                names.insert(0, name);
+            }
+            Err(err) => {
+                first_error.get_or_insert(err);
+            }
+        }
+
+        definitions.families.insert(FontFamily::Name((*family).into()), names);
+    }
+
+    crate::set_font_definitions(ctx, definitions);
+
+    match first_error {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+/// Key a style's font is registered under
+fn font_name(style: ChineseStyle) -> String {
+    let style = match style {
+        ChineseStyle::Hei => "hei",
+        ChineseStyle::Song => "song",
+        ChineseStyle::Kai => "kai",
+        ChineseStyle::FangSong => "fangsong",
+    };
+    format!("chinese-{}", style)
+}
+
+/// Setup separate Chinese fonts for proportional and monospaced text
+///
+/// The proportional font is registered as `"chinese"` at the front of
+/// `Proportional`, the monospace font as `"chinese-mono"` at the front of
+/// `Monospace`, so each family uses only its own Chinese font, e.g.
+/// WenQuanYi Zen Hei for prose and WenQuanYi Micro Hei Mono for code. Both
+/// names replace fonts registered under them earlier.
+///
+/// With [`FontPreference::System`], `Monospace` gets a monospaced CJK font
+/// such as Noto Sans Mono CJK if one is installed, and the regular Chinese
+/// font otherwise. Installed fonts get their [`recommended_tweak`](crate::recommended_tweak);
+/// custom bytes are used as they are.
+///
+/// # Arguments
+/// * `ctx` - The egui context to configure
+/// * `proportional` - The font for `FontFamily::Proportional`
+/// * `monospace` - The font for `FontFamily::Monospace`
+///
+/// # Returns
+/// * `Ok(())` if both fonts were registered
+/// * `Err(FontError)` if either font could not be loaded; nothing is registered then
+///
+/// # Example
+///
+/// ```rust
+/// use egui::FontFamily;
+/// use egui_chinese_font::{setup_multiple_chinese_fonts, FontPreference};
+///
+/// # let prose = egui::FontDefinitions::default().font_data["Ubuntu-Light"].font.to_vec();
+/// # let code_path = std::env::temp_dir().join("egui-chinese-font-mono-example.ttf");
+/// # std::fs::write(&code_path, &*egui::FontDefinitions::default().font_data["Hack"].font)?;
+/// let ctx = egui::Context::default();
+/// setup_multiple_chinese_fonts(&ctx, FontPreference::Custom(prose), FontPreference::Path(code_path))?;
+///
+/// let _ = ctx.run(Default::default(), |_| {});
+/// let families = ctx.fonts(|fonts| fonts.definitions().families.clone());
+/// assert_eq!(families[&FontFamily::Proportional][0], "chinese");
+/// assert_eq!(families[&FontFamily::Monospace][0], "chinese-mono");
+/// assert!(!families[&FontFamily::Monospace].contains(&"chinese".to_owned()));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn setup_multiple_chinese_fonts(
+    ctx: &Context,
+    proportional: FontPreference,
+    monospace: FontPreference,
+) -> Result<(), FontError> {
+    let fonts = [
+        ("chinese", FontFamily::Proportional, load_preference(proportional, false)?),
+        ("chinese-mono", FontFamily::Monospace, load_preference(monospace, true)?),
+    ];
+
+    let mut definitions = crate::current_font_definitions(ctx);
+    for names in definitions.families.values_mut() {
+        names.retain(|name| fonts.iter().all(|(font_name, ..)| name != font_name));
+    }
+    for (name, family, font_data) in fonts {
+        definitions.font_data.insert(name.to_owned(), Arc::new(font_data));
+        definitions.families.entry(family).or_default().insert(0, name.to_owned());
+    }
+    crate::set_font_definitions(ctx, definitions);
+
+    Ok(())
+}
+
+/// Load the font for one family, preferring a monospaced CJK font from the system if `monospace`
+fn load_preference(preference: FontPreference, monospace: bool) -> Result<FontData, FontError> {
+    let config = FontConfig::default();
+    let font_data = match preference {
+        FontPreference::Custom(font_data) => return crate::validated_font_data(font_data, 0),
+        FontPreference::Path(path) => crate::read_font_file(&path, None)?,
+        FontPreference::System => {
+            let variant = config.variant().resolve();
+            let query = FontQuery {
+                variant,
+                style: None,
+                required_chars: "",
+            };
+            let mono = if monospace { crate::mono::load_mono_font(&query) } else { None };
+            match mono {
+                Some(found) => found.font_data,
+                None => load_chinese_font(variant, &config)?,
+            }
+        }
+    };
+
+    let tweak = config.tweak_for(&font_data);
+    Ok(font_data.tweak(tweak))
+}
@@ -0,0 +1,62 @@
+//! Font discovery through the user's fontconfig setup
+//!
+//! Enabled with the `fc-match` feature. Asks `fc-match` which font is
+//! configured for Chinese text, so local aliases and preferences are honored.
+
+use std::process::Command;
+
+use egui::FontData;
+
+use crate::platform::{FontQuery, FoundFont};
+use crate::{ChineseStyle, ChineseVariant, FontError};
+
+/// Load the font fontconfig picks for Chinese text
+pub(crate) fn load_chinese_font(query: &FontQuery<'_>) -> Result<FoundFont, FontError> {
+    let pattern = pattern(query).ok_or_else(|| FontError::not_found("fontconfig has no generic family for this style"))?;
+
+    let output = Command::new("fc-match")
+        .args(["-f", "%{file}\n%{index}", &pattern])
+        .output()
+        .map_err(|err| FontError::not_found(format!("fc-match is not available ({})", err)))?;
+    if !output.status.success() {
+        return Err(FontError::not_found(format!("fc-match failed for {}", pattern)));
+    }
+
+    let output = String::from_utf8_lossy(&output.stdout);
+    let mut lines = output.lines();
+    let path = lines
+        .next()
+        .filter(|path| !path.is_empty())
+        .ok_or_else(|| FontError::not_found(format!("fc-match returned no font for {}", pattern)))?;
+    let index = lines.next().and_then(|index| index.trim().parse().ok()).unwrap_or(0);
+
+    let font_data = crate::cache::read(path.as_ref()).map_err(|err| FontError::from_read(path.as_ref(), err))?;
+    crate::faces::validate_font(&font_data, index)
+        .and_then(|()| crate::coverage::check_candidate(&font_data, index, query.required_chars))
+        .map_err(|reason| FontError::InvalidFont {
+            path: Some(path.into()),
+            reason,
+        })?;
+
+    log!(info, "using {} (face {}) from fontconfig", path, index);
+    let font_data = FontData {
+        font: font_data,
+        index,
+        tweak: Default::default(),
+    };
+    Ok(FoundFont::new(crate::cache::store(path.as_ref(), font_data), Some(path.into())))
+}
+
+/// The fontconfig pattern for `query`, e.g. `sans-serif:lang=zh-cn`
+fn pattern(query: &FontQuery<'_>) -> Option<String> {
+    let family = match query.style {
+        None | Some(ChineseStyle::Hei) => "sans-serif",
+        Some(ChineseStyle::Song) => "serif",
+        Some(ChineseStyle::Kai | ChineseStyle::FangSong) => return None,
+    };
+    let lang = match query.variant {
+        ChineseVariant::Simplified => "zh-cn",
+        ChineseVariant::Traditional => "zh-tw",
+    };
+    Some(format!("{}:lang={}", family, lang))
+}
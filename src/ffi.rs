@@ -0,0 +1,231 @@
+//! C entry points for hosts that drive egui through C bindings
+//!
+//! Enabled with the `ffi` feature. Link this crate into a `staticlib` or
+//! `cdylib` and declare the functions from `include/egui_chinese_font.h`.
+//! Every function returns one of the `EGUI_CHINESE_FONT_*` status codes;
+//! [`egui_chinese_font_last_error`] describes the last failure.
+//!
+//! `egui::Context` has no stable C layout, so C code never sees one. The
+//! Rust side of the bindings wraps the context in a [`ContextHandle`] and
+//! hands C the opaque `EguiChineseFontContext *` pointer it returns; C frees
+//! it with [`egui_chinese_font_context_free`].
+
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::Path;
+
+use egui::Context;
+
+use crate::FontError;
+
+/// The font was loaded and registered
+pub const EGUI_CHINESE_FONT_OK: c_int = 0;
+/// No suitable Chinese font was found
+pub const EGUI_CHINESE_FONT_NOT_FOUND: c_int = 1;
+/// A font file exists but could not be read
+pub const EGUI_CHINESE_FONT_READ_ERROR: c_int = 2;
+/// The font data is not a usable font
+pub const EGUI_CHINESE_FONT_INVALID_FONT: c_int = 3;
+/// There are no known font locations for this platform
+pub const EGUI_CHINESE_FONT_UNSUPPORTED_PLATFORM: c_int = 4;
+/// Any other error; see [`egui_chinese_font_last_error`]
+pub const EGUI_CHINESE_FONT_OTHER_ERROR: c_int = 5;
+/// A required pointer argument was null, or a string was not valid UTF-8
+pub const EGUI_CHINESE_FONT_INVALID_ARGUMENT: c_int = -1;
+/// The call panicked; the panic was caught at the FFI boundary
+pub const EGUI_CHINESE_FONT_PANIC: c_int = -2;
+
+/// Opaque handle to an egui context, seen from C as `EguiChineseFontContext`
+///
+/// A handle holds its own reference to the context, which egui shares
+/// between clones, so it stays valid after the Rust side drops its
+/// `Context` and until it is freed.
+pub struct ContextHandle(Context);
+
+impl ContextHandle {
+    /// Create a handle for `ctx` to pass to C code
+    ///
+    /// The returned pointer is never null. Free it exactly once with
+    /// [`egui_chinese_font_context_free`]; it must not be used afterwards.
+    pub fn create(ctx: &Context) -> *mut ContextHandle {
+        Box::into_raw(Box::new(ContextHandle(ctx.clone())))
+    }
+}
+
+/// Free a handle created by [`ContextHandle::create`]
+///
+/// Does nothing if `handle` is null.
+///
+/// # Safety
+/// `handle` must be null or a handle from [`ContextHandle::create`] that was
+/// not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn egui_chinese_font_context_free(handle: *mut ContextHandle) {
+    if !handle.is_null() {
+        // SAFETY: the caller guarantees `handle` came from `ContextHandle::create` and is freed only once
+        drop(unsafe { Box::from_raw(handle) });
+    }
+}
+
+thread_local! {
+    /// Description of the last failed call on this thread
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Setup Chinese fonts for the egui context behind `ctx`
+///
+/// The Rust equivalent of [`setup_chinese_fonts`](crate::setup_chinese_fonts).
+///
+/// # Safety
+/// `ctx` must be null or a handle from [`ContextHandle::create`] that was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn egui_chinese_font_setup(ctx: *const ContextHandle) -> c_int {
+    // SAFETY: the caller guarantees `ctx` is null or a live handle
+    let Some(ContextHandle(ctx)) = (unsafe { ctx.as_ref() }) else {
+        return invalid_argument("ctx is null");
+    };
+    run(|| crate::setup_chinese_fonts(ctx))
+}
+
+/// Setup the font file at `path`, a NUL-terminated UTF-8 string, for the egui context behind `ctx`
+///
+/// The Rust equivalent of [`setup_chinese_font_from_path`](crate::setup_chinese_font_from_path).
+///
+/// # Safety
+/// `ctx` must be null or a handle from [`ContextHandle::create`] that was not
+/// freed yet, and `path` must be null or point to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn egui_chinese_font_setup_from_path(
+    ctx: *const ContextHandle,
+    path: *const c_char,
+    face_index: u32,
+) -> c_int {
+    // SAFETY: the caller guarantees `ctx` is null or a live handle
+    let Some(ContextHandle(ctx)) = (unsafe { ctx.as_ref() }) else {
+        return invalid_argument("ctx is null");
+    };
+    if path.is_null() {
+        return invalid_argument("path is null");
+    }
+    // SAFETY: the caller guarantees `path` is NUL-terminated
+    let Ok(path) = unsafe { CStr::from_ptr(path) }.to_str() else {
+        return invalid_argument("path is not valid UTF-8");
+    };
+    run(|| crate::setup_chinese_font_from_path(ctx, Path::new(path), face_index))
+}
+
+/// Description of the last failed call on this thread, or null if none failed yet
+///
+/// The string is owned by the library and stays valid until the next call
+/// into this library on the same thread; copy it to keep it.
+///
+/// # Example
+///
+/// ```rust
+/// use std::ffi::CStr;
+/// use egui_chinese_font::ffi::*;
+///
+/// // The Rust side of the bindings passes C a handle instead of the context itself
+/// let ctx = egui::Context::default();
+/// let handle = ContextHandle::create(&ctx);
+///
+/// let status = unsafe { egui_chinese_font_setup(handle) };
+/// if status != EGUI_CHINESE_FONT_OK {
+///     let message = unsafe { CStr::from_ptr(egui_chinese_font_last_error()) };
+///     eprintln!("Chinese text may not display: {}", message.to_string_lossy());
+/// }
+/// unsafe { egui_chinese_font_context_free(handle) };
+/// ```
+#[no_mangle]
+pub extern "C" fn egui_chinese_font_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(std::ptr::null(), |message| message.as_ptr()))
+}
+
+/// Run `setup`, turning its outcome and any panic into a status code
+fn run<T>(setup: impl FnOnce() -> Result<T, FontError>) -> c_int {
+    match catch_unwind(AssertUnwindSafe(setup)) {
+        Ok(Ok(_)) => EGUI_CHINESE_FONT_OK,
+        Ok(Err(err)) => {
+            let status = match err {
+                FontError::NotFound { .. } | FontError::StyleNotFound(_) => EGUI_CHINESE_FONT_NOT_FOUND,
+                FontError::ReadError(_) | FontError::PermissionDenied(_) | FontError::EnvOverride { .. } => {
+                    EGUI_CHINESE_FONT_READ_ERROR
+                }
+                FontError::InvalidFont { .. } => EGUI_CHINESE_FONT_INVALID_FONT,
+                FontError::UnsupportedPlatform => EGUI_CHINESE_FONT_UNSUPPORTED_PLATFORM,
+                _ => EGUI_CHINESE_FONT_OTHER_ERROR,
+            };
+            set_last_error(&err.to_string());
+            status
+        }
+        Err(_) => {
+            set_last_error("panicked while setting up fonts");
+            EGUI_CHINESE_FONT_PANIC
+        }
+    }
+}
+
+fn invalid_argument(message: &str) -> c_int {
+    set_last_error(message);
+    EGUI_CHINESE_FONT_INVALID_ARGUMENT
+}
+
+fn set_last_error(message: &str) {
+    // Interior NUL bytes can't be represented in a C string
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::chinese_font_config;
+
+    fn last_error() -> String {
+        // SAFETY: the message stays valid until the next call on this thread
+        unsafe { CStr::from_ptr(egui_chinese_font_last_error()) }.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn null_arguments_are_rejected() {
+        // SAFETY: null is allowed for both pointers
+        let status = unsafe { egui_chinese_font_setup(std::ptr::null()) };
+        assert_eq!(status, EGUI_CHINESE_FONT_INVALID_ARGUMENT);
+        assert_eq!(last_error(), "ctx is null");
+
+        let ctx = Context::default();
+        let handle = ContextHandle::create(&ctx);
+        // SAFETY: `handle` is live and `path` may be null
+        let status = unsafe { egui_chinese_font_setup_from_path(handle, std::ptr::null(), 0) };
+        assert_eq!(status, EGUI_CHINESE_FONT_INVALID_ARGUMENT);
+        assert_eq!(last_error(), "path is null");
+        // SAFETY: `handle` was not freed yet
+        unsafe { egui_chinese_font_context_free(handle) };
+    }
+
+    #[test]
+    fn setup_from_path_registers_the_font() {
+        let (path, _) = chinese_font_config("ffi-test");
+        let c_path = CString::new(path.to_str().unwrap()).unwrap();
+        let ctx = Context::default();
+        let handle = ContextHandle::create(&ctx);
+
+        // SAFETY: `handle` is live and `c_path` is NUL-terminated
+        let status = unsafe { egui_chinese_font_setup_from_path(handle, c_path.as_ptr(), 1) };
+        assert_eq!(status, EGUI_CHINESE_FONT_INVALID_FONT);
+        assert!(last_error().contains(&*path.to_string_lossy()));
+
+        // SAFETY: as above
+        let status = unsafe { egui_chinese_font_setup_from_path(handle, c_path.as_ptr(), 0) };
+        assert_eq!(status, EGUI_CHINESE_FONT_OK);
+
+        // The context outlives the freed handle
+        // SAFETY: `handle` was not freed yet
+        unsafe { egui_chinese_font_context_free(handle) };
+        let _ = ctx.run(Default::default(), |_| {});
+        assert!(ctx.fonts(|fonts| fonts.definitions().font_data.contains_key("chinese")));
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,415 @@
+//! Enumeration of the fonts installed on the system
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use ttf_parser::{cmap, name};
+
+use crate::coverage::{CHINESE_SAMPLE, MIN_CHINESE_COVERAGE};
+use crate::{faces, FontError};
+
+/// System-wide font directories, searched recursively
+#[cfg(target_os = "windows")]
+const SYSTEM_FONT_DIRS: &[&str] = &[r"C:\Windows\Fonts"];
+
+#[cfg(target_os = "macos")]
+const SYSTEM_FONT_DIRS: &[&str] = &["/System/Library/Fonts", "/Library/Fonts"];
+
+#[cfg(target_os = "ios")]
+const SYSTEM_FONT_DIRS: &[&str] = &["/System/Library/Fonts"];
+
+#[cfg(target_os = "linux")]
+const SYSTEM_FONT_DIRS: &[&str] = &["/usr/share/fonts", "/usr/local/share/fonts"];
+
+#[cfg(target_os = "android")]
+const SYSTEM_FONT_DIRS: &[&str] = &["/system/fonts", "/product/fonts"];
+
+#[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+const SYSTEM_FONT_DIRS: &[&str] = &["/usr/local/share/fonts", "/usr/pkg/share/fonts", "/usr/X11R7/lib/X11/fonts"];
+
+#[cfg(not(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd"
+)))]
+const SYSTEM_FONT_DIRS: &[&str] = &[];
+
+/// Characters that only exist in Simplified Chinese
+const SIMPLIFIED_SAMPLE: &str = "们这说为国会发来对见么学";
+
+/// The Traditional forms of [`SIMPLIFIED_SAMPLE`]
+const TRADITIONAL_SAMPLE: &str = "們這說為國會發來對見麼學";
+
+/// Directory levels searched below each font directory
+const MAX_DEPTH: usize = 8;
+
+/// Font files opened per scan, so huge font directories can't stall the caller
+const MAX_FONT_FILES: usize = 10_000;
+
+/// Faces read per collection
+const MAX_FACES: u32 = 64;
+
+/// Largest `name` or `cmap` table read, in bytes
+const MAX_TABLE_SIZE: u32 = 16 * 1024 * 1024;
+
+/// A Chinese face installed on the system, as listed by [`available_chinese_fonts`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InstalledFont {
+    /// English family name, e.g. `"Noto Sans CJK SC"`, or the file stem if the font has none
+    pub family: String,
+    /// English subfamily (style) name, e.g. `"Regular"`
+    pub subfamily: Option<String>,
+    /// The font file
+    pub path: PathBuf,
+    /// Index of the face within the file, as used by [`egui::FontData::index`]
+    pub index: u32,
+    /// Whether the face has glyphs for Simplified-only characters such as 们 and 这
+    pub simplified: bool,
+    /// Whether the face has glyphs for Traditional-only characters such as 們 and 這
+    pub traditional: bool,
+}
+
+/// A font file that looks like a CJK font, as listed by [`scan_system_fonts`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FontInfo {
+    /// The font file
+    pub path: PathBuf,
+    /// English family names of the faces in the file, without duplicates
+    pub families: Vec<String>,
+    /// Number of faces in the file, more than one for `.ttc` collections
+    pub face_count: u32,
+}
+
+/// Find the font files on the system that look like CJK fonts
+///
+/// Walks the system and per-user font directories:
+///
+/// * Windows: `C:\Windows\Fonts` and `%LOCALAPPDATA%\Microsoft\Windows\Fonts`
+/// * macOS: `/System/Library/Fonts`, `/Library/Fonts` and `~/Library/Fonts`
+/// * iOS: `/System/Library/Fonts`
+/// * Linux and the BSDs: `fonts` below `$XDG_DATA_DIRS` (e.g. `/usr/share/fonts`),
+///   `$XDG_DATA_HOME/fonts` and `~/.fonts`
+/// * Android: `/system/fonts` and `/product/fonts`
+///
+/// Each file's signature is checked, and it is listed if its file name or one
+/// of its family names contains a known CJK font identifier, such as
+/// `wqy-microhei` or `Noto Sans CJK SC`. Unlike [`available_chinese_fonts`],
+/// the character map is not checked, so Japanese and Korean fonts are listed
+/// too. The paths can be reordered and passed to
+/// [`PlatformFontProvider::with_candidates`](crate::PlatformFontProvider::with_candidates).
+///
+/// The list is sorted by path.
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::scan_system_fonts;
+///
+/// for font in scan_system_fonts() {
+///     println!("{} ({} faces): {}", font.path.display(), font.face_count, font.families.join(", "));
+/// }
+/// ```
+pub fn scan_system_fonts() -> Vec<FontInfo> {
+    let mut fonts: Vec<FontInfo> = font_files()
+        .into_iter()
+        .filter_map(|path| match read_font_info(&path) {
+            Ok(info) => info,
+            Err(err) => {
+                log!(debug, "{}: skipped ({})", path.display(), err);
+                None
+            }
+        })
+        .collect();
+
+    fonts.sort_by(|a, b| a.path.cmp(&b.path));
+    fonts
+}
+
+/// Describe the font file at `path` if its file or family names suggest a CJK font
+fn read_font_info(path: &Path) -> std::io::Result<Option<FontInfo>> {
+    let mut file = File::open(path)?;
+
+    let offsets = face_offsets(&mut file)?;
+    let mut families: Vec<String> = Vec::new();
+    for &offset in &offsets {
+        let Some(name_data) = read_table(&mut file, offset, b"name")? else {
+            continue;
+        };
+        let family = name::Table::parse(&name_data).and_then(|names| faces::names_family(names.names));
+        if let Some(family) = family.filter(|family| !families.contains(family)) {
+            families.push(family);
+        }
+    }
+
+    let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+    if !faces::is_cjk_name(file_name) && !families.iter().any(|family| faces::is_cjk_name(family)) {
+        return Ok(None);
+    }
+
+    Ok(Some(FontInfo {
+        path: path.to_path_buf(),
+        families,
+        face_count: offsets.len() as u32,
+    }))
+}
+
+/// List the Chinese fonts installed on the system, e.g. for a font picker
+///
+/// Searches the system and per-user font directories for `.ttf`, `.otf`,
+/// `.ttc` and `.otc` files and lists every face that covers common Chinese
+/// characters; OpenType fonts with CFF outlines, such as Source Han Sans, are
+/// handled like TrueType fonts.
+/// Only the table directory and the `name` and `cmap` tables are read, not
+/// the glyph data, and files that can't be read are skipped.
+///
+/// The list is sorted by family, subfamily and path. Pass an entry's `path`
+/// and `index` to [`setup_chinese_font_from_path`](crate::setup_chinese_font_from_path)
+/// to use it.
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::available_chinese_fonts;
+///
+/// for font in available_chinese_fonts() {
+///     let variants = match (font.simplified, font.traditional) {
+///         (true, true) => "简/繁",
+///         (true, false) => "简",
+///         (false, true) => "繁",
+///         (false, false) => "-",
+///     };
+///     println!("{} {:?} [{}] {}", font.family, font.subfamily, variants, font.path.display());
+/// }
+/// ```
+pub fn available_chinese_fonts() -> Vec<InstalledFont> {
+    let mut fonts = Vec::new();
+    for path in font_files() {
+        match read_chinese_faces(&path) {
+            Ok(faces) => fonts.extend(faces),
+            Err(err) => log!(debug, "{}: skipped ({})", path.display(), err),
+        }
+    }
+
+    fonts.sort_by(|a, b| {
+        (&a.family, &a.subfamily, &a.path, a.index).cmp(&(&b.family, &b.subfamily, &b.path, b.index))
+    });
+    fonts
+}
+
+/// The font directories of this platform, system-wide first
+fn font_dirs() -> Vec<PathBuf> {
+    #[allow(unused_mut)]
+    let mut dirs: Vec<PathBuf> = SYSTEM_FONT_DIRS.iter().map(PathBuf::from).collect();
+
+    // Other data directories, e.g. Flatpak exports or /opt prefixes
+    #[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+    if let Some(data_dirs) = std::env::var_os("XDG_DATA_DIRS") {
+        dirs.extend(
+            std::env::split_paths(&data_dirs)
+                .filter(|dir| !dir.as_os_str().is_empty())
+                .map(|dir| dir.join("fonts")),
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    dirs.extend(crate::sandbox::packaged_font_dirs());
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "windows",
+        target_os = "macos",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd"
+    ))]
+    dirs.extend(crate::user_fonts::user_font_dirs());
+
+    dirs
+}
+
+/// Every font file in the font directories, each file once even if linked from several places
+fn font_files() -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut seen = HashSet::new();
+    for dir in font_dirs() {
+        collect_font_files(&dir, 0, &mut seen, &mut files);
+    }
+    files
+}
+
+fn collect_font_files(dir: &Path, depth: usize, seen: &mut HashSet<PathBuf>, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        if files.len() >= MAX_FONT_FILES {
+            log!(warn, "more than {} font files installed; skipping the rest", MAX_FONT_FILES);
+            return;
+        }
+
+        let path = entry.path();
+        if path.is_dir() {
+            if depth < MAX_DEPTH {
+                collect_font_files(&path, depth + 1, seen, files);
+            }
+        } else if faces::is_font_file(&path) {
+            let canonical = std::fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+            if seen.insert(canonical) {
+                files.push(path);
+            }
+        }
+    }
+}
+
+/// Read the faces of the font file at `path` that cover common Chinese characters
+fn read_chinese_faces(path: &Path) -> std::io::Result<Vec<InstalledFont>> {
+    let mut file = File::open(path)?;
+
+    let mut fonts = Vec::new();
+    for (index, offset) in face_offsets(&mut file)?.into_iter().enumerate() {
+        let name_data = read_table(&mut file, offset, b"name")?;
+        let cmap_data = read_table(&mut file, offset, b"cmap")?;
+        let (Some(name_data), Some(cmap_data)) = (name_data, cmap_data) else {
+            continue;
+        };
+        let (Some(names), Some(cmap)) = (name::Table::parse(&name_data), cmap::Table::parse(&cmap_data)) else {
+            continue;
+        };
+
+        let share = |sample: &str| sample_share(&cmap, sample);
+        if share(CHINESE_SAMPLE) < MIN_CHINESE_COVERAGE {
+            continue;
+        }
+
+        let family = faces::names_family(names.names)
+            .or_else(|| path.file_stem().map(|stem| stem.to_string_lossy().into_owned()))
+            .unwrap_or_default();
+        fonts.push(InstalledFont {
+            family,
+            subfamily: faces::names_subfamily(names.names),
+            path: path.to_path_buf(),
+            index: index as u32,
+            simplified: share(SIMPLIFIED_SAMPLE) == 1.0,
+            traditional: share(TRADITIONAL_SAMPLE) == 1.0,
+        });
+    }
+
+    Ok(fonts)
+}
+
+/// Whether the font file at `path` can display Chinese
+///
+/// The same check the loader applies to candidate fonts: a face must have
+/// glyphs for at least [`MIN_CHINESE_COVERAGE`] of [`CHINESE_SAMPLE`]. Only
+/// the table directory and `cmap` table of each face are read, so this is
+/// cheap enough to run over whole font directories. A collection counts if
+/// any of its faces does.
+///
+/// # Returns
+/// * `Ok(true)` if a face covers common Chinese characters
+/// * `Ok(false)` for a valid font without them, e.g. a Latin or emoji font
+/// * `Err(FontError::InvalidFont)` if the file is not a font
+/// * `Err(FontError)` if the file can't be read
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::{font_has_chinese_coverage, FontError};
+///
+/// # let path = std::env::temp_dir().join("egui-chinese-font-coverage-example.ttf");
+/// # std::fs::write(&path, &*egui::FontDefinitions::default().font_data["Ubuntu-Light"].font)?;
+/// // A Latin font
+/// assert!(!font_has_chinese_coverage(&path)?);
+///
+/// # let path = std::env::temp_dir().join("egui-chinese-font-coverage-example.txt");
+/// # std::fs::write(&path, "not a font")?;
+/// assert!(matches!(font_has_chinese_coverage(&path), Err(FontError::InvalidFont { .. })));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn font_has_chinese_coverage(path: impl AsRef<Path>) -> Result<bool, FontError> {
+    let path = path.as_ref();
+    let covers_chinese = || -> std::io::Result<bool> {
+        let mut file = File::open(path)?;
+        for offset in face_offsets(&mut file)? {
+            let Some(cmap_data) = read_table(&mut file, offset, b"cmap")? else {
+                continue;
+            };
+            if cmap::Table::parse(&cmap_data).is_some_and(|cmap| sample_share(&cmap, CHINESE_SAMPLE) >= MIN_CHINESE_COVERAGE)
+            {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    };
+
+    covers_chinese().map_err(|err| match err.kind() {
+        std::io::ErrorKind::InvalidData | std::io::ErrorKind::UnexpectedEof => FontError::InvalidFont {
+            path: Some(path.to_path_buf()),
+            reason: format!("not a valid font file ({})", err),
+        },
+        _ => FontError::from_read(path, err),
+    })
+}
+
+/// Offsets of the table directories of the faces in `file`
+fn face_offsets(file: &mut File) -> std::io::Result<Vec<u64>> {
+    let header = read_at(file, 0, 12)?;
+    match &header[..4] {
+        b"ttcf" => {
+            let count = read_u32(&header, 8).min(MAX_FACES);
+            let offsets = read_at(file, 12, count as usize * 4)?;
+            Ok(offsets.chunks_exact(4).map(|offset| u64::from(read_u32(offset, 0))).collect())
+        }
+        [0x00, 0x01, 0x00, 0x00] | b"OTTO" | b"true" => Ok(vec![0]),
+        _ => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "not a font file")),
+    }
+}
+
+/// Read the table tagged `tag` of the face whose table directory is at `offset`
+fn read_table(file: &mut File, offset: u64, tag: &[u8; 4]) -> std::io::Result<Option<Vec<u8>>> {
+    let directory = read_at(file, offset, 12)?;
+    let count = u16::from_be_bytes([directory[4], directory[5]]);
+    let records = read_at(file, offset + 12, usize::from(count) * 16)?;
+
+    let Some(record) = records.chunks_exact(16).find(|record| &record[..4] == tag) else {
+        return Ok(None);
+    };
+    let length = read_u32(record, 12);
+    if length > MAX_TABLE_SIZE {
+        return Ok(None);
+    }
+    read_at(file, u64::from(read_u32(record, 8)), length as usize).map(Some)
+}
+
+fn read_at(file: &mut File, offset: u64, len: usize) -> std::io::Result<Vec<u8>> {
+    file.seek(SeekFrom::Start(offset))?;
+    let mut data = vec![0; len];
+    file.read_exact(&mut data)?;
+    Ok(data)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+/// Share of the characters of `sample` that have a glyph in `cmap`
+fn sample_share(cmap: &cmap::Table<'_>, sample: &str) -> f32 {
+    let covered = sample.chars().filter(|&c| has_glyph(cmap, c)).count();
+    covered as f32 / sample.chars().count() as f32
+}
+
+fn has_glyph(cmap: &cmap::Table<'_>, c: char) -> bool {
+    cmap.subtables
+        .into_iter()
+        .any(|subtable| subtable.is_unicode() && subtable.glyph_index(u32::from(c)).is_some())
+}
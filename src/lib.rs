@@ -13,237 +13,2701 @@
 //! let ctx = egui::Context::default();
 //! setup_chinese_fonts(&ctx);
 //! ```
+//!
+//! Or as a method on the context, via the [`prelude`]:
+//!
+//! ```rust,no_run
+//! use egui_chinese_font::prelude::*;
+//!
+//! let ctx = egui::Context::default();
+//! ctx.setup_chinese_fonts();
+//! ```
+
+use std::borrow::Cow;
+use std::sync::{Arc, Mutex};
+
+use egui::{Context, FontData, FontDefinitions, FontFamily, FontTweak};
 
-use std::sync::Arc;
+use platform::FontQuery;
 
-use egui::{Context, FontData, FontDefinitions, FontFamily};
+/// Log through the `log` crate when the `logging` feature is enabled
+///
+/// Without the feature the arguments are still type-checked, but nothing is
+/// formatted or emitted.
+macro_rules! log {
+    ($level:ident, $($arg:tt)+) => {{
+        #[cfg(feature = "logging")]
+        ::log::$level!($($arg)+);
+        #[cfg(not(feature = "logging"))]
+        let _ = format_args!($($arg)+);
+    }};
+}
+
+mod cache;
+mod chain;
+mod cjk;
+mod coverage;
+#[cfg(feature = "async")]
+mod async_load;
+#[cfg(feature = "bundled")]
+mod bundled;
+#[cfg(feature = "download")]
+mod download;
+#[cfg(feature = "eframe")]
+mod eframe_support;
+#[cfg(feature = "embedded-noto")]
+mod embedded;
+mod emoji;
+mod ensure;
+mod ext;
+mod faces;
+mod families;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod installed;
+#[cfg(all(feature = "fc-match", any(target_os = "linux", target_os = "freebsd", target_os = "openbsd", target_os = "netbsd")))]
+mod fc_match;
+mod mono;
+mod once;
+mod path_cache;
+mod platform;
+mod provider;
+mod sandbox;
+mod stats;
+mod style;
+#[cfg(feature = "subset")]
+mod subset;
+#[cfg(feature = "system-query")]
+mod system_query;
+#[cfg(any(
+    target_os = "linux",
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd"
+))]
+mod user_fonts;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+mod tweaks;
+mod variant;
+#[cfg(feature = "watch")]
+mod watch;
+mod weights;
+
+#[cfg(feature = "async")]
+pub use async_load::{setup_chinese_fonts_async, setup_chinese_fonts_async_with_config, FontLoadFuture};
+pub use cache::{clear_font_cache, load_chinese_font_shared};
+pub use chain::{setup_font_chain, FontChain, FontSource};
+pub use cjk::{detect_cjk_language, setup_cjk_fonts, setup_cjk_fonts_with_config, CjkLanguage};
+pub use coverage::{coverage_report, font_covers, CoverageReport, CHINESE_SAMPLE, MIN_CHINESE_COVERAGE};
+#[cfg(feature = "download")]
+pub use download::{
+    setup_chinese_fonts_or_download, setup_chinese_fonts_or_download_with_config, FontDownload, NOTO_SANS_SC_SHA256,
+    NOTO_SANS_SC_URL,
+};
+#[cfg(all(feature = "download", feature = "async"))]
+pub use download::setup_chinese_fonts_or_download_async;
+#[cfg(feature = "eframe")]
+pub use eframe_support::{get_loaded_font_name, setup_chinese_fonts_from_cc, with_chinese_fonts};
+pub use ensure::{ensure_chinese_fonts, ensure_chinese_fonts_with_config, EnsureOutcome};
+pub use ext::ChineseFontExt;
+pub use faces::{convert_woff_hint, extract_ttc_face, list_ttc_faces, FaceInfo};
+pub use families::{register_chinese_families, setup_multiple_chinese_fonts, FontPreference};
+pub use installed::{available_chinese_fonts, font_has_chinese_coverage, scan_system_fonts, FontInfo, InstalledFont};
+pub use mono::MonospacePolicy;
+pub use once::setup_chinese_fonts_once;
+#[cfg(feature = "path-cache")]
+pub use path_cache::default_font_path_cache_file;
+pub use path_cache::{clear_font_path_cache, setup_chinese_fonts_cached, setup_chinese_fonts_cached_with_config};
+pub use platform::{
+    FontFormat, FontPathInfo, FoundFont, ANDROID_FONT_CANDIDATES, BSD_FONT_CANDIDATES, IOS_FONT_CANDIDATES,
+    LINUX_FONT_CANDIDATES, MACOS_FONT_CANDIDATES, PLATFORM_FONT_CANDIDATES, WINDOWS_FONT_CANDIDATES,
+};
+pub use provider::{FontProvider, PlatformFontProvider};
+pub use sandbox::Sandbox;
+pub use stats::{setup_chinese_fonts_with_stats, AttemptOutcome, AttemptStats, LoadStats};
+pub use style::ChineseStyle;
+#[cfg(feature = "subset")]
+pub use subset::subset_font;
+pub use tweaks::recommended_tweak;
+#[cfg(feature = "embedded-noto")]
+pub use embedded::{setup_embedded_chinese_font, NOTO_SANS_SC};
+#[cfg(any(
+    target_os = "linux",
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd"
+))]
+pub use user_fonts::scan_user_fonts;
+pub use variant::{detect_chinese_variant, ChineseVariant, VariantPreference};
+#[cfg(feature = "watch")]
+pub use watch::{watch_chinese_font, FontWatcher};
+pub use weights::{
+    chinese_bold_family, chinese_light_family, setup_chinese_font_weights, setup_chinese_font_weights_from,
+    setup_chinese_font_weights_with_config, FontWeight,
+};
+
+/// The extension trait and the types its methods take and return
+///
+/// ```rust
+/// use egui_chinese_font::prelude::*;
+/// ```
+pub mod prelude {
+    pub use crate::{ChineseFontExt, ChineseVariant, FontConfig, FontError, FontOrigin, LoadedFont, VariantPreference};
+}
 
 /// Error type for font loading operations
+///
+/// Errors can be cloned, compared and hashed, e.g. to remember them per
+/// font in a `HashMap` or to compare them in tests. The I/O errors inside
+/// compare equal if their [`std::io::ErrorKind`]s are equal, and a clone
+/// keeps the kind and message of the original I/O error.
+///
+/// # Example
+///
+/// ```rust
+/// use std::collections::HashSet;
+/// use std::io::{Error, ErrorKind};
+/// use egui_chinese_font::FontError;
+///
+/// let error = FontError::ReadError(Error::new(ErrorKind::NotFound, "msyh.ttc"));
+/// let copy = error.clone();
+/// assert_eq!(error, copy);
+/// assert_eq!(copy.to_string(), "Failed to read font file: msyh.ttc");
+/// assert_ne!(error, FontError::ReadError(Error::new(ErrorKind::Interrupted, "msyh.ttc")));
+///
+/// let seen: HashSet<FontError> = [error, copy, FontError::UnsupportedPlatform].into_iter().collect();
+/// assert_eq!(seen.len(), 2);
+/// ```
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum FontError {
-    /// Font file not found
-    NotFound(String),
+    /// No suitable font was found
+    NotFound {
+        /// What was being looked for, e.g. `"No Chinese font found on Linux"`
+        message: String,
+        /// Every candidate path that was tried, and why it wasn't used
+        attempts: Vec<FontAttempt>,
+    },
     /// Failed to read font file
     ReadError(std::io::Error),
+    /// Not allowed to read the font file at this path
+    PermissionDenied(String),
     /// Platform not supported
     UnsupportedPlatform,
+    /// Failed to download a fallback font (e.g. the network is unreachable)
+    Download(String),
+    /// Downloaded font did not match the expected SHA-256 checksum
+    ChecksumMismatch {
+        /// Checksum that was expected
+        expected: String,
+        /// Checksum of the downloaded data
+        actual: String,
+    },
+    /// Failed to create or write the font cache directory
+    CacheDir(std::io::Error),
+    /// Font data failed validation, e.g. a truncated or corrupted file
+    InvalidFont {
+        /// Path of the rejected file, if the data came from disk
+        path: Option<std::path::PathBuf>,
+        /// Why the data was rejected
+        reason: String,
+    },
+    /// No installed font has the requested [`ChineseStyle`]
+    StyleNotFound(ChineseStyle),
+    /// The font file named by the [`FONT_PATH_ENV`] environment variable could not be read
+    EnvOverride {
+        /// Path the environment variable points to
+        path: std::path::PathBuf,
+        /// Underlying I/O error
+        error: std::io::Error,
+    },
+    /// Every font file that exists took longer than [`FontConfig::with_timeout`] to read
+    #[cfg(feature = "timeout")]
+    Timeout(std::time::Duration),
 }
 
 impl std::fmt::Display for FontError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            FontError::NotFound(path) => write!(f, "Font file not found: {}", path),
+            FontError::NotFound { message, attempts } if attempts.is_empty() => write!(f, "{}", message),
+            FontError::NotFound { message, attempts } => {
+                write!(f, "{}; tried:", message)?;
+                for attempt in attempts {
+                    write!(f, "\n  {}", attempt)?;
+                }
+                Ok(())
+            }
             FontError::ReadError(err) => write!(f, "Failed to read font file: {}", err),
+            FontError::PermissionDenied(path) => write!(f, "Permission denied reading font file: {}", path),
+            #[cfg(feature = "timeout")]
+            FontError::Timeout(timeout) => write!(f, "Reading the font files took longer than {:?}", timeout),
             FontError::UnsupportedPlatform => write!(f, "Platform not supported"),
+            FontError::Download(msg) => write!(f, "Failed to download font: {}", msg),
+            FontError::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "Font checksum mismatch: expected {}, got {}",
+                expected, actual
+            ),
+            FontError::CacheDir(err) => write!(f, "Failed to write font cache directory: {}", err),
+            FontError::InvalidFont { path: Some(path), reason } => {
+                write!(f, "Invalid font file {}: {}", path.display(), reason)
+            }
+            FontError::InvalidFont { path: None, reason } => write!(f, "Invalid font data: {}", reason),
+            FontError::StyleNotFound(style) => write!(f, "No Chinese font with {:?} style found", style),
+            FontError::EnvOverride { path, error } => write!(
+                f,
+                "Failed to read font file {} from {}: {}",
+                path.display(),
+                FONT_PATH_ENV,
+                error
+            ),
+        }
+    }
+}
+
+impl Clone for FontError {
+    fn clone(&self) -> Self {
+        match self {
+            FontError::NotFound { message, attempts } => FontError::NotFound {
+                message: message.clone(),
+                attempts: attempts.clone(),
+            },
+            FontError::ReadError(err) => FontError::ReadError(clone_io_error(err)),
+            FontError::PermissionDenied(path) => FontError::PermissionDenied(path.clone()),
+            FontError::UnsupportedPlatform => FontError::UnsupportedPlatform,
+            FontError::Download(msg) => FontError::Download(msg.clone()),
+            FontError::ChecksumMismatch { expected, actual } => FontError::ChecksumMismatch {
+                expected: expected.clone(),
+                actual: actual.clone(),
+            },
+            FontError::CacheDir(err) => FontError::CacheDir(clone_io_error(err)),
+            FontError::InvalidFont { path, reason } => FontError::InvalidFont {
+                path: path.clone(),
+                reason: reason.clone(),
+            },
+            FontError::StyleNotFound(style) => FontError::StyleNotFound(*style),
+            FontError::EnvOverride { path, error } => FontError::EnvOverride {
+                path: path.clone(),
+                error: clone_io_error(error),
+            },
+            #[cfg(feature = "timeout")]
+            FontError::Timeout(timeout) => FontError::Timeout(*timeout),
+        }
+    }
+}
+
+impl PartialEq for FontError {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                FontError::NotFound { message, attempts },
+                FontError::NotFound {
+                    message: other_message,
+                    attempts: other_attempts,
+                },
+            ) => message == other_message && attempts == other_attempts,
+            (FontError::ReadError(err), FontError::ReadError(other)) | (FontError::CacheDir(err), FontError::CacheDir(other)) => {
+                err.kind() == other.kind()
+            }
+            (FontError::PermissionDenied(path), FontError::PermissionDenied(other)) => path == other,
+            (FontError::UnsupportedPlatform, FontError::UnsupportedPlatform) => true,
+            (FontError::Download(msg), FontError::Download(other)) => msg == other,
+            (
+                FontError::ChecksumMismatch { expected, actual },
+                FontError::ChecksumMismatch {
+                    expected: other_expected,
+                    actual: other_actual,
+                },
+            ) => expected == other_expected && actual == other_actual,
+            (
+                FontError::InvalidFont { path, reason },
+                FontError::InvalidFont {
+                    path: other_path,
+                    reason: other_reason,
+                },
+            ) => path == other_path && reason == other_reason,
+            (FontError::StyleNotFound(style), FontError::StyleNotFound(other)) => style == other,
+            (
+                FontError::EnvOverride { path, error },
+                FontError::EnvOverride {
+                    path: other_path,
+                    error: other_error,
+                },
+            ) => path == other_path && error.kind() == other_error.kind(),
+            #[cfg(feature = "timeout")]
+            (FontError::Timeout(timeout), FontError::Timeout(other)) => timeout == other,
+            _ => false,
         }
     }
 }
 
-impl std::error::Error for FontError {}
+impl Eq for FontError {}
 
-/// Setup Chinese fonts for egui context
+impl std::hash::Hash for FontError {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            FontError::NotFound { message, attempts } => (message, attempts).hash(state),
+            FontError::ReadError(err) | FontError::CacheDir(err) => err.kind().hash(state),
+            FontError::PermissionDenied(text) | FontError::Download(text) => text.hash(state),
+            FontError::UnsupportedPlatform => {}
+            FontError::ChecksumMismatch { expected, actual } => (expected, actual).hash(state),
+            FontError::InvalidFont { path, reason } => (path, reason).hash(state),
+            FontError::StyleNotFound(style) => style.hash(state),
+            FontError::EnvOverride { path, error } => (path, error.kind()).hash(state),
+            #[cfg(feature = "timeout")]
+            FontError::Timeout(timeout) => timeout.hash(state),
+        }
+    }
+}
+
+/// Copy an I/O error, keeping its kind and message
+fn clone_io_error(err: &std::io::Error) -> std::io::Error {
+    std::io::Error::new(err.kind(), err.to_string())
+}
+
+impl std::error::Error for FontError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FontError::ReadError(err) | FontError::CacheDir(err) | FontError::EnvOverride { error: err, .. } => Some(err),
+            // The first candidate that existed but couldn't be read
+            FontError::NotFound { attempts, .. } => attempts.iter().find_map(|attempt| match &attempt.reason {
+                RejectReason::ReadError(err) => Some(err as &(dyn std::error::Error + 'static)),
+                _ => None,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl FontError {
+    /// A not-found error without any attempted paths
+    pub(crate) fn not_found(message: impl Into<String>) -> Self {
+        FontError::NotFound {
+            message: message.into(),
+            attempts: Vec::new(),
+        }
+    }
+
+    /// Classify an error from reading the font file at `path`
+    pub(crate) fn from_read(path: &std::path::Path, err: std::io::Error) -> Self {
+        if err.kind() == std::io::ErrorKind::PermissionDenied {
+            FontError::PermissionDenied(path.display().to_string())
+        } else {
+            FontError::ReadError(err)
+        }
+    }
+}
+
+/// A candidate font file that was tried and not used, listed in [`FontError::NotFound`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FontAttempt {
+    /// The candidate file
+    pub path: std::path::PathBuf,
+    /// Why it wasn't used
+    pub reason: RejectReason,
+}
+
+impl std::fmt::Display for FontAttempt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.reason)
+    }
+}
+
+/// Why a candidate font file wasn't used
 ///
-/// This function will attempt to load system Chinese fonts and configure them
-/// for use with the provided egui context.
+/// # Example
 ///
-/// # Arguments
-/// * `ctx` - The egui context to configure
+/// ```rust
+/// use egui_chinese_font::{ChineseVariant, FontError, FontProvider, PlatformFontProvider, RejectReason};
 ///
-/// # Returns
-/// * `Ok(())` if fonts were successfully loaded
-/// * `Err(FontError)` if font loading failed
-pub fn setup_chinese_fonts(ctx: &Context) -> Result<(), FontError> {
-    let mut fonts = FontDefinitions::default();
+/// # let bitmap_only = std::env::temp_dir().join("egui-chinese-font-bitmap-only.ttf");
+/// # let mut bytes = egui::FontDefinitions::default().font_data["Ubuntu-Light"].font.to_vec();
+/// # // Hide the outlines by renaming the `glyf` table in the table directory
+/// # let glyf = bytes.windows(4).position(|tag| tag == b"glyf").unwrap();
+/// # bytes[glyf..glyf + 4].copy_from_slice(b"glyg");
+/// # std::fs::write(&bitmap_only, bytes)?;
+/// let provider = PlatformFontProvider::new(ChineseVariant::Simplified).with_candidates(vec![
+///     "/nonexistent/NotoSansSC-Regular.otf".to_string(),
+///     concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml").to_string(),
+///     bitmap_only.display().to_string(),
+/// ]);
+/// match provider.load() {
+///     Err(FontError::NotFound { attempts, .. }) => {
+///         assert!(matches!(attempts[0].reason, RejectReason::Missing));
+///         assert!(matches!(attempts[1].reason, RejectReason::InvalidFont(_)));
+///         assert!(matches!(attempts[2].reason, RejectReason::BitmapOnly));
+///     }
+///     other => panic!("unexpected result: {:?}", other.map(|_| ())),
+/// }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum RejectReason {
+    /// The file doesn't exist
+    Missing,
+    /// The file exists, but the process isn't allowed to read it, e.g. inside a Flatpak or Snap sandbox
+    PermissionDenied,
+    /// Reading the file failed otherwise
+    ReadError(std::io::Error),
+    /// The file is not a usable font, e.g. truncated or in an unsupported format
+    InvalidFont(String),
+    /// The font lacks common Chinese characters or the required characters
+    InsufficientCoverage(String),
+    /// Reading the file took too long, see [`FontConfig::with_timeout`]
+    TimedOut,
+    /// The font has only embedded bitmaps and no outline (`glyf` or `CFF`) glyphs, which egui can't draw
+    BitmapOnly,
+}
 
-    // Try to load Chinese fonts based on platform
-    let chinese_font_data = Arc::new(load_chinese_font()?);
+impl Clone for RejectReason {
+    fn clone(&self) -> Self {
+        match self {
+            RejectReason::Missing => RejectReason::Missing,
+            RejectReason::PermissionDenied => RejectReason::PermissionDenied,
+            RejectReason::ReadError(err) => RejectReason::ReadError(clone_io_error(err)),
+            RejectReason::InvalidFont(reason) => RejectReason::InvalidFont(reason.clone()),
+            RejectReason::InsufficientCoverage(reason) => RejectReason::InsufficientCoverage(reason.clone()),
+            RejectReason::TimedOut => RejectReason::TimedOut,
+            RejectReason::BitmapOnly => RejectReason::BitmapOnly,
+        }
+    }
+}
+
+/// Read errors compare equal if their [`std::io::ErrorKind`]s are equal
+impl PartialEq for RejectReason {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (RejectReason::ReadError(err), RejectReason::ReadError(other)) => err.kind() == other.kind(),
+            (RejectReason::InvalidFont(reason), RejectReason::InvalidFont(other))
+            | (RejectReason::InsufficientCoverage(reason), RejectReason::InsufficientCoverage(other)) => reason == other,
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+}
+
+impl Eq for RejectReason {}
+
+impl std::hash::Hash for RejectReason {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            RejectReason::ReadError(err) => err.kind().hash(state),
+            RejectReason::InvalidFont(reason) | RejectReason::InsufficientCoverage(reason) => reason.hash(state),
+            _ => {}
+        }
+    }
+}
+
+impl RejectReason {
+    /// Classify an error from reading a candidate file
+    pub(crate) fn from_read(err: std::io::Error) -> Self {
+        match err.kind() {
+            std::io::ErrorKind::NotFound => RejectReason::Missing,
+            std::io::ErrorKind::PermissionDenied => RejectReason::PermissionDenied,
+            std::io::ErrorKind::TimedOut => RejectReason::TimedOut,
+            _ => RejectReason::ReadError(err),
+        }
+    }
+}
+
+impl std::fmt::Display for RejectReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RejectReason::Missing => write!(f, "not found"),
+            RejectReason::PermissionDenied => write!(f, "permission denied"),
+            RejectReason::ReadError(err) => write!(f, "read failed ({})", err),
+            RejectReason::TimedOut => write!(f, "timed out"),
+            RejectReason::BitmapOnly => write!(f, "font has only bitmap glyphs"),
+            RejectReason::InvalidFont(reason) | RejectReason::InsufficientCoverage(reason) => write!(f, "{}", reason),
+        }
+    }
+}
 
-    // Insert the Chinese font
-    fonts.font_data.insert(
-        "chinese".to_owned(),
-        chinese_font_data,
-    );
+/// Environment variable that forces a specific font file
+///
+/// When set, [`setup_chinese_fonts`] loads this file and skips platform
+/// detection entirely. If the file is missing, setup fails with
+/// [`FontError::EnvOverride`]; if it is not a valid font, with
+/// [`FontError::InvalidFont`]. Either way there is no silent fallback. This is
+/// useful for CI screenshots and containers where fonts are bind-mounted and
+/// rendering must be reproducible.
+pub const FONT_PATH_ENV: &str = "EGUI_CHINESE_FONT_PATH";
+
+/// Where a configured font came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FontOrigin {
+    /// A font installed on the system
+    System,
+    /// A previously downloaded font found in the cache directory
+    Cached(std::path::PathBuf),
+    /// A font that was just downloaded into the cache directory
+    Downloaded(std::path::PathBuf),
+    /// A font file supplied by the application
+    File(std::path::PathBuf),
+    /// Font data supplied by the application from memory
+    Memory,
+}
 
-    // Configure font families
-    fonts.families.entry(FontFamily::Proportional).or_default()
-        .insert(0, "chinese".to_owned());
-    fonts.families.entry(FontFamily::Monospace).or_default()
-        .insert(0, "chinese".to_owned());
+/// How the bytes of a loaded font are held in memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FontStorage {
+    /// Read from disk into the heap
+    Heap,
+    /// Memory-mapped from the font file, with the `mmap` feature
+    Mapped,
+    /// Static data, e.g. an embedded font or `include_bytes!`
+    Static,
+}
 
-    // Apply the font configuration
-    ctx.set_fonts(fonts);
+/// A font registered with an egui context
+///
+/// Keep it to register the font again with [`LoadedFont::reapply`] without
+/// searching or reading the file again.
+#[derive(Debug, Clone)]
+#[doc(alias = "ChineseFontHandle")]
+pub struct LoadedFont {
+    name: String,
+    origin: FontOrigin,
+    font_data: Arc<FontData>,
+    weight: FontWeight,
+    storage: FontStorage,
+    collection_size: Option<usize>,
+    placement: Placement,
+}
 
-    Ok(())
+/// Where a [`LoadedFont`] was placed among the font families
+#[derive(Debug, Clone)]
+enum Placement {
+    /// In the proportional and monospace families
+    Families(FontPriority, MonospacePolicy),
+    /// First in its own named family, e.g. a bold weight
+    Family(FontFamily),
 }
 
-/// Load Chinese font data from system
-fn load_chinese_font() -> Result<FontData, FontError> {
-    #[cfg(target_os = "windows")]
-    {
-        load_windows_chinese_font()
+impl LoadedFont {
+    fn new(name: impl Into<String>, origin: FontOrigin, font_data: Arc<FontData>) -> Self {
+        Self {
+            name: name.into(),
+            origin,
+            storage: cache::storage(&font_data),
+            font_data,
+            weight: FontWeight::Regular,
+            collection_size: None,
+            placement: Placement::Families(FontPriority::First, MonospacePolicy::SameAsProportional),
+        }
     }
 
-    #[cfg(target_os = "macos")]
-    {
-        load_macos_chinese_font()
+    fn with_weight(mut self, weight: FontWeight) -> Self {
+        self.weight = weight;
+        self
     }
 
-    #[cfg(target_os = "linux")]
-    {
-        load_linux_chinese_font()
+    #[cfg(feature = "download")]
+    fn with_origin(mut self, origin: FontOrigin) -> Self {
+        self.origin = origin;
+        self
     }
 
-    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
-    {
-        Err(FontError::UnsupportedPlatform)
+    fn placed_like(mut self, config: &FontConfig) -> Self {
+        self.placement = Placement::Families(config.priority, config.monospace);
+        self
     }
-}
 
-#[cfg(target_os = "windows")]
-fn load_windows_chinese_font() -> Result<FontData, FontError> {
-    // List of common Chinese font paths on Windows
-    let font_paths = [
-        r"C:\Windows\Fonts\msyh.ttc",      // Microsoft YaHei
-        r"C:\Windows\Fonts\msyhbd.ttc",    // Microsoft YaHei Bold
-        r"C:\Windows\Fonts\simsun.ttc",    // SimSun
-        r"C:\Windows\Fonts\simhei.ttf",    // SimHei
-        r"C:\Windows\Fonts\simkai.ttf",    // KaiTi
-        r"C:\Windows\Fonts\simfang.ttf",   // FangSong
-        r"C:\Windows\Fonts\msjh.ttc",      // Microsoft JhengHei (Traditional Chinese)
-        r"C:\Windows\Fonts\msjhbd.ttc",    // Microsoft JhengHei Bold
-        r"C:\Windows\Fonts\kaiu.ttf",      // DFKai-SB (Traditional Chinese)
-        r"C:\Windows\Fonts\mingliu.ttc",   // MingLiU (Traditional Chinese)
-    ];
+    fn placed_in(mut self, family: FontFamily) -> Self {
+        self.placement = Placement::Family(family);
+        self
+    }
+
+    /// Register the font with `ctx` again, as it was set up
+    ///
+    /// Fonts stay registered when the scale factor changes or the window
+    /// moves to another monitor, but replacing the context's fonts, e.g. with
+    /// [`Context::set_fonts`] to switch themes, drops them. This puts the font
+    /// back at its place, reusing the loaded font data, so no file is
+    /// searched or read. The context's other fonts are kept.
+    ///
+    /// A monospaced font found for [`MonospacePolicy::Dedicated`] is a
+    /// separate font and not reapplied. To reapply several fonts set up
+    /// together, such as a [`FontChain`], go from the last to the first.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use egui::{FontDefinitions, FontFamily};
+    /// use egui_chinese_font::prelude::*;
+    ///
+    /// # let path = std::env::temp_dir().join("egui-chinese-font-reapply-example.ttf");
+    /// # std::fs::write(&path, &*egui::FontDefinitions::default().font_data["Ubuntu-Light"].font)?;
+    /// # std::env::set_var(egui_chinese_font::FONT_PATH_ENV, &path);
+    /// let ctx = egui::Context::default();
+    /// let chinese_font = ctx.setup_chinese_fonts()?;
+    ///
+    /// // Rebuilding the definitions drops the Chinese font...
+    /// ctx.set_fonts(FontDefinitions::default());
+    /// // ...and reapplying restores it from memory
+    /// chinese_font.reapply(&ctx);
+    ///
+    /// let _ = ctx.run(Default::default(), |_| {});
+    /// let definitions = ctx.fonts(|fonts| fonts.definitions().clone());
+    /// assert_eq!(definitions.families[&FontFamily::Proportional][0], "chinese");
+    /// assert!(Arc::ptr_eq(&definitions.font_data["chinese"], chinese_font.font_data()));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn reapply(&self, ctx: &Context) {
+        let mut definitions = current_font_definitions(ctx);
+        self.insert_into(&mut definitions);
+        set_font_definitions(ctx, definitions);
+    }
 
-    for font_path in &font_paths {
-        if let Ok(font_data) = std::fs::read(font_path) {
-            return Ok(FontData::from_owned(font_data));
+    /// Add the font to `definitions` as it was set up, see [`LoadedFont::reapply`]
+    ///
+    /// Use it to include the font when building definitions from scratch.
+    pub fn insert_into(&self, definitions: &mut FontDefinitions) {
+        let font = (self.name.clone(), Arc::clone(&self.font_data));
+        match &self.placement {
+            Placement::Families(priority, monospace) => insert_fonts(definitions, vec![font], *priority, *monospace),
+            Placement::Family(family) => {
+                let proportional = definitions.families.get(&FontFamily::Proportional).cloned().unwrap_or_default();
+                definitions.font_data.insert(font.0, font.1);
+                let names = definitions.families.entry(family.clone()).or_insert(proportional);
+                names.retain(|name| *name != self.name);
+                names.insert(0, self.name.clone());
+            }
         }
     }
 
-    Err(FontError::NotFound("No Chinese font found on Windows".to_string()))
+    /// The key the font is registered under in [`FontDefinitions::font_data`]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Where the font came from
+    pub fn origin(&self) -> &FontOrigin {
+        &self.origin
+    }
+
+    /// The font data registered with the context
+    pub fn font_data(&self) -> &Arc<FontData> {
+        &self.font_data
+    }
+
+    /// The weight of the font, see [`setup_chinese_font_weights`]
+    pub fn weight(&self) -> FontWeight {
+        self.weight
+    }
+
+    /// Whether the font was memory-mapped or read into the heap
+    ///
+    /// With the `mmap` feature, fonts loaded from files are
+    /// [`FontStorage::Mapped`] unless mapping failed and the file was read
+    /// instead.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use egui_chinese_font::{setup_chinese_fonts_returning_definitions, FontStorage};
+    ///
+    /// # let path = std::env::temp_dir().join("egui-chinese-font-storage-example.ttf");
+    /// # std::fs::write(&path, &*egui::FontDefinitions::default().font_data["Ubuntu-Light"].font)?;
+    /// # std::env::set_var(egui_chinese_font::FONT_PATH_ENV, &path);
+    /// let (_, loaded) = setup_chinese_fonts_returning_definitions()?;
+    /// if cfg!(feature = "mmap") {
+    ///     assert_eq!(loaded.storage(), FontStorage::Mapped);
+    /// } else {
+    ///     assert_eq!(loaded.storage(), FontStorage::Heap);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn storage(&self) -> FontStorage {
+        self.storage
+    }
+
+    /// Size in bytes of the collection the font was extracted from
+    ///
+    /// Set when [`FontConfig::with_extract_single_face`] replaced a
+    /// collection with its selected face; the face itself takes
+    /// `font_data().font.len()` bytes. `None` if nothing was extracted.
+    pub fn collection_size(&self) -> Option<usize> {
+        self.collection_size
+    }
+
+    /// Characters of `text` this font has no glyph for
+    ///
+    /// Whitespace and control characters are ignored. Use this to warn about
+    /// text the font can't display, or to decide whether another fallback
+    /// font is needed. See [`font_covers`] for a full report.
+    pub fn missing_chars(&self, text: &str) -> Vec<char> {
+        font_covers(&self.font_data, text).missing
+    }
+}
+
+/// Where the Chinese font is placed among the fonts already registered
+///
+/// egui draws each character with the first font in the family that has a
+/// glyph for it.
+///
+/// # Example
+///
+/// ```rust
+/// use egui::FontFamily;
+/// use egui_chinese_font::{setup_chinese_fonts_with_config, FontConfig, FontPriority};
+/// # struct Provider;
+/// # impl egui_chinese_font::FontProvider for Provider {
+/// #     fn load(&self) -> Result<egui::FontData, egui_chinese_font::FontError> {
+/// #         Ok(egui::FontDefinitions::default().font_data["Ubuntu-Light"].as_ref().clone())
+/// #     }
+/// #     fn available_paths(&self) -> Vec<String> { Vec::new() }
+/// # }
+///
+/// let ctx = egui::Context::default();
+/// let config = FontConfig::new().with_priority(FontPriority::Fallback);
+/// # let config = config.with_provider(Box::new(Provider));
+/// setup_chinese_fonts_with_config(&ctx, &config)?;
+///
+/// let _ = ctx.run(Default::default(), |_| {});
+/// let families = ctx.fonts(|fonts| fonts.definitions().families.clone());
+/// assert_eq!(families[&FontFamily::Proportional][0], "Ubuntu-Light");
+/// assert_eq!(families[&FontFamily::Proportional].last().unwrap(), "chinese");
+/// # Ok::<(), egui_chinese_font::FontError>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FontPriority {
+    /// Put the Chinese font first, so it draws all text including ASCII
+    #[default]
+    #[doc(alias = "Primary")]
+    First,
+    /// Add the Chinese font last, so it only draws glyphs the earlier fonts lack
+    Fallback,
+}
+
+/// How [`FontConfig::with_search_paths`] combine with the platform's font paths
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::{find_chinese_font_with_config, FontConfig, FontError, PathMode};
+///
+/// let dir = std::env::temp_dir();
+/// let missing = dir.join("egui-chinese-font-path-mode-missing.ttf");
+/// let config = FontConfig::new().with_search_paths([&missing]).with_path_mode(PathMode::Replace);
+///
+/// // Only the given path was tried
+/// match find_chinese_font_with_config(&config) {
+///     Err(FontError::NotFound { attempts, .. }) => {
+///         assert_eq!(attempts.len(), 1);
+///         assert_eq!(attempts[0].path, missing);
+///     }
+///     // The `bundled` feature's compiled-in font steps in instead of failing
+///     #[cfg(feature = "bundled")]
+///     Ok(found) => assert!(found.path().is_none()),
+///     other => panic!("unexpected result: {:?}", other.map(|found| found.path().map(|path| path.to_owned()))),
+/// }
+///
+/// // A font without Chinese glyphs is only used as a last resort
+/// let latin = dir.join("egui-chinese-font-path-mode-latin.ttf");
+/// # std::fs::write(&latin, &*egui::FontDefinitions::default().font_data["Ubuntu-Light"].font)?;
+/// let config = FontConfig::new().with_search_paths([&latin]).with_path_mode(PathMode::Replace);
+/// let found = find_chinese_font_with_config(&config)?;
+/// #[cfg(not(feature = "bundled"))]
+/// assert_eq!(found.path(), Some(latin.as_path()));
+/// #[cfg(feature = "bundled")]
+/// assert!(found.path().is_none());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PathMode {
+    /// Try the search paths first, then the platform's paths
+    ///
+    /// The search paths also come before the OS font database and
+    /// fontconfig, enabled with the `system-query` and `fc-match` features.
+    ///
+    /// ```rust
+    /// # #[cfg(all(feature = "fc-match", target_os = "linux"))] {
+    /// use egui_chinese_font::{find_chinese_font_with_config, FontConfig};
+    ///
+    /// # // Ubuntu Light with a `cmap` mapping CJK punctuation, ideographs and full-width forms to one glyph
+    /// # fn chinese_font() -> Vec<u8> {
+    /// #     let mut font = egui::FontDefinitions::default().font_data["Ubuntu-Light"].font.to_vec();
+    /// #     let groups: [(u32, u32); 3] = [(0x3000, 0x303F), (0x4E00, 0x9FFF), (0xFF00, 0xFFEF)];
+    /// #     // One Windows Unicode full-repertoire subtable in format 13 (many-to-one)
+    /// #     let mut cmap = vec![0, 0, 0, 1, 0, 3, 0, 10, 0, 0, 0, 12, 0, 13, 0, 0];
+    /// #     for value in [16 + 12 * groups.len() as u32, 0, groups.len() as u32] {
+    /// #         cmap.extend(value.to_be_bytes());
+    /// #     }
+    /// #     for (start, end) in groups {
+    /// #         for value in [start, end, 3] {
+    /// #             cmap.extend(value.to_be_bytes());
+    /// #         }
+    /// #     }
+    /// #     let tables = u16::from_be_bytes([font[4], font[5]]) as usize;
+    /// #     let record = (0..tables).map(|i| 12 + 16 * i).find(|&record| &font[record..record + 4] == b"cmap").unwrap();
+    /// #     let offset = font.len() as u32;
+    /// #     font[record + 8..record + 12].copy_from_slice(&offset.to_be_bytes());
+    /// #     font[record + 12..record + 16].copy_from_slice(&(cmap.len() as u32).to_be_bytes());
+    /// #     font.extend(cmap);
+    /// #     font
+    /// # }
+    /// # let dir = std::env::temp_dir().join("egui-chinese-font-prepend-example");
+    /// # let system = dir.join("system");
+    /// # std::fs::create_dir_all(&system)?;
+    /// # std::fs::write(system.join("SystemChinese.ttf"), chinese_font())?;
+    /// # let conf = dir.join("fonts.conf");
+    /// # let cache = dir.join("cache");
+    /// # std::fs::write(&conf, format!("<fontconfig><dir>{}</dir><cachedir>{}</cachedir></fontconfig>", system.display(), cache.display()))?;
+    /// // fontconfig prefers an installed Chinese font
+    /// # std::env::set_var("FONTCONFIG_FILE", &conf);
+    /// let app_font = dir.join("AppChinese.ttf");
+    /// # std::fs::write(&app_font, chinese_font())?;
+    ///
+    /// // But the application's own font is used
+    /// let found = find_chinese_font_with_config(&FontConfig::new().with_search_paths([&app_font]))?;
+    /// assert_eq!(found.path(), Some(app_font.as_path()));
+    /// # }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[default]
+    Prepend,
+    /// Try the platform's paths first, and the search paths if none of them is usable
+    Append,
+    /// Only try the search paths
+    ///
+    /// The OS font database and fontconfig, enabled with the `system-query`
+    /// and `fc-match` features, are not asked either.
+    Replace,
+}
+
+/// Configuration for how the Chinese font is registered with egui
+///
+/// CJK fonts often render slightly too large, too small or off the baseline
+/// relative to egui's default Latin font. Fonts loaded from the system get a
+/// built-in [`recommended_tweak`] when one is known for their family:
+///
+/// | Font | `scale` | `y_offset_factor` |
+/// |------|---------|-------------------|
+/// | Microsoft YaHei / JhengHei | `1.0` | `-0.05` |
+/// | SimSun / MingLiU | `1.1` | `0.0` |
+/// | SimHei | `1.05` | `0.0` |
+/// | PingFang / Hiragino Sans GB | `0.95` | `-0.05` |
+/// | Noto Sans CJK / Source Han Sans | `1.12` | `-0.04` |
+/// | WenQuanYi | `0.95` | `-0.05` |
+///
+/// Use [`FontConfig::with_tweak`], or the individual
+/// [`FontConfig::with_scale`] and [`FontConfig::with_y_offset_factor`]
+/// setters, to override it.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use egui_chinese_font::{setup_chinese_fonts_with_config, FontConfig};
+///
+/// let ctx = egui::Context::default();
+/// let config = FontConfig::new().with_tweak(egui::FontTweak {
+///     scale: 0.9,
+///     y_offset_factor: -0.1,
+///     ..Default::default()
+/// });
+/// setup_chinese_fonts_with_config(&ctx, &config).unwrap();
+/// ```
+///
+/// With the `serde` feature, the configuration can be read from a settings
+/// file, e.g. the one in `examples/font-config.toml`.
+/// Absent fields keep their defaults and unknown fields are ignored, so
+/// older files keep working. The provider and [`FontConfig::on_load`]
+/// callback are not serialized.
+///
+/// ```rust
+/// # #[cfg(feature = "serde")] {
+/// use egui_chinese_font::{FontConfig, MonospacePolicy};
+///
+/// let config: FontConfig = toml::from_str(include_str!("../examples/font-config.toml")).unwrap();
+/// assert_eq!(config.monospace(), MonospacePolicy::Append);
+/// assert_eq!(config.tweak().map(|tweak| tweak.scale), Some(0.95));
+/// assert_eq!(config.tweak().map(|tweak| tweak.y_offset), Some(0.0));
+///
+/// let round_trip: FontConfig = toml::from_str(&toml::to_string(&config).unwrap()).unwrap();
+/// assert_eq!(toml::to_string(&round_trip).unwrap(), toml::to_string(&config).unwrap());
+///
+/// // JSON works the same way, with enums written by name
+/// let json = serde_json::to_string(&config).unwrap();
+/// assert!(json.contains(r#""monospace":"Append""#));
+/// let round_trip: FontConfig = serde_json::from_str(&json).unwrap();
+/// assert_eq!(serde_json::to_string(&round_trip).unwrap(), json);
+/// let config = config.with_style(egui_chinese_font::ChineseStyle::Song);
+/// assert!(serde_json::to_string(&config).unwrap().contains(r#""style":"Song""#));
+///
+/// let config: FontConfig = toml::from_str("variant = \"Traditional\"\nremoved_option = 1").unwrap();
+/// assert!(config.search_paths().is_empty());
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct FontConfig {
+    #[cfg_attr(feature = "serde", serde(with = "tweaks::optional_tweak"))]
+    tweak: Option<FontTweak>,
+    variant: VariantPreference,
+    face_index: Option<u32>,
+    extract_single_face: bool,
+    #[cfg(feature = "timeout")]
+    timeout: Option<std::time::Duration>,
+    font_name: Option<String>,
+    font_path: Option<std::path::PathBuf>,
+    search_paths: Vec<std::path::PathBuf>,
+    path_mode: PathMode,
+    #[cfg(feature = "subset")]
+    subset_ranges: Option<Vec<std::ops::RangeInclusive<char>>>,
+    required_chars: String,
+    style: Option<ChineseStyle>,
+    style_fallback: bool,
+    priority: FontPriority,
+    monospace: MonospacePolicy,
+    emoji_fallback: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    provider: Option<Arc<dyn FontProvider>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    on_load: Option<LoadCallback>,
+}
+
+/// Callback registered with [`FontConfig::on_load`]
+///
+/// The mutex lets callbacks that are only `Send` be shared with the async loader's thread.
+#[derive(Clone)]
+struct LoadCallback(Arc<Mutex<LoadCallbackFn>>);
+
+type LoadCallbackFn = dyn Fn(Result<&LoadedFont, &FontError>) + Send;
+
+impl std::fmt::Debug for LoadCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("LoadCallback")
+    }
 }
 
-#[cfg(target_os = "macos")]
-fn load_macos_chinese_font() -> Result<FontData, FontError> {
-    let font_paths = [
-        "/System/Library/Fonts/PingFang.ttc",           // PingFang SC
-        "/System/Library/Fonts/STHeiti Light.ttc",      // STHeiti
-        "/System/Library/Fonts/STHeiti Medium.ttc",
-        "/System/Library/Fonts/Hiragino Sans GB.ttc",   // Hiragino Sans GB
-        "/Library/Fonts/Arial Unicode.ttf",             // Arial Unicode MS
-        "/System/Library/Fonts/Apple LiGothic Medium.ttf", // Apple LiGothic (Traditional)
-    ];
+impl FontConfig {
+    /// Create a configuration with egui's default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the [`FontTweak`] applied to the loaded Chinese font, replacing the recommended one
+    pub fn with_tweak(mut self, tweak: FontTweak) -> Self {
+        self.tweak = Some(tweak);
+        self
+    }
+
+    /// Scale the Chinese font's glyphs by `scale`, keeping the rest of the tweak
+    ///
+    /// Values below `1.0` shrink full-width characters that look oversized next to ASCII.
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.tweak.get_or_insert_with(FontTweak::default).scale = scale;
+        self
+    }
+
+    /// Shift the Chinese font's glyphs down by this fraction of the font size
+    ///
+    /// Negative values move glyphs up, for fonts that sit below the Latin baseline.
+    pub fn with_y_offset_factor(mut self, y_offset_factor: f32) -> Self {
+        self.tweak.get_or_insert_with(FontTweak::default).y_offset_factor = y_offset_factor;
+        self
+    }
+
+    /// Shift the Chinese font's glyphs down by a fixed number of points
+    pub fn with_y_offset(mut self, y_offset: f32) -> Self {
+        self.tweak.get_or_insert_with(FontTweak::default).y_offset = y_offset;
+        self
+    }
+
+    /// The explicitly set [`FontTweak`], if any
+    ///
+    /// `None` means the [`recommended_tweak`] for the loaded font is used.
+    pub fn tweak(&self) -> Option<FontTweak> {
+        self.tweak
+    }
+
+    /// Set which Chinese variant to prefer when choosing a system font
+    ///
+    /// Defaults to [`VariantPreference::Auto`], which follows the system locale
+    /// (`LANGUAGE`, `LC_ALL`, `LC_MESSAGES` and `LANG` on Unix). With
+    /// [`VariantPreference::Traditional`] fonts such as Microsoft JhengHei,
+    /// MingLiU and the PingFang TC face are tried before Simplified ones.
+    pub fn with_variant(mut self, variant: VariantPreference) -> Self {
+        self.variant = variant;
+        self
+    }
+
+    /// The preferred Chinese variant
+    pub fn variant(&self) -> VariantPreference {
+        self.variant
+    }
+
+    /// Use a specific face of a font collection (`.ttc`)
+    ///
+    /// By default the face is chosen by matching face names against the
+    /// preferred variant, e.g. `PingFang TC` for Traditional Chinese. Use
+    /// [`list_ttc_faces`] to see which faces a file contains.
+    pub fn with_face_index(mut self, face_index: Option<u32>) -> Self {
+        self.face_index = face_index;
+        self
+    }
+
+    /// The explicitly selected face index, if any
+    pub fn face_index(&self) -> Option<u32> {
+        self.face_index
+    }
+
+    /// Keep only the selected face of a font collection in memory
+    ///
+    /// egui holds on to the whole file even though it draws with a single
+    /// face, e.g. all ~70 MB of `PingFang.ttc`. With this enabled the face
+    /// is copied into a standalone font with [`extract_ttc_face`] and the
+    /// collection is dropped instead of kept in the font cache;
+    /// [`LoadedFont::collection_size`] reports the size before extraction.
+    /// Extracting copies the face's tables once, so it only pays off for
+    /// collections with several large faces. Off by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use egui_chinese_font::{setup_chinese_fonts_returning_definitions_with_config, FontConfig};
+    ///
+    /// # let path = std::env::temp_dir().join("egui-chinese-font-extract-example.ttf");
+    /// # std::fs::write(&path, &*egui::FontDefinitions::default().font_data["Ubuntu-Light"].font)?;
+    /// # std::env::set_var(egui_chinese_font::FONT_PATH_ENV, &path);
+    /// let config = FontConfig::new().with_extract_single_face(true);
+    /// let (_, loaded) = setup_chinese_fonts_returning_definitions_with_config(&config)?;
+    /// if let Some(collection_size) = loaded.collection_size() {
+    ///     println!("kept {} of {} bytes", loaded.font_data().font.len(), collection_size);
+    /// }
+    /// assert_eq!(loaded.font_data().index, 0);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_extract_single_face(mut self, extract: bool) -> Self {
+        self.extract_single_face = extract;
+        self
+    }
+
+    /// Whether only the selected face of a collection is kept
+    pub fn extract_single_face(&self) -> bool {
+        self.extract_single_face
+    }
+
+    /// Register the font under `name` instead of `"chinese"`
+    ///
+    /// Use it when the application already registers a font named
+    /// `"chinese"`, or to keep fonts from several setup calls side by side.
+    /// A dedicated monospaced font is registered as `"<name>-mono"`.
+    /// [`remove_chinese_fonts`] only removes names starting with `"chinese"`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use egui_chinese_font::{setup_chinese_fonts_with_config, FontConfig};
+    ///
+    /// # let path = std::env::temp_dir().join("egui-chinese-font-name-example.ttf");
+    /// # std::fs::write(&path, &*egui::FontDefinitions::default().font_data["Ubuntu-Light"].font)?;
+    /// # std::env::set_var(egui_chinese_font::FONT_PATH_ENV, &path);
+    /// let ctx = egui::Context::default();
+    /// setup_chinese_fonts_with_config(&ctx, &FontConfig::new().with_font_name("zh-ui"))?;
+    /// setup_chinese_fonts_with_config(&ctx, &FontConfig::new().with_font_name("zh-docs"))?;
+    /// let _ = ctx.run(Default::default(), |_| {});
+    ///
+    /// let definitions = ctx.fonts(|fonts| fonts.definitions().clone());
+    /// assert!(definitions.font_data.contains_key("zh-ui"));
+    /// assert!(definitions.font_data.contains_key("zh-docs"));
+    /// assert!(!definitions.font_data.contains_key("chinese"));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_font_name(mut self, name: impl Into<String>) -> Self {
+        self.font_name = Some(name.into());
+        self
+    }
+
+    /// The name the font is registered under, `"chinese"` unless changed
+    pub fn font_name(&self) -> &str {
+        self.font_name.as_deref().unwrap_or("chinese")
+    }
+
+    /// Subset the font to the characters in `ranges` before registering it
+    ///
+    /// Keeps only the glyphs an application needs, e.g. ASCII and the
+    /// GB2312 characters on a kiosk, to save memory. This is lossy and off by
+    /// default: characters outside `ranges` are drawn with the next font in
+    /// the family, or not at all, and layout tables are dropped; see
+    /// [`subset_font`]. If subsetting fails, the whole font is used and a
+    /// warning is logged.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use egui_chinese_font::{setup_chinese_fonts_returning_definitions_with_config, FontConfig};
+    ///
+    /// # let path = std::env::temp_dir().join("egui-chinese-font-subset-example.ttf");
+    /// # let bytes = egui::FontDefinitions::default().font_data["Ubuntu-Light"].font.to_vec();
+    /// # std::fs::write(&path, &bytes)?;
+    /// # std::env::set_var(egui_chinese_font::FONT_PATH_ENV, &path);
+    /// let config = FontConfig::new().with_subset_ranges([
+    ///     ' '..='~',                // ASCII
+    ///     '\u{3000}'..='\u{303F}', // CJK punctuation
+    ///     '\u{4E00}'..='\u{9FFF}', // CJK Unified Ideographs
+    ///     '\u{FF00}'..='\u{FFEF}', // Full-width forms
+    /// ]);
+    /// let (_, loaded) = setup_chinese_fonts_returning_definitions_with_config(&config)?;
+    /// assert!(loaded.font_data().font.len() < bytes.len());
+    /// assert!(loaded.missing_chars("Hello").is_empty());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "subset")]
+    #[doc(alias = "subset_to_ranges")]
+    pub fn with_subset_ranges(mut self, ranges: impl IntoIterator<Item = std::ops::RangeInclusive<char>>) -> Self {
+        self.subset_ranges = Some(ranges.into_iter().collect());
+        self
+    }
+
+    /// Subset the font to the inclusive `(first, last)` character ranges in `ranges`
+    ///
+    /// Like [`FontConfig::with_subset_ranges`], for ranges kept as pairs,
+    /// e.g. in a constant table.
+    ///
+    /// ```rust
+    /// use egui_chinese_font::FontConfig;
+    ///
+    /// const CHARSET: &[(char, char)] = &[(' ', '~'), ('\u{4E00}', '\u{9FFF}')];
+    /// let config = FontConfig::new().with_charset(CHARSET);
+    /// assert_eq!(config.subset_ranges(), Some(&[' '..='~', '\u{4E00}'..='\u{9FFF}'][..]));
+    /// ```
+    #[cfg(feature = "subset")]
+    pub fn with_charset(self, ranges: &[(char, char)]) -> Self {
+        self.with_subset_ranges(ranges.iter().map(|&(first, last)| first..=last))
+    }
+
+    /// The character ranges the font is subset to, if any
+    #[cfg(feature = "subset")]
+    pub fn subset_ranges(&self) -> Option<&[std::ops::RangeInclusive<char>]> {
+        self.subset_ranges.as_deref()
+    }
 
-    for font_path in &font_paths {
-        if let Ok(font_data) = std::fs::read(font_path) {
-            return Ok(FontData::from_owned(font_data));
+    /// Whether [`FontConfig::configure`] replaces the font data with a smaller copy
+    fn replaces_font_data(&self) -> bool {
+        #[cfg(feature = "subset")]
+        if self.subset_ranges.is_some() {
+            return true;
         }
+        self.extract_single_face
     }
 
-    Err(FontError::NotFound("No Chinese font found on macOS".to_string()))
-}
+    /// Skip font files that take longer than `timeout` to read
+    ///
+    /// Reading a file from a hung NFS or SMB mount can block for minutes.
+    /// With a timeout, each file is read on a helper thread; a candidate that
+    /// doesn't finish in time is skipped with [`RejectReason::TimedOut`] and
+    /// the next one is tried. If every existing candidate timed out, setup
+    /// fails with [`FontError::Timeout`]. A read that timed out can't be
+    /// cancelled, so its thread finishes in the background.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use egui_chinese_font::{setup_chinese_fonts_with_config, ChineseVariant, FontConfig, FontError, PlatformFontProvider};
+    ///
+    /// # #[cfg(target_os = "linux")] {
+    /// // Nothing ever writes to this FIFO, so reading it blocks like a hung network mount
+    /// let fifo = std::env::temp_dir().join("egui-chinese-font-timeout-example.ttf");
+    /// # let _ = std::fs::remove_file(&fifo);
+    /// # std::process::Command::new("mkfifo").arg(&fifo).status()?;
+    /// let provider = PlatformFontProvider::new(ChineseVariant::Simplified).with_candidates(vec![fifo.display().to_string()]);
+    /// let config = FontConfig::new()
+    ///     .with_provider(Box::new(provider))
+    ///     .with_timeout(Duration::from_millis(200));
+    ///
+    /// let result = setup_chinese_fonts_with_config(&egui::Context::default(), &config);
+    /// assert!(matches!(result, Err(FontError::Timeout(timeout)) if timeout == Duration::from_millis(200)));
+    /// # }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "timeout")]
+    pub fn with_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// How long reading a font file may take, if limited
+    #[cfg(feature = "timeout")]
+    pub fn timeout(&self) -> Option<std::time::Duration> {
+        self.timeout
+    }
+
+    /// Only accept system fonts that have glyphs for every character in `chars`
+    ///
+    /// Candidates missing any of these characters are skipped in favor of the
+    /// next one, e.g. to make sure both Simplified and Traditional UI strings
+    /// render. Whitespace and control characters are ignored.
+    pub fn with_required_chars(mut self, chars: impl Into<String>) -> Self {
+        self.required_chars = chars.into();
+        self
+    }
+
+    /// Characters every accepted system font must cover
+    pub fn required_chars(&self) -> &str {
+        &self.required_chars
+    }
+
+    /// Only accept system fonts of the given style, e.g. Song for body text
+    ///
+    /// By default any style is accepted, with sans-serif (Hei) fonts usually
+    /// found first. If no font of `style` is installed, loading fails with
+    /// [`FontError::StyleNotFound`] unless [`FontConfig::with_style_fallback`]
+    /// is enabled.
+    pub fn with_style(mut self, style: ChineseStyle) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    /// The requested style, if any
+    pub fn style(&self) -> Option<ChineseStyle> {
+        self.style
+    }
 
-#[cfg(target_os = "linux")]
-fn load_linux_chinese_font() -> Result<FontData, FontError> {
-    // Common Chinese font paths on Linux distributions
-    let font_paths = [
-        "/usr/share/fonts/truetype/droid/DroidSansFallbackFull.ttf",
-        "/usr/share/fonts/truetype/arphic/uming.ttc",
-        "/usr/share/fonts/truetype/arphic/ukai.ttc",
-        "/usr/share/fonts/truetype/wqy/wqy-microhei.ttc",
-        "/usr/share/fonts/truetype/wqy/wqy-zenhei.ttc",
-        "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
-        "/usr/share/fonts/truetype/liberation/LiberationSans-Regular.ttf",
-        // Ubuntu/Debian paths
-        "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
-        // CentOS/RHEL paths
-        "/usr/share/fonts/google-droid/DroidSansFallbackFull.ttf",
-        // Arch Linux paths
-        "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
-    ];
+    /// Fall back to a Hei (sans-serif) font when the requested style isn't installed
+    pub fn with_style_fallback(mut self, fallback: bool) -> Self {
+        self.style_fallback = fallback;
+        self
+    }
+
+    /// Whether a missing style falls back to Hei
+    pub fn style_fallback(&self) -> bool {
+        self.style_fallback
+    }
+
+    /// Choose whether the Chinese font comes before or after the fonts already registered
+    ///
+    /// Defaults to [`FontPriority::First`]. With [`FontPriority::Fallback`]
+    /// egui's Latin font keeps drawing ASCII text, and the Chinese font only
+    /// provides the glyphs the earlier fonts lack.
+    pub fn with_priority(mut self, priority: FontPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Where the Chinese font is placed in the font families
+    pub fn priority(&self) -> FontPriority {
+        self.priority
+    }
+
+    /// Choose how the Chinese font is added to the `Monospace` family
+    ///
+    /// Defaults to [`MonospacePolicy::SameAsProportional`]. Use [`MonospacePolicy::Append`]
+    /// or [`MonospacePolicy::Dedicated`] to keep code and tables aligned.
+    pub fn with_monospace(mut self, policy: MonospacePolicy) -> Self {
+        self.monospace = policy;
+        self
+    }
+
+    /// How the Chinese font is added to the `Monospace` family
+    pub fn monospace(&self) -> MonospacePolicy {
+        self.monospace
+    }
+
+    /// Also register an installed emoji font, after all other fonts
+    ///
+    /// Chinese fonts have no emoji, and egui's built-in emoji font only
+    /// covers part of them. With this option a system emoji font is added
+    /// last to the `Proportional` and `Monospace` families, registered as
+    /// `"chinese-emoji"` (or after [`FontConfig::with_font_name`]):
+    ///
+    /// * Windows: Segoe UI Emoji, or Segoe UI Symbol
+    /// * Linux and the BSDs: Noto Emoji or Symbola
+    /// * macOS: Noto Emoji or Symbola, if installed
+    ///
+    /// Fonts in the user's font directory with "emoji" or "symbola" in their
+    /// name are tried as well. egui draws glyphs in a single color from their
+    /// outlines, so bitmap color fonts such as Noto Color Emoji and Apple
+    /// Color Emoji can't be used. Without a usable emoji font, only the
+    /// Chinese font is registered.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use egui_chinese_font::prelude::*;
+    ///
+    /// # let path = std::env::temp_dir().join("egui-chinese-font-emoji-example.ttf");
+    /// # std::fs::write(&path, &*egui::FontDefinitions::default().font_data["Ubuntu-Light"].font)?;
+    /// # std::env::set_var(egui_chinese_font::FONT_PATH_ENV, &path);
+    /// let ctx = egui::Context::default();
+    /// ctx.setup_chinese_fonts_with(|config| config.with_emoji_fallback(true))?;
+    ///
+    /// let fonts = egui_chinese_font::list_loaded_fonts(&ctx);
+    /// if fonts.iter().any(|name| name == "chinese-emoji") {
+    ///     println!("system emoji font registered");
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_emoji_fallback(mut self, enabled: bool) -> Self {
+        self.emoji_fallback = enabled;
+        self
+    }
+
+    /// Whether an installed emoji font is registered too
+    pub fn emoji_fallback(&self) -> bool {
+        self.emoji_fallback
+    }
+
+    /// Load the font file at `path` instead of discovering system fonts
+    ///
+    /// Unlike a failed search, a missing or invalid file is an error rather
+    /// than a reason to try other fonts. Only the [`FONT_PATH_ENV`] override
+    /// takes precedence.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use egui_chinese_font::{find_chinese_font_with_config, FontConfig};
+    ///
+    /// # let path = std::env::temp_dir().join("egui-chinese-font-config-path-example.ttf");
+    /// # std::fs::write(&path, &*egui::FontDefinitions::default().font_data["Ubuntu-Light"].font)?;
+    /// let config = FontConfig::new().with_font_path(&path);
+    /// assert_eq!(find_chinese_font_with_config(&config)?.path(), Some(path.as_path()));
+    ///
+    /// let config = FontConfig::new().with_font_path("/nonexistent/font.ttf");
+    /// assert!(find_chinese_font_with_config(&config).is_err());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_font_path(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.font_path = Some(path.into());
+        self
+    }
+
+    /// The font file set by [`FontConfig::with_font_path`], if any
+    pub fn font_path(&self) -> Option<&std::path::Path> {
+        self.font_path.as_deref()
+    }
+
+    /// Also search `paths` for a Chinese font, before the platform's known paths
+    ///
+    /// Each path is a font file or a directory whose font files are tried in
+    /// alphabetical order, e.g. a `fonts` directory shipped next to the
+    /// application. Candidates are checked for Chinese coverage like installed
+    /// fonts, and listed in [`FontError::NotFound`] when rejected. Use
+    /// [`FontConfig::with_path_mode`] to try them after the platform's paths,
+    /// or instead of them.
+    pub fn with_search_paths(mut self, paths: impl IntoIterator<Item = impl Into<std::path::PathBuf>>) -> Self {
+        self.search_paths = paths.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// The extra paths set by [`FontConfig::with_search_paths`]
+    pub fn search_paths(&self) -> &[std::path::PathBuf] {
+        &self.search_paths
+    }
+
+    /// Set how the [search paths](FontConfig::with_search_paths) combine with the platform's paths
+    pub fn with_path_mode(mut self, mode: PathMode) -> Self {
+        self.path_mode = mode;
+        self
+    }
+
+    /// How the search paths combine with the platform's paths
+    pub fn path_mode(&self) -> PathMode {
+        self.path_mode
+    }
+
+    /// Load the font from `provider` instead of discovering system fonts
+    ///
+    /// The [`FONT_PATH_ENV`] override and [`FontConfig::with_font_path`] still
+    /// take precedence. The provider's
+    /// font is checked for a usable face, but not for Chinese coverage or
+    /// [`FontConfig::with_required_chars`].
+    pub fn with_provider(mut self, provider: Box<dyn FontProvider>) -> Self {
+        self.provider = Some(Arc::from(provider));
+        self
+    }
+
+    /// The custom font provider, if any
+    pub fn provider(&self) -> Option<&dyn FontProvider> {
+        self.provider.as_deref()
+    }
+
+    /// Call `callback` once loading finishes, whether it succeeded or not
+    ///
+    /// Used by [`setup_chinese_fonts_with_config`],
+    /// [`setup_chinese_font_weights_with_config`] and the async loader. The
+    /// synchronous functions call it on the calling thread before returning;
+    /// the async loader calls it on its background thread, so a loading screen
+    /// can e.g. update shared state that the next frame reads.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::sync::{Arc, Mutex};
+    /// use egui_chinese_font::{setup_chinese_fonts_with_config, FontConfig};
+    ///
+    /// let finished = Arc::new(Mutex::new(false));
+    /// let flag = Arc::clone(&finished);
+    /// let config = FontConfig::new().on_load(move |result| {
+    ///     *flag.lock().unwrap() = true;
+    ///     if let Err(err) = result {
+    ///         eprintln!("Chinese font unavailable: {}", err);
+    ///     }
+    /// });
+    ///
+    /// let _ = setup_chinese_fonts_with_config(&egui::Context::default(), &config);
+    /// assert!(*finished.lock().unwrap());
+    /// ```
+    pub fn on_load(mut self, callback: impl Fn(Result<&LoadedFont, &FontError>) + Send + 'static) -> Self {
+        self.on_load = Some(LoadCallback(Arc::new(Mutex::new(callback))));
+        self
+    }
+
+    /// Report the outcome of loading to the [`FontConfig::on_load`] callback, if any
+    fn notify_loaded(&self, result: Result<&LoadedFont, &FontError>) {
+        if let Some(LoadCallback(callback)) = &self.on_load {
+            let callback = callback.lock().unwrap_or_else(|err| err.into_inner());
+            callback(result);
+        }
+    }
 
-    for font_path in &font_paths {
-        if let Ok(font_data) = std::fs::read(font_path) {
-            return Ok(FontData::from_owned(font_data));
+    /// Apply this configuration to loaded font data
+    fn configure(&self, font_data: FontData) -> FontData {
+        let mut font_data = FontData {
+            index: self.face_index.unwrap_or(font_data.index),
+            ..font_data
+        };
+        if self.extract_single_face && font_data.font.starts_with(b"ttcf") {
+            match faces::extract_ttc_face(&font_data.font, font_data.index) {
+                Ok(face) => {
+                    log!(info, "extracted face {} ({} of {} bytes)", font_data.index, face.len(), font_data.font.len());
+                    font_data.font = std::borrow::Cow::Owned(face);
+                    font_data.index = 0;
+                }
+                Err(err) => log!(warn, "keeping the whole collection: {}", err),
+            }
         }
+        #[cfg(feature = "subset")]
+        if let Some(ranges) = &self.subset_ranges {
+            match subset::subset_face(&font_data.font, font_data.index, ranges) {
+                Ok(subset) => {
+                    log!(info, "subset the font to {} of {} bytes", subset.len(), font_data.font.len());
+                    font_data.font = std::borrow::Cow::Owned(subset);
+                    font_data.index = 0;
+                }
+                Err(err) => log!(warn, "using the whole font: {}", err),
+            }
+        }
+        let tweak = self.tweak_for(&font_data);
+        font_data.tweak(tweak)
     }
 
-    Err(FontError::NotFound("No Chinese font found on Linux".to_string()))
+    /// The explicit tweak, or else the one recommended for `font_data`
+    fn tweak_for(&self, font_data: &FontData) -> FontTweak {
+        self.tweak.or_else(|| recommended_tweak(font_data)).unwrap_or_default()
+    }
 }
 
-/// Setup Chinese fonts with custom font data
+/// Setup Chinese fonts for egui context
 ///
-/// This function allows you to provide your own font data instead of
-/// loading from system fonts.
+/// This function will attempt to load system Chinese fonts and configure them
+/// for use with the provided egui context. Fonts already installed in the
+/// context, including egui's built-in Latin and emoji fonts, are kept as
+/// lower-priority fallbacks.
 ///
 /// # Arguments
 /// * `ctx` - The egui context to configure
-/// * `font_data` - The font data to use
-/// * `font_name` - Name for the font (optional, defaults to "chinese")
-pub fn setup_custom_chinese_font(
-    ctx: &Context,
-    font_data: Vec<u8>,
-    font_name: Option<&str>
-) {
-    let mut fonts = FontDefinitions::default();
-    let name = font_name.unwrap_or("chinese");
+///
+/// # Returns
+/// * `Ok(LoadedFont)` describing the registered font
+/// * `Err(FontError)` if font loading failed
+pub fn setup_chinese_fonts(ctx: &Context) -> Result<LoadedFont, FontError> {
+    setup_chinese_fonts_with_config(ctx, &FontConfig::default())
+}
+
+/// Setup Chinese fonts for egui context using a custom [`FontConfig`]
+///
+/// # Arguments
+/// * `ctx` - The egui context to configure
+/// * `config` - Options controlling how the font is registered
+///
+/// # Returns
+/// * `Ok(LoadedFont)` describing the registered font
+/// * `Err(FontError)` if font loading failed
+pub fn setup_chinese_fonts_with_config(ctx: &Context, config: &FontConfig) -> Result<LoadedFont, FontError> {
+    // Try to load Chinese fonts based on platform
+    let result =
+        load_chinese_font(config.variant.resolve(), config).map(|font_data| register_chinese_font(ctx, font_data, config));
+
+    config.notify_loaded(result.as_ref());
+    result
+}
+
+/// Setup Chinese fonts, also searching the font files and directories in `paths`
+///
+/// A shortcut for [`FontConfig::with_search_paths`] and
+/// [`FontConfig::with_path_mode`], e.g. to add a font directory of a
+/// customized Linux distribution. Directories are expanded to the font files
+/// directly inside them. The extra candidates are validated and checked for
+/// Chinese coverage like installed fonts, and listed in
+/// [`FontError::NotFound`] when none is usable.
+///
+/// # Arguments
+/// * `ctx` - The egui context to configure
+/// * `paths` - Font files or directories of font files
+/// * `mode` - Whether to try `paths` before, after or instead of the platform's paths
+///
+/// # Returns
+/// * `Ok(LoadedFont)` describing the registered font
+/// * `Err(FontError)` if font loading failed
+///
+/// # Example
+///
+/// ```rust
+/// use std::path::PathBuf;
+/// use egui_chinese_font::{setup_chinese_fonts_with_paths, FontError, PathMode};
+///
+/// let ctx = egui::Context::default();
+/// let paths = [PathBuf::from("/opt/ourcompany/fonts")];
+/// match setup_chinese_fonts_with_paths(&ctx, &paths, PathMode::Prepend) {
+///     Ok(loaded) => println!("using {:?}", loaded.origin()),
+///     Err(FontError::NotFound { attempts, .. }) => {
+///         for attempt in attempts {
+///             eprintln!("{}", attempt);
+///         }
+///     }
+///     Err(err) => eprintln!("{}", err),
+/// }
+/// ```
+pub fn setup_chinese_fonts_with_paths(
+    ctx: &Context,
+    paths: &[std::path::PathBuf],
+    mode: PathMode,
+) -> Result<LoadedFont, FontError> {
+    let config = FontConfig::new().with_search_paths(paths).with_path_mode(mode);
+    let font_data = load_chinese_font(config.variant.resolve(), &config)?;
+    Ok(register_chinese_font(ctx, font_data, &config))
+}
+
+/// Setup Chinese fonts with the font `provider` loads
+///
+/// Like [`FontConfig::with_provider`] with the default options, but borrows
+/// the provider, e.g. a [`PlatformFontProvider`] reading from an in-memory
+/// file system in tests, or one serving fonts from a game's asset pack.
+///
+/// # Returns
+/// * `Ok(LoadedFont)` describing the registered font
+/// * `Err(FontError)` if the provider failed
+///
+/// # Example
+///
+/// ```rust
+/// use std::io::ErrorKind;
+/// use egui_chinese_font::{setup_chinese_fonts_with_provider, ChineseVariant, PlatformFontProvider};
+///
+/// # let font = egui::FontDefinitions::default().font_data["Ubuntu-Light"].font.to_vec();
+/// let provider = PlatformFontProvider::new(ChineseVariant::Simplified)
+///     .with_candidates(vec!["assets/fonts/chinese.ttf".to_owned()])
+///     .with_reader(move |path| match path {
+///         "assets/fonts/chinese.ttf" => Ok(font.clone()),
+///         _ => Err(ErrorKind::NotFound.into()),
+///     });
+///
+/// let ctx = egui::Context::default();
+/// let loaded = setup_chinese_fonts_with_provider(&ctx, &provider)?;
+/// assert_eq!(loaded.name(), "chinese");
+/// # Ok::<(), egui_chinese_font::FontError>(())
+/// ```
+pub fn setup_chinese_fonts_with_provider(
+    ctx: &Context,
+    provider: &(impl FontProvider + ?Sized),
+) -> Result<LoadedFont, FontError> {
+    let config = FontConfig::default();
+    provider.load().map(|font_data| register_chinese_font(ctx, font_data, &config))
+}
+
+/// Setup Chinese fonts, using face `face_index` of the font collection that is found
+///
+/// A shortcut for [`FontConfig::with_face_index`], e.g. to switch `PingFang.ttc`
+/// on macOS from its Simplified face to the Traditional one. Use
+/// [`list_ttc_faces`] to see which faces a file contains. Single fonts only
+/// have face `0`.
+///
+/// # Arguments
+/// * `ctx` - The egui context to configure
+/// * `face_index` - Index of the face within the collection
+///
+/// # Returns
+/// * `Ok(LoadedFont)` describing the registered font
+/// * `Err(FontError::InvalidFont)` if the font found has no usable face at `face_index`
+/// * `Err(FontError)` if font loading failed otherwise
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::{setup_chinese_fonts_with_index, FontError};
+///
+/// # let path = std::env::temp_dir().join("egui-chinese-font-index-example.ttf");
+/// # std::fs::write(&path, &*egui::FontDefinitions::default().font_data["Ubuntu-Light"].font)?;
+/// # std::env::set_var(egui_chinese_font::FONT_PATH_ENV, &path);
+/// let ctx = egui::Context::default();
+/// // A single font has no second face
+/// assert!(matches!(setup_chinese_fonts_with_index(&ctx, 1), Err(FontError::InvalidFont { .. })));
+///
+/// let loaded = setup_chinese_fonts_with_index(&ctx, 0)?;
+/// assert_eq!(loaded.font_data().index, 0);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn setup_chinese_fonts_with_index(ctx: &Context, face_index: u32) -> Result<LoadedFont, FontError> {
+    let config = FontConfig::new().with_face_index(Some(face_index));
+    let font_data = load_chinese_font(config.variant.resolve(), &config)?;
+    Ok(register_chinese_font(ctx, font_data, &config))
+}
+
+/// Load the Chinese font and build the font definitions [`setup_chinese_fonts`] would apply
+///
+/// Does the same discovery and registration as [`setup_chinese_fonts`], but
+/// on a copy of egui's default [`FontDefinitions`] instead of a context. Use
+/// it to unit-test font setup without running egui, to inspect or adjust the
+/// definitions before applying them with [`Context::set_fonts`], or to share
+/// them between contexts.
+///
+/// # Returns
+/// * `Ok((FontDefinitions, LoadedFont))` with the Chinese font registered as `"chinese"`,
+///   or under [`FontConfig::with_font_name`]
+/// * `Err(FontError)` if font loading failed
+///
+/// # Example
+///
+/// ```rust
+/// use egui::FontFamily;
+/// use egui_chinese_font::setup_chinese_fonts_returning_definitions;
+///
+/// # let path = std::env::temp_dir().join("egui-chinese-font-definitions-example.ttf");
+/// # std::fs::write(&path, &*egui::FontDefinitions::default().font_data["Ubuntu-Light"].font)?;
+/// # std::env::set_var(egui_chinese_font::FONT_PATH_ENV, &path);
+/// let (definitions, loaded) = setup_chinese_fonts_returning_definitions()?;
+/// assert_eq!(loaded.name(), "chinese");
+/// assert!(definitions.font_data.contains_key("chinese"));
+/// assert_eq!(definitions.families[&FontFamily::Proportional][0], "chinese");
+/// // egui's own fonts stay as fallbacks
+/// assert!(definitions.families[&FontFamily::Proportional].len() > 1);
+///
+/// let ctx = egui::Context::default();
+/// ctx.set_fonts(definitions);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn setup_chinese_fonts_returning_definitions() -> Result<(FontDefinitions, LoadedFont), FontError> {
+    setup_chinese_fonts_returning_definitions_with_config(&FontConfig::default())
+}
+
+/// Like [`setup_chinese_fonts_returning_definitions`], using a custom [`FontConfig`]
+pub fn setup_chinese_fonts_returning_definitions_with_config(
+    config: &FontConfig,
+) -> Result<(FontDefinitions, LoadedFont), FontError> {
+    let result = load_chinese_font(config.variant.resolve(), config).map(|font_data| {
+        let loaded = configure_system_font(font_data, config);
+        let mut definitions = FontDefinitions::default();
+        insert_configured_fonts(&mut definitions, vec![(loaded.name.clone(), Arc::clone(loaded.font_data()))], config);
+        (definitions, loaded)
+    });
+
+    config.notify_loaded(result.as_ref().map(|(_, loaded)| loaded));
+    result
+}
+
+/// Register a discovered Chinese font according to `config`
+fn register_chinese_font(ctx: &Context, font_data: FontData, config: &FontConfig) -> LoadedFont {
+    let loaded = configure_system_font(font_data, config);
+    apply_configured_fonts(ctx, vec![(loaded.name.clone(), Arc::clone(loaded.font_data()))], config);
+    loaded
+}
+
+/// Apply `config` to a discovered font, naming it after [`FontConfig::font_name`]
+fn configure_system_font(font_data: FontData, config: &FontConfig) -> LoadedFont {
+    let file_size = font_data.font.len();
+    let mut loaded =
+        LoadedFont::new(config.font_name(), FontOrigin::System, Arc::new(config.configure(font_data))).placed_like(config);
+    if config.extract_single_face && loaded.font_data.font.len() != file_size {
+        loaded.collection_size = Some(file_size);
+    }
+    loaded
+}
+
+/// Register `font_data` under `name` as the first choice for all font families
+fn apply_chinese_font(ctx: &Context, name: &str, font_data: Arc<FontData>) {
+    apply_fonts(ctx, vec![(name.to_owned(), font_data)]);
+}
+
+/// Register `fonts` ahead of the context's current fonts, keeping their relative priority
+///
+/// Fonts already installed in the context (egui's built-in Latin and emoji
+/// fonts, or fonts added by the application) stay in place as lower-priority
+/// fallbacks. Registering a name again replaces the earlier font.
+fn apply_fonts(ctx: &Context, fonts: Vec<(String, Arc<FontData>)>) {
+    apply_fonts_with(ctx, fonts, FontPriority::First, MonospacePolicy::SameAsProportional);
+}
+
+/// Like [`apply_fonts`], placing the fonts according to `priority` and the `Monospace` policy
+///
+/// [`MonospacePolicy::Dedicated`] appends like [`MonospacePolicy::Append`];
+/// the monospaced font itself is added by [`apply_configured_fonts`].
+fn apply_fonts_with(ctx: &Context, fonts: Vec<(String, Arc<FontData>)>, priority: FontPriority, monospace: MonospacePolicy) {
+    let mut definitions = current_font_definitions(ctx);
+    insert_fonts(&mut definitions, fonts, priority, monospace);
+    set_font_definitions(ctx, definitions);
+}
+
+/// Add `fonts` to `definitions`, see [`apply_fonts_with`]
+fn insert_fonts(
+    definitions: &mut FontDefinitions,
+    fonts: Vec<(String, Arc<FontData>)>,
+    priority: FontPriority,
+    monospace: MonospacePolicy,
+) {
+    for (position, (name, font_data)) in fonts.into_iter().enumerate() {
+        // Insert the font, replacing any earlier font with this name
+        definitions.font_data.insert(name.clone(), font_data);
+
+        // Configure font families
+        for family in [FontFamily::Proportional, FontFamily::Monospace] {
+            let names = definitions.families.entry(family.clone()).or_default();
+            names.retain(|existing| *existing != name);
+            match (family, monospace, priority) {
+                (FontFamily::Monospace, MonospacePolicy::Skip, _) => {}
+                (FontFamily::Monospace, MonospacePolicy::Append | MonospacePolicy::Dedicated, _) => names.push(name.clone()),
+                (_, _, FontPriority::Fallback) => names.push(name.clone()),
+                (_, _, FontPriority::First) => names.insert(position.min(names.len()), name.clone()),
+            }
+        }
+    }
+}
+
+/// Register `fonts` following `config`, including its monospace policy
+fn apply_configured_fonts(ctx: &Context, fonts: Vec<(String, Arc<FontData>)>, config: &FontConfig) {
+    let mut definitions = current_font_definitions(ctx);
+    insert_configured_fonts(&mut definitions, fonts, config);
+    set_font_definitions(ctx, definitions);
+}
+
+/// Add `fonts` to `definitions`, see [`apply_configured_fonts`]
+fn insert_configured_fonts(definitions: &mut FontDefinitions, fonts: Vec<(String, Arc<FontData>)>, config: &FontConfig) {
+    insert_fonts(definitions, fonts, config.priority, config.monospace);
+    if config.emoji_fallback {
+        insert_emoji_font(definitions, config);
+    }
+    if config.monospace != MonospacePolicy::Dedicated {
+        return;
+    }
+
+    let query = FontQuery {
+        variant: config.variant.resolve(),
+        style: None,
+        required_chars: &config.required_chars,
+    };
+    let Some(found) = mono::load_mono_font(&query) else {
+        log!(info, "no monospaced Chinese font found, appending the Chinese font to Monospace");
+        return;
+    };
+
+    let tweak = config.tweak_for(&found.font_data);
+    let mono_name = format!("{}-mono", config.font_name());
+    definitions.font_data.insert(mono_name.clone(), Arc::new(found.font_data.tweak(tweak)));
+    let names = definitions.families.entry(FontFamily::Monospace).or_default();
+    names.retain(|existing| *existing != mono_name);
+    names.insert(0, mono_name);
+}
+
+/// Add an installed emoji font last to the families the Chinese font is in
+fn insert_emoji_font(definitions: &mut FontDefinitions, config: &FontConfig) {
+    let Some(font_data) = emoji::load_emoji_font() else {
+        log!(info, "no emoji font found that egui can draw");
+        return;
+    };
+
+    let emoji_name = format!("{}-emoji", config.font_name());
+    definitions.font_data.insert(emoji_name.clone(), Arc::new(font_data));
+    for family in [FontFamily::Proportional, FontFamily::Monospace] {
+        if family == FontFamily::Monospace && config.monospace == MonospacePolicy::Skip {
+            continue;
+        }
+        let names = definitions.families.entry(family).or_default();
+        names.retain(|existing| *existing != emoji_name);
+        names.push(emoji_name.clone());
+    }
+}
+
+/// Remove the Chinese fonts registered by this crate from an egui context
+///
+/// Strips every font whose name starts with `"chinese"` (the default name
+/// used by [`setup_chinese_fonts`] and [`setup_custom_chinese_font`]) from the
+/// font data and all font families, leaving other fonts untouched. Does
+/// nothing if no such font is registered.
+///
+/// # Arguments
+/// * `ctx` - The egui context to configure
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::{remove_chinese_fonts, setup_custom_chinese_font};
+///
+/// # let font_data = egui::FontDefinitions::default().font_data["Ubuntu-Light"].font.to_vec();
+/// let ctx = egui::Context::default();
+/// setup_custom_chinese_font(&ctx, font_data, None)?;
+/// let _ = ctx.run(Default::default(), |_| {});
+///
+/// remove_chinese_fonts(&ctx);
+/// let _ = ctx.run(Default::default(), |_| {});
+/// let definitions = ctx.fonts(|fonts| fonts.definitions().clone());
+/// assert!(!definitions.font_data.contains_key("chinese"));
+/// assert!(definitions.families.values().all(|names| !names.iter().any(|name| name == "chinese")));
+///
+/// // Removing again is a no-op
+/// remove_chinese_fonts(&ctx);
+/// # Ok::<(), egui_chinese_font::FontError>(())
+/// ```
+pub fn remove_chinese_fonts(ctx: &Context) {
+    let mut definitions = current_font_definitions(ctx);
+    let is_chinese = |name: &String| name.starts_with("chinese");
+
+    let font_count = definitions.font_data.len();
+    definitions.font_data.retain(|name, _| !is_chinese(name));
+    if definitions.font_data.len() == font_count {
+        return;
+    }
+
+    for names in definitions.families.values_mut() {
+        names.retain(|name| !is_chinese(name));
+    }
+
+    set_font_definitions(ctx, definitions);
+}
+
+/// Names of all fonts registered in the context, in alphabetical order
+///
+/// Useful for debugging font setup. Includes fonts set in this pass that
+/// egui only applies at the start of the next one, so it can be called right
+/// after [`setup_chinese_fonts`], even before the first frame.
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::{list_family_fonts, list_loaded_fonts, setup_custom_chinese_font};
+/// use egui::FontFamily;
+///
+/// # let font_data = egui::FontDefinitions::default().font_data["Ubuntu-Light"].font.to_vec();
+/// let ctx = egui::Context::default();
+/// setup_custom_chinese_font(&ctx, font_data, None)?;
+///
+/// assert!(list_loaded_fonts(&ctx).contains(&"chinese".to_string()));
+/// assert_eq!(list_family_fonts(&ctx, FontFamily::Proportional).first().map(String::as_str), Some("chinese"));
+/// assert!(list_family_fonts(&ctx, FontFamily::Name("missing".into())).is_empty());
+/// # Ok::<(), egui_chinese_font::FontError>(())
+/// ```
+pub fn list_loaded_fonts(ctx: &Context) -> Vec<String> {
+    current_font_definitions(ctx).font_data.into_keys().collect()
+}
+
+/// Names of the fonts of `family` in the context, in fallback order
+///
+/// Empty if the family isn't defined. See [`list_loaded_fonts`].
+pub fn list_family_fonts(ctx: &Context, family: FontFamily) -> Vec<String> {
+    current_font_definitions(ctx).families.remove(&family).unwrap_or_default()
+}
+
+/// Font definitions set by this crate that egui applies at the start of the next pass
+#[derive(Clone)]
+struct PendingFonts {
+    pass_nr: u64,
+    definitions: FontDefinitions,
+}
+
+fn pending_fonts_id() -> egui::Id {
+    egui::Id::new("egui_chinese_font::pending_fonts")
+}
+
+/// The context's current font definitions, including changes not yet applied
+fn current_font_definitions(ctx: &Context) -> FontDefinitions {
+    // Fonts set earlier in this pass only take effect in the next one, so
+    // building on `Context::fonts` alone would drop them
+    let pass_nr = ctx.cumulative_pass_nr();
+    let pending = ctx.data(|data| data.get_temp::<PendingFonts>(pending_fonts_id()));
+    if let Some(pending) = pending.filter(|pending| pending.pass_nr == pass_nr) {
+        return pending.definitions;
+    }
+
+    // `Context::fonts` is only available once the first frame has run;
+    // before that the context uses egui's defaults
+    if pass_nr > 0 {
+        ctx.fonts(|fonts| fonts.definitions().clone())
+    } else {
+        FontDefinitions::default()
+    }
+}
+
+/// Apply `definitions`, remembering them until egui picks them up
+fn set_font_definitions(ctx: &Context, definitions: FontDefinitions) {
+    let pending = PendingFonts {
+        pass_nr: ctx.cumulative_pass_nr(),
+        definitions: definitions.clone(),
+    };
+    ctx.data_mut(|data| data.insert_temp(pending_fonts_id(), pending));
+    ctx.set_fonts(definitions);
+}
+
+/// Load Chinese font data for `config`, preferring fonts for `variant`
+///
+/// The environment override wins, then the config's custom provider. Without
+/// one, discovered fonts must have glyphs for every required character.
+fn load_chinese_font(variant: ChineseVariant, config: &FontConfig) -> Result<FontData, FontError> {
+    let locate = || locate_chinese_font(variant, config).map(|found| found.font_data);
+    if config.replaces_font_data() {
+        // Caching would keep the whole file alive next to the smaller copy
+        cache::without_storing(locate)
+    } else {
+        locate()
+    }
+}
+
+/// Find the Chinese font [`setup_chinese_fonts`] would use, without touching an egui context
+///
+/// Runs the same discovery as the setup functions, including the
+/// [`FONT_PATH_ENV`] override and the preferred variant, so the reported font
+/// is the one that would be loaded. Use it to decide on a default UI language
+/// or to show the font in a diagnostics screen.
+///
+/// Checking that a font covers Chinese needs its character map, so the chosen
+/// file is read once. Its bytes are kept in the font cache, so setting up the
+/// font afterwards doesn't read it again.
+///
+/// # Returns
+/// * `Ok(FoundFont)` describing the font that would be used
+/// * `Err(FontError)` if setting up the font would fail
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::{find_chinese_font, FONT_PATH_ENV};
+///
+/// # let path = std::env::temp_dir().join("egui-chinese-font-find-example.ttf");
+/// # std::fs::write(&path, &*egui::FontDefinitions::default().font_data["Ubuntu-Light"].font)?;
+/// std::env::set_var(FONT_PATH_ENV, &path);
+///
+/// let found = find_chinese_font()?;
+/// assert_eq!(found.path(), Some(path.as_path()));
+/// assert_eq!(found.file_size(), std::fs::metadata(&path)?.len());
+/// assert!(!found.is_collection());
+/// println!("would use {:?}", found.family());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn find_chinese_font() -> Result<FoundFont, FontError> {
+    find_chinese_font_with_config(&FontConfig::default())
+}
+
+/// Find the Chinese font [`setup_chinese_fonts_with_config`] would use with `config`
+///
+/// See [`find_chinese_font`].
+pub fn find_chinese_font_with_config(config: &FontConfig) -> Result<FoundFont, FontError> {
+    locate_chinese_font(config.variant.resolve(), config)
+}
+
+/// Load the Chinese font [`setup_chinese_fonts`] would register, without registering it
+///
+/// Runs the same discovery and applies the same tweak, but the returned
+/// [`FontData`] isn't attached to any [`FontFamily`] or context yet. Insert it
+/// into your own [`FontDefinitions`], e.g. next to icon fonts or fonts for
+/// other scripts.
+///
+/// # Returns
+/// * `Ok(FontData)` ready for [`FontDefinitions::font_data`]
+/// * `Err(FontError)` if no font could be loaded
+///
+/// # Example
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use egui::{FontDefinitions, FontFamily};
+/// use egui_chinese_font::load_system_chinese_font;
+///
+/// # let path = std::env::temp_dir().join("egui-chinese-font-load-system-example.ttf");
+/// # std::fs::write(&path, &*egui::FontDefinitions::default().font_data["Ubuntu-Light"].font)?;
+/// # std::env::set_var(egui_chinese_font::FONT_PATH_ENV, &path);
+/// let mut definitions = FontDefinitions::default();
+/// definitions.font_data.insert("my-chinese".to_owned(), Arc::new(load_system_chinese_font()?));
+/// // Chinese after egui's Latin font, so Latin text keeps its look
+/// definitions.families.get_mut(&FontFamily::Proportional).unwrap().push("my-chinese".to_owned());
+///
+/// let ctx = egui::Context::default();
+/// ctx.set_fonts(definitions);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn load_system_chinese_font() -> Result<FontData, FontError> {
+    load_system_chinese_font_with_config(&FontConfig::default())
+}
+
+/// Like [`load_system_chinese_font`], using a custom [`FontConfig`]
+///
+/// The config's tweak, face index and other font options are applied; its
+/// family and naming options only matter when registering and are ignored.
+pub fn load_system_chinese_font_with_config(config: &FontConfig) -> Result<FontData, FontError> {
+    load_chinese_font(config.variant.resolve(), config).map(|font_data| config.configure(font_data))
+}
+
+/// Like [`load_chinese_font`], also reporting the file the font was read from
+fn locate_chinese_font(variant: ChineseVariant, config: &FontConfig) -> Result<FoundFont, FontError> {
+    let search = || search_chinese_font(variant, config).and_then(|found| check_face_index(found, config));
+    #[cfg(feature = "timeout")]
+    if let Some(timeout) = config.timeout {
+        return cache::with_read_timeout(timeout, search);
+    }
+    search()
+}
+
+/// Check that the font found has the face set with [`FontConfig::with_face_index`]
+///
+/// egui panics on a face index past the end of a collection, so this has to
+/// fail before the font is registered.
+fn check_face_index(found: FoundFont, config: &FontConfig) -> Result<FoundFont, FontError> {
+    if let Some(index) = config.face_index {
+        faces::validate_font(&found.font_data.font, index).map_err(|reason| FontError::InvalidFont {
+            path: found.path.clone(),
+            reason,
+        })?;
+    }
+    Ok(found)
+}
+
+/// Search the override, provider and platform fonts, see [`locate_chinese_font`]
+fn search_chinese_font(variant: ChineseVariant, config: &FontConfig) -> Result<FoundFont, FontError> {
+    // An explicit override wins over any platform detection
+    if let Some(path) = std::env::var_os(FONT_PATH_ENV).filter(|path| !path.is_empty()) {
+        let path = std::path::PathBuf::from(path);
+        let font_data = match cache::read(&path) {
+            Ok(font_data) => font_data_for_variant(font_data, variant),
+            Err(error) => return Err(FontError::EnvOverride { path, error }),
+        };
+        return match faces::validate_font(&font_data.font, font_data.index) {
+            Ok(()) => {
+                log!(info, "using {} from {} (face {})", path.display(), FONT_PATH_ENV, font_data.index);
+                Ok(FoundFont::new(cache::store(&path, font_data), Some(path)))
+            }
+            Err(reason) => Err(FontError::InvalidFont { path: Some(path), reason }),
+        };
+    }
+
+    if let Some(path) = &config.font_path {
+        let font_data = cache::read(path).map_err(|err| FontError::from_read(path, err))?;
+        let font_data = font_data_for_variant(font_data, variant);
+        faces::validate_font(&font_data.font, font_data.index).map_err(|reason| FontError::InvalidFont {
+            path: Some(path.clone()),
+            reason,
+        })?;
+        log!(info, "using {} from the config (face {})", path.display(), font_data.index);
+        return Ok(FoundFont::new(cache::store(path, font_data), Some(path.clone())));
+    }
+
+    if let Some(provider) = &config.provider {
+        let font_data = provider.load()?;
+        faces::validate_font(&font_data.font, font_data.index)
+            .map_err(|reason| FontError::InvalidFont { path: None, reason })?;
+        log!(info, "using font from custom provider (face {})", font_data.index);
+        return Ok(FoundFont::new(font_data, None));
+    }
+
+    let query = FontQuery {
+        variant,
+        style: config.style,
+        required_chars: &config.required_chars,
+    };
+    let search_paths = platform::search_path_candidates(&config.search_paths);
+    let discover_chinese_font = |query: &FontQuery<'_>| discover_chinese_font(query, &search_paths, config.path_mode);
+    let result = match discover_chinese_font(&query) {
+        Err(FontError::StyleNotFound(style)) if config.style_fallback && style != ChineseStyle::Hei => {
+            log!(info, "no {:?} style font found, falling back to Hei", style);
+            let query = FontQuery {
+                style: Some(ChineseStyle::Hei),
+                ..query
+            };
+            discover_chinese_font(&query)
+        }
+        result => result,
+    };
+
+    // Without an installed font covering Chinese, use the font compiled into the binary
+    #[cfg(feature = "bundled")]
+    let result = bundled::or_bundled(result);
+
+    result
+}
+
+/// Discover an installed Chinese font matching `query`, trying `search_paths` as `mode` says
+fn discover_chinese_font(query: &FontQuery<'_>, search_paths: &[String], mode: PathMode) -> Result<FoundFont, FontError> {
+    if mode == PathMode::Replace {
+        return platform::load_platform_font(query, search_paths, mode);
+    }
+
+    // Prepended search paths come before the system font database and fontconfig
+    let mut rejected = platform::Rejected::default();
+    let mut extra_paths = search_paths;
+    if mode == PathMode::Prepend {
+        if let Some(found) = platform::try_search_paths(search_paths, query, &mut rejected) {
+            return Ok(found);
+        }
+        extra_paths = &[];
+    }
+
+    // Ask the system font database next, falling back to the known paths
+    #[cfg(feature = "system-query")]
+    match system_query::load_chinese_font(query) {
+        Ok(found) => return Ok(found),
+        Err(err) => log!(debug, "system font database: {}", err),
+    }
+
+    // Then the font the user's fontconfig setup prefers for Chinese
+    #[cfg(all(feature = "fc-match", any(target_os = "linux", target_os = "freebsd", target_os = "openbsd", target_os = "netbsd")))]
+    match fc_match::load_chinese_font(query) {
+        Ok(found) => return Ok(found),
+        Err(err) => log!(debug, "fontconfig: {}", err),
+    }
+
+    platform::continue_platform_search(query, extra_paths, mode, rejected)
+}
+
+/// Wrap font bytes, selecting the collection face that matches `variant`
+fn font_data_for_variant(font_data: Cow<'static, [u8]>, variant: ChineseVariant) -> FontData {
+    let index = faces::select_face_index(&font_data, variant);
+    FontData {
+        font: font_data,
+        index,
+        tweak: FontTweak::default(),
+    }
+}
+
+/// Setup Chinese fonts with custom font data
+///
+/// This function allows you to provide your own font data instead of
+/// loading from system fonts. The font is added ahead of the context's
+/// current fonts rather than replacing them. Use
+/// [`setup_custom_chinese_font_arc`] to share data that is already loaded,
+/// [`setup_custom_chinese_font_with_tweak`] to adjust its glyphs and
+/// [`setup_custom_chinese_font_with_index`] to pick a face of a collection.
+///
+/// # Arguments
+/// * `ctx` - The egui context to configure
+/// * `font_data` - The font data to use
+/// * `font_name` - Name for the font (optional, defaults to "chinese")
+///
+/// # Returns
+/// * `Ok(LoadedFont)` describing the registered font
+/// * `Err(FontError::InvalidFont)` if `font_data` is not a usable font
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::{setup_custom_chinese_font, FontError};
+///
+/// let ctx = egui::Context::default();
+///
+/// // Truncated or corrupted data is rejected instead of panicking inside egui later
+/// # let font_data = egui::FontDefinitions::default().font_data["Ubuntu-Light"].font.to_vec();
+/// let truncated = font_data[..font_data.len() / 10].to_vec();
+/// assert!(matches!(
+///     setup_custom_chinese_font(&ctx, truncated, None),
+///     Err(FontError::InvalidFont { .. })
+/// ));
+/// assert!(setup_custom_chinese_font(&ctx, Vec::new(), None).is_err());
+/// // So is an image passed by mistake
+/// let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+/// assert!(matches!(setup_custom_chinese_font(&ctx, png, None), Err(FontError::InvalidFont { .. })));
+///
+/// let loaded = setup_custom_chinese_font(&ctx, font_data, None)?;
+/// assert_eq!(loaded.name(), "chinese");
+///
+/// // egui's own fonts stay registered as fallbacks
+/// let _ = ctx.run(Default::default(), |_| {});
+/// assert!(ctx.fonts(|fonts| fonts.definitions().font_data.contains_key("Ubuntu-Light")));
+/// # Ok::<(), FontError>(())
+/// ```
+pub fn setup_custom_chinese_font(
+    ctx: &Context,
+    font_data: Vec<u8>,
+    font_name: Option<&str>
+) -> Result<LoadedFont, FontError> {
+    setup_custom_chinese_font_with_index(ctx, font_data, font_name, None)
+}
+
+/// Setup an ordered fallback chain of custom fonts
+///
+/// Each font is registered under its name and placed ahead of egui's default
+/// fonts in the given order, so egui tries the first font for every glyph and
+/// falls back to the next one for glyphs it lacks. Use this to pair a brand
+/// font with a CJK font that fills in missing characters.
+///
+/// # Arguments
+/// * `ctx` - The egui context to configure
+/// * `fonts` - `(name, font data)` pairs, highest priority first
+///
+/// # Returns
+/// * `Ok(Vec<LoadedFont>)` describing the registered fonts, highest priority first
+/// * `Err(FontError::InvalidFont)` if any font is not usable; nothing is registered
+///
+/// # Example
+///
+/// ```rust
+/// use egui::FontFamily;
+/// use egui_chinese_font::setup_custom_chinese_fonts;
+///
+/// # let defaults = egui::FontDefinitions::default();
+/// # let brand_font = defaults.font_data["Ubuntu-Light"].font.to_vec();
+/// # let cjk_font = defaults.font_data["Hack"].font.to_vec();
+/// let ctx = egui::Context::default();
+/// let loaded = setup_custom_chinese_fonts(
+///     &ctx,
+///     vec![("brand".to_owned(), brand_font), ("noto-cjk".to_owned(), cjk_font)],
+/// )?;
+/// assert_eq!(loaded.iter().map(|font| font.name()).collect::<Vec<_>>(), ["brand", "noto-cjk"]);
+///
+/// // Fonts take effect on the next frame
+/// let _ = ctx.run(Default::default(), |_| {});
+/// let proportional = ctx.fonts(|fonts| fonts.definitions().families[&FontFamily::Proportional].clone());
+/// assert_eq!(proportional[..2], ["brand", "noto-cjk"]);
+///
+/// // egui's built-in fonts stay available as lower-priority fallbacks
+/// assert!(proportional.iter().any(|name| name == "Ubuntu-Light"));
+/// assert!(proportional.iter().any(|name| name == "NotoEmoji-Regular"));
+/// # Ok::<(), egui_chinese_font::FontError>(())
+/// ```
+pub fn setup_custom_chinese_fonts(ctx: &Context, fonts: Vec<(String, Vec<u8>)>) -> Result<Vec<LoadedFont>, FontError> {
+    let fonts: Vec<(String, Arc<FontData>)> = fonts
+        .into_iter()
+        .map(|(name, font_data)| Ok((name, Arc::new(validated_font_data(font_data, 0)?))))
+        .collect::<Result<_, FontError>>()?;
+
+    apply_fonts(ctx, fonts.clone());
+
+    Ok(fonts
+        .into_iter()
+        .map(|(name, font_data)| LoadedFont::new(name, FontOrigin::Memory, font_data))
+        .collect())
+}
+
+/// Setup Chinese fonts with custom font data, using a specific collection face
+///
+/// Same as [`setup_custom_chinese_font`], but selects face `face_index` of a
+/// font collection (`.ttc`). `None` uses the first face.
+///
+/// # Arguments
+/// * `ctx` - The egui context to configure
+/// * `font_data` - The font data to use
+/// * `font_name` - Name for the font (optional, defaults to "chinese")
+/// * `face_index` - Face to use within a font collection (optional, defaults to 0)
+///
+/// # Returns
+/// * `Ok(LoadedFont)` describing the registered font
+/// * `Err(FontError::InvalidFont)` if the selected face is not a usable font
+pub fn setup_custom_chinese_font_with_index(
+    ctx: &Context,
+    font_data: Vec<u8>,
+    font_name: Option<&str>,
+    face_index: Option<u32>,
+) -> Result<LoadedFont, FontError> {
+    let name = font_name.unwrap_or("chinese");
+    let font_data = Arc::new(validated_font_data(font_data, face_index.unwrap_or(0))?);
+
+    apply_chinese_font(ctx, name, Arc::clone(&font_data));
+
+    Ok(LoadedFont::new(name, FontOrigin::Memory, font_data))
+}
+
+/// Setup Chinese fonts with font data read from `reader`
+///
+/// Reads the stream to completion, e.g. a font decompressed from an asset
+/// archive, then registers it like [`setup_custom_chinese_font`].
+///
+/// # Arguments
+/// * `ctx` - The egui context to configure
+/// * `reader` - Source of the font bytes
+/// * `font_name` - Name for the font (optional, defaults to "chinese")
+///
+/// # Returns
+/// * `Ok(LoadedFont)` describing the registered font
+/// * `Err(FontError::ReadError)` if reading from `reader` failed
+/// * `Err(FontError::InvalidFont)` if the data is not a usable font
+///
+/// # Example
+///
+/// ```rust
+/// use std::io::Cursor;
+/// use egui_chinese_font::{setup_chinese_font_from_reader, FontError};
+///
+/// let ctx = egui::Context::default();
+/// # let font_data = egui::FontDefinitions::default().font_data["Ubuntu-Light"].font.to_vec();
+/// setup_chinese_font_from_reader(&ctx, Cursor::new(font_data), None)?;
+///
+/// let err = setup_chinese_font_from_reader(&ctx, Cursor::new(b"not a font"), None).unwrap_err();
+/// assert!(matches!(err, FontError::InvalidFont { .. }));
+/// # Ok::<(), FontError>(())
+/// ```
+pub fn setup_chinese_font_from_reader<R: std::io::Read>(
+    ctx: &Context,
+    mut reader: R,
+    font_name: Option<&str>,
+) -> Result<LoadedFont, FontError> {
+    let mut font_data = Vec::new();
+    reader.read_to_end(&mut font_data).map_err(FontError::ReadError)?;
+
+    setup_custom_chinese_font(ctx, font_data, font_name)
+}
+
+/// Setup the face at `face_index` of an installed font file as the Chinese font
+///
+/// Use it with an entry of [`available_chinese_fonts`] to apply the font the
+/// user picked. Like discovered fonts, known CJK fonts get their
+/// [`recommended_tweak`].
+///
+/// # Arguments
+/// * `ctx` - The egui context to configure
+/// * `path` - Path to a `.ttf`, `.otf` or `.ttc` file
+/// * `face_index` - Index of the face within the file, `0` for single fonts
+///
+/// # Returns
+/// * `Ok(LoadedFont)` describing the registered font
+/// * `Err(FontError::PermissionDenied)` or `Err(FontError::ReadError)` if the file could not be read
+/// * `Err(FontError::InvalidFont)` if the file has no usable face at `face_index`
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::{available_chinese_fonts, setup_chinese_font_from_path};
+///
+/// let ctx = egui::Context::default();
+/// if let Some(font) = available_chinese_fonts().first() {
+///     setup_chinese_font_from_path(&ctx, &font.path, font.index)?;
+/// }
+/// # Ok::<(), egui_chinese_font::FontError>(())
+/// ```
+pub fn setup_chinese_font_from_path(
+    ctx: &Context,
+    path: impl AsRef<std::path::Path>,
+    face_index: u32,
+) -> Result<LoadedFont, FontError> {
+    let path = path.as_ref();
+    let font_data = read_font_file(path, Some(face_index))?;
+    let tweak = recommended_tweak(&font_data).unwrap_or_default();
+    let font_data = Arc::new(font_data.tweak(tweak));
+    apply_chinese_font(ctx, "chinese", Arc::clone(&font_data));
+
+    Ok(LoadedFont::new("chinese", FontOrigin::File(path.to_path_buf()), font_data))
+}
+
+/// Read and validate a face of the font file at `path`, through the font cache
+///
+/// Without a `face_index`, the face designed for the preferred variant is used.
+fn read_font_file(path: &std::path::Path, face_index: Option<u32>) -> Result<FontData, FontError> {
+    let font = cache::read(path).map_err(|err| FontError::from_read(path, err))?;
+    let index = face_index.unwrap_or_else(|| faces::select_face_index(&font, VariantPreference::Auto.resolve()));
+
+    faces::validate_font(&font, index).map_err(|reason| FontError::InvalidFont {
+        path: Some(path.to_path_buf()),
+        reason,
+    })?;
+
+    Ok(cache::store(path, FontData {
+        font,
+        index,
+        tweak: FontTweak::default(),
+    }))
+}
+
+/// Setup Chinese fonts with custom font data and a [`FontTweak`]
+///
+/// Same as [`setup_custom_chinese_font`], but scales and shifts the glyphs by
+/// `tweak`. Custom fonts don't get a [`recommended_tweak`] automatically; pass
+/// it here to use it.
+///
+/// # Arguments
+/// * `ctx` - The egui context to configure
+/// * `font_data` - The font data to use
+/// * `font_name` - Name for the font (optional, defaults to "chinese")
+/// * `tweak` - Scale and baseline correction for the font
+///
+/// # Returns
+/// * `Ok(LoadedFont)` describing the registered font
+/// * `Err(FontError::InvalidFont)` if `font_data` is not a usable font
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::setup_custom_chinese_font_with_tweak;
+///
+/// let ctx = egui::Context::default();
+/// # let font_data = egui::FontDefinitions::default().font_data["Ubuntu-Light"].font.to_vec();
+/// let tweak = egui::FontTweak {
+///     scale: 1.12,
+///     y_offset_factor: -0.04,
+///     ..Default::default()
+/// };
+/// setup_custom_chinese_font_with_tweak(&ctx, font_data, None, tweak)?;
+/// # Ok::<(), egui_chinese_font::FontError>(())
+/// ```
+pub fn setup_custom_chinese_font_with_tweak(
+    ctx: &Context,
+    font_data: Vec<u8>,
+    font_name: Option<&str>,
+    tweak: FontTweak,
+) -> Result<LoadedFont, FontError> {
+    let name = font_name.unwrap_or("chinese");
+    let font_data = Arc::new(validated_font_data(font_data, 0)?.tweak(tweak));
+
+    apply_chinese_font(ctx, name, Arc::clone(&font_data));
+
+    Ok(LoadedFont::new(name, FontOrigin::Memory, font_data))
+}
+
+/// Setup Chinese fonts with shared font data, without copying it
+///
+/// Registers `font_data` as-is, so a font loaded once can be shared between
+/// several contexts. Its face index and tweak are kept.
+///
+/// # Arguments
+/// * `ctx` - The egui context to configure
+/// * `font_data` - The shared font data to use
+/// * `font_name` - Name for the font (optional, defaults to "chinese")
+///
+/// # Returns
+/// * `Ok(LoadedFont)` describing the registered font
+/// * `Err(FontError::InvalidFont)` if `font_data` is not a usable font
+///
+/// # Example
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use egui_chinese_font::setup_custom_chinese_font_arc;
+///
+/// # let font_data = egui::FontDefinitions::default().font_data["Ubuntu-Light"].clone();
+/// let first = egui::Context::default();
+/// let second = egui::Context::default();
+/// setup_custom_chinese_font_arc(&first, Arc::clone(&font_data), None)?;
+/// setup_custom_chinese_font_arc(&second, Arc::clone(&font_data), None)?;
+///
+/// // Both contexts use the same allocation
+/// let _ = second.run(Default::default(), |_| {});
+/// let registered = second.fonts(|fonts| fonts.definitions().font_data["chinese"].clone());
+/// assert!(Arc::ptr_eq(&registered, &font_data));
+/// # Ok::<(), egui_chinese_font::FontError>(())
+/// ```
+pub fn setup_custom_chinese_font_arc(
+    ctx: &Context,
+    font_data: Arc<FontData>,
+    font_name: Option<&str>,
+) -> Result<LoadedFont, FontError> {
+    check_font(&font_data.font, font_data.index)?;
+
+    let name = font_name.unwrap_or("chinese");
+    apply_chinese_font(ctx, name, Arc::clone(&font_data));
+
+    Ok(LoadedFont::new(name, FontOrigin::Memory, font_data))
+}
+
+/// Setup Chinese fonts with static font data, e.g. from `include_bytes!`
+///
+/// The bytes are borrowed for the lifetime of the program instead of being
+/// copied into a new allocation.
+///
+/// # Arguments
+/// * `ctx` - The egui context to configure
+/// * `font_data` - The font data to use
+/// * `font_name` - Name for the font (optional, defaults to "chinese")
+///
+/// # Returns
+/// * `Ok(LoadedFont)` describing the registered font
+/// * `Err(FontError::InvalidFont)` if `font_data` is not a usable font
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::setup_custom_chinese_font_static;
+///
+/// // static FONT: &[u8] = include_bytes!("../assets/NotoSansSC-Regular.otf");
+/// # let font: &'static [u8] = egui::FontDefinitions::default().font_data["Ubuntu-Light"].font.to_vec().leak();
+/// let ctx = egui::Context::default();
+/// setup_custom_chinese_font_static(&ctx, font, None)?;
+/// # Ok::<(), egui_chinese_font::FontError>(())
+/// ```
+pub fn setup_custom_chinese_font_static(
+    ctx: &Context,
+    font_data: &'static [u8],
+    font_name: Option<&str>,
+) -> Result<LoadedFont, FontError> {
+    check_font(font_data, 0)?;
+
+    let name = font_name.unwrap_or("chinese");
+    let font_data = Arc::new(FontData::from_static(font_data));
+    apply_chinese_font(ctx, name, Arc::clone(&font_data));
+
+    Ok(LoadedFont::new(name, FontOrigin::Memory, font_data))
+}
+
+/// Load a font file through the crate's validation
+///
+/// Reads the file and checks its signature and character map, returning
+/// `FontData` ready to insert into [`egui::FontDefinitions`] or to pass to
+/// [`setup_custom_chinese_font_arc`]. Collections (`.ttc`) use their first
+/// face; set [`FontData::index`] or see [`list_ttc_faces`] to pick another.
+///
+/// # Arguments
+/// * `path` - Path to a `.ttf`, `.otf` or `.ttc` file
+///
+/// # Returns
+/// * `Ok(FontData)` if the file holds a usable font
+/// * `Err(FontError::PermissionDenied)` if the file is not readable by this process
+/// * `Err(FontError::ReadError)` if the file could not be read otherwise
+/// * `Err(FontError::InvalidFont)` if the file is not a usable font
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::{load_font_from_path, FontError};
+///
+/// let err = load_font_from_path("Cargo.toml").unwrap_err();
+/// assert!(matches!(err, FontError::InvalidFont { path: Some(_), .. }));
+/// let err = load_font_from_path("missing.ttf").unwrap_err();
+/// assert!(matches!(err, FontError::ReadError(_)));
+///
+/// // The underlying I/O error is available for error-chain libraries
+/// let source = std::error::Error::source(&err).unwrap();
+/// assert_eq!(source.downcast_ref::<std::io::Error>().unwrap().kind(), std::io::ErrorKind::NotFound);
+/// ```
+pub fn load_font_from_path(path: impl AsRef<std::path::Path>) -> Result<FontData, FontError> {
+    let path = path.as_ref();
+    let font_data = cache::read(path).map_err(|err| FontError::from_read(path, err))?;
+
+    faces::validate_font(&font_data, 0).map_err(|reason| FontError::InvalidFont {
+        path: Some(path.to_path_buf()),
+        reason,
+    })?;
+
+    Ok(cache::store(path, FontData {
+        font: font_data,
+        index: 0,
+        tweak: FontTweak::default(),
+    }))
+}
+
+/// Wrap caller-supplied font bytes after checking they hold a usable face
+fn validated_font_data(font_data: Vec<u8>, index: u32) -> Result<FontData, FontError> {
+    check_font(&font_data, index)?;
+
+    Ok(FontData {
+        index,
+        ..FontData::from_owned(font_data)
+    })
+}
+
+/// Check that caller-supplied font bytes hold a usable face at `index`
+fn check_font(font_data: &[u8], index: u32) -> Result<(), FontError> {
+    faces::validate_font(font_data, index).map_err(|reason| FontError::InvalidFont { path: None, reason })
+}
+
+/// Get the Chinese font paths the loader probes on this platform, in the order it tries them
+///
+/// Each of the [`PLATFORM_FONT_CANDIDATES`], the CJK fonts visible to Flatpak,
+/// Snap and Nix-packaged apps on Linux, and the per-user CJK fonts on
+/// Windows, macOS, Linux and the BSDs, is described by name, variant, format and
+/// search tier, then read and checked like the loader does. The first entry
+/// with `valid` set is the font [`setup_chinese_fonts`] uses unless the
+/// [`FONT_PATH_ENV`] override, the `system-query` or the `fc-match` feature
+/// picks another one. Candidates for the preferred [`ChineseVariant`] come
+/// first within each tier.
+///
+/// Every existing candidate is read in full, so only call this for
+/// diagnostics; [`get_chinese_font_paths_raw`] lists the paths without
+/// reading them.
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::{find_chinese_font, get_chinese_font_paths};
+///
+/// let report = get_chinese_font_paths();
+/// for info in &report {
+///     let name = info.display_name.unwrap_or("unknown font");
+///     match &info.rejection {
+///         None => println!("{} ({:?}, {:?}): ok", info.path.display(), name, info.format),
+///         Some(reason) => println!("{} ({:?}): {}", info.path.display(), name, reason),
+///     }
+/// }
+/// assert!(report.windows(2).all(|pair| pair[0].priority <= pair[1].priority));
+///
+/// // The loader uses the first font that passes the checks
+/// # #[cfg(not(any(feature = "system-query", feature = "fc-match")))]
+/// if let (Some(first), Ok(found)) = (report.iter().find(|info| info.valid), find_chinese_font()) {
+///     assert_eq!(found.path(), Some(first.path.as_path()));
+/// }
+/// ```
+pub fn get_chinese_font_paths() -> Vec<FontPathInfo> {
+    let query = default_probe_query();
+    platform::check_paths(platform::tiered_probe_order(&query), &query)
+}
+
+/// Get the Chinese font paths the loader probes on this platform, without checking them
+///
+/// Returns the same paths as [`get_chinese_font_paths`], in the same order,
+/// as the plain strings that function returned in the previous release.
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::{get_chinese_font_paths, get_chinese_font_paths_raw};
+///
+/// let paths = get_chinese_font_paths_raw();
+/// let checked: Vec<String> = get_chinese_font_paths()
+///     .into_iter()
+///     .map(|info| info.path.display().to_string())
+///     .collect();
+/// assert_eq!(paths, checked);
+/// ```
+pub fn get_chinese_font_paths_raw() -> Vec<String> {
+    platform::probe_order(&default_probe_query())
+}
+
+/// The query the default loader probes with, ignoring style and required characters
+fn default_probe_query() -> FontQuery<'static> {
+    FontQuery {
+        variant: VariantPreference::Auto.resolve(),
+        style: None,
+        required_chars: "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn face_index_past_the_end_is_an_error() {
+        let defaults = FontDefinitions::default();
+        let ttc = testing::font_collection(&[&defaults.font_data["Ubuntu-Light"].font, &defaults.font_data["Hack"].font]);
+        let path = std::env::temp_dir().join(format!("egui-chinese-font-face-index-test-{}.ttc", std::process::id()));
+        std::fs::write(&path, ttc).unwrap();
+
+        let ctx = Context::default();
+        let config = FontConfig::new().with_font_path(&path).with_face_index(Some(1));
+        assert!(setup_chinese_fonts_with_config(&ctx, &config).is_ok());
+
+        let config = config.with_face_index(Some(2));
+        assert!(matches!(find_chinese_font_with_config(&config), Err(FontError::InvalidFont { .. })));
+        assert!(matches!(setup_chinese_fonts_with_config(&ctx, &config), Err(FontError::InvalidFont { .. })));
+        // The context still renders with its previous fonts
+        let _ = ctx.run(Default::default(), |_| {});
 
-    fonts.font_data.insert(
-        name.to_owned(),
-        Arc::new(FontData::from_owned(font_data)),
-    );
-
-    fonts.families.entry(FontFamily::Proportional).or_default()
-        .insert(0, name.to_owned());
-    fonts.families.entry(FontFamily::Monospace).or_default()
-        .insert(0, name.to_owned());
-
-    ctx.set_fonts(fonts);
-}
-
-/// Get available Chinese font paths on the current system
-///
-/// This function returns a list of paths where Chinese fonts might be located
-/// on the current platform. Useful for debugging font loading issues.
-pub fn get_chinese_font_paths() -> Vec<String> {
-    #[cfg(target_os = "windows")]
-    {
-        vec![
-            r"C:\Windows\Fonts\msyh.ttc".to_string(),
-            r"C:\Windows\Fonts\msyhbd.ttc".to_string(),
-            r"C:\Windows\Fonts\simsun.ttc".to_string(),
-            r"C:\Windows\Fonts\simhei.ttf".to_string(),
-            r"C:\Windows\Fonts\simkai.ttf".to_string(),
-            r"C:\Windows\Fonts\simfang.ttf".to_string(),
-            r"C:\Windows\Fonts\msjh.ttc".to_string(),
-            r"C:\Windows\Fonts\msjhbd.ttc".to_string(),
-        ]
-    }
-
-    #[cfg(target_os = "macos")]
-    {
-        vec![
-            "/System/Library/Fonts/PingFang.ttc".to_string(),
-            "/System/Library/Fonts/STHeiti Light.ttc".to_string(),
-            "/System/Library/Fonts/STHeiti Medium.ttc".to_string(),
-            "/System/Library/Fonts/Hiragino Sans GB.ttc".to_string(),
-            "/Library/Fonts/Arial Unicode.ttf".to_string(),
-        ]
-    }
-
-    #[cfg(target_os = "linux")]
-    {
-        vec![
-            "/usr/share/fonts/truetype/droid/DroidSansFallbackFull.ttf".to_string(),
-            "/usr/share/fonts/truetype/arphic/uming.ttc".to_string(),
-            "/usr/share/fonts/truetype/wqy/wqy-microhei.ttc".to_string(),
-            "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc".to_string(),
-        ]
-    }
-
-    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
-    {
-        vec![]
+        std::fs::remove_file(path).unwrap();
     }
 }
@@ -0,0 +1,158 @@
+//! Monospaced CJK fonts for the `Monospace` family
+
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+use egui::FontData;
+use ttf_parser::Face;
+
+use crate::platform::{FontQuery, FoundFont};
+use crate::{faces, ChineseVariant};
+
+/// How the Chinese font is added to egui's `Monospace` family
+///
+/// Most Chinese fonts have proportional Latin glyphs. Putting one first in
+/// `Monospace` makes egui use those glyphs instead of its monospaced Latin
+/// font, which breaks column alignment in code editors and tables.
+///
+/// # Example
+///
+/// ```rust
+/// use egui::FontFamily;
+/// use egui_chinese_font::{setup_chinese_fonts_with_config, FontConfig, MonospacePolicy};
+/// # struct Provider;
+/// # impl egui_chinese_font::FontProvider for Provider {
+/// #     fn load(&self) -> Result<egui::FontData, egui_chinese_font::FontError> {
+/// #         Ok(egui::FontDefinitions::default().font_data["Ubuntu-Light"].as_ref().clone())
+/// #     }
+/// #     fn available_paths(&self) -> Vec<String> { Vec::new() }
+/// # }
+///
+/// let ctx = egui::Context::default();
+/// let config = FontConfig::new().with_monospace(MonospacePolicy::Append);
+/// # let config = config.with_provider(Box::new(Provider));
+/// setup_chinese_fonts_with_config(&ctx, &config)?;
+///
+/// let _ = ctx.run(Default::default(), |_| {});
+/// let families = ctx.fonts(|fonts| fonts.definitions().families.clone());
+/// assert_eq!(families[&FontFamily::Proportional][0], "chinese");
+/// assert_eq!(families[&FontFamily::Monospace][0], "Hack");
+/// assert_eq!(families[&FontFamily::Monospace].last().unwrap(), "chinese");
+/// # Ok::<(), egui_chinese_font::FontError>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MonospacePolicy {
+    /// Put the Chinese font where it goes in `Proportional`: first, or last with [`FontPriority::Fallback`](crate::FontPriority::Fallback)
+    #[default]
+    SameAsProportional,
+    /// Add the Chinese font last, so it only provides glyphs the monospaced Latin fonts lack
+    Append,
+    /// Leave the `Monospace` family untouched
+    Skip,
+    /// Use a monospaced CJK font such as Noto Sans Mono CJK, NSimSun or Sarasa Mono
+    ///
+    /// The font is registered as `"chinese-mono"` at the front of `Monospace`,
+    /// followed by the regular Chinese font as a last fallback. Without an
+    /// installed monospaced CJK font this behaves like [`MonospacePolicy::Append`].
+    Dedicated,
+}
+
+/// Font files that may contain a monospaced CJK face, in priority order
+#[cfg(target_os = "windows")]
+const MONO_FONT_CANDIDATES: &[&str] = &[
+    r"C:\Windows\Fonts\simsun.ttc",  // NSimSun
+    r"C:\Windows\Fonts\mingliu.ttc", // MingLiU
+];
+
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+const MONO_FONT_CANDIDATES: &[&str] = &[
+    // Noto Sans CJK collections include the Noto Sans Mono CJK faces
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/google-noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/wqy/wqy-microhei.ttc", // WenQuanYi Micro Hei Mono
+    "/usr/share/fonts/truetype/wqy/wqy-zenhei.ttc",   // WenQuanYi Zen Hei Mono
+    "/system/fonts/NotoSansCJK-Regular.ttc",
+    "/usr/local/share/fonts/noto/NotoSansCJK-Regular.ttc",
+    "/usr/local/share/fonts/wqy/wqy-microhei.ttc",
+];
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "android", target_os = "freebsd", target_os = "openbsd", target_os = "netbsd")))]
+const MONO_FONT_CANDIDATES: &[&str] = &[];
+
+/// Find a face with monospaced Latin glyphs that covers Chinese text
+///
+/// Searches the known candidates, then per-user fonts with "mono" in their
+/// name, preferring faces designed for the query's variant.
+pub(crate) fn load_mono_font(query: &FontQuery<'_>) -> Option<FoundFont> {
+    let mut paths: Vec<PathBuf> = MONO_FONT_CANDIDATES.iter().map(PathBuf::from).collect();
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "windows",
+        target_os = "macos",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd"
+    ))]
+    paths.extend(
+        crate::scan_user_fonts()
+            .into_iter()
+            .filter(|path| path.to_string_lossy().to_ascii_lowercase().contains("mono")),
+    );
+
+    let mut fallback = None;
+    for path in paths {
+        let Ok(data) = crate::cache::read(&path) else {
+            continue;
+        };
+        let Some((index, variant)) = find_mono_face(&data, query) else {
+            continue;
+        };
+        if variant == Some(query.variant) {
+            return Some(found_font(&path, data, index));
+        }
+        fallback.get_or_insert((path, data, index));
+    }
+
+    fallback.map(|(path, data, index)| found_font(&path, data, index))
+}
+
+/// The index and variant of the best monospaced Chinese face in `data`
+fn find_mono_face(data: &[u8], query: &FontQuery<'_>) -> Option<(u32, Option<ChineseVariant>)> {
+    let mut found: Option<(u32, Option<ChineseVariant>)> = None;
+    for (index, face) in faces::faces(data) {
+        if !has_monospaced_latin(&face) || crate::coverage::check_candidate(data, index, query.required_chars).is_err() {
+            continue;
+        }
+        let variant = faces::english_family(&face).and_then(|family| faces::family_variant(&family));
+        if variant == Some(query.variant) {
+            return Some((index, variant));
+        }
+        if found.map_or(true, |(_, found_variant)| found_variant.is_none() && variant.is_some()) {
+            found = Some((index, variant));
+        }
+    }
+
+    found
+}
+
+/// Whether narrow and wide Latin letters have the same advance
+///
+/// CJK monospace fonts often don't set the fixed-pitch flag, because their
+/// Han glyphs are twice as wide as Latin ones, so the advances are compared.
+fn has_monospaced_latin(face: &Face<'_>) -> bool {
+    let advance = |c| face.glyph_index(c).and_then(|glyph| face.glyph_hor_advance(glyph));
+    matches!((advance('i'), advance('M')), (Some(narrow), Some(wide)) if narrow == wide)
+}
+
+fn found_font(path: &Path, font: Cow<'static, [u8]>, index: u32) -> FoundFont {
+    log!(info, "using {} (face {}) for monospace text", path.display(), index);
+
+    let font_data = FontData {
+        font,
+        index,
+        tweak: Default::default(),
+    };
+    FoundFont::new(crate::cache::store(path, font_data), Some(path.to_path_buf()))
+}
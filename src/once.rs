@@ -0,0 +1,102 @@
+//! Setting up Chinese fonts at most once per context
+
+use std::sync::{Arc, OnceLock};
+
+use egui::{Context, Id};
+
+use crate::{ChineseFontExt, FontError, LoadedFont};
+
+/// Result of the first setup, shared by every later call on the same context
+type SetupOnce = Arc<OnceLock<Result<LoadedFont, FontError>>>;
+
+/// Setup Chinese fonts the first time this is called for `ctx`
+///
+/// Meant for modular applications where several components share a context
+/// and each makes sure Chinese text displays during initialization. The first
+/// call searches for a font and registers it like
+/// [`setup_chinese_fonts`](crate::setup_chinese_fonts); later calls, from any
+/// thread, return its result without reading fonts or calling
+/// [`Context::set_fonts`] again, which would rebuild the font atlas each time.
+/// Calls racing with the first one wait for it to finish.
+///
+/// The result is kept in the context's memory, so each context is set up
+/// separately. A failure is kept too and returned again; call
+/// [`setup_chinese_fonts`](crate::setup_chinese_fonts) directly to retry, e.g.
+/// after installing a font.
+///
+/// # Returns
+/// * `Ok(LoadedFont)` describing the font registered by the first call
+/// * `Err(FontError)` if the first call failed to load a font
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::setup_chinese_fonts_once;
+///
+/// let ctx = egui::Context::default();
+///
+/// // Components initialized on different threads each make sure the fonts are set up
+/// std::thread::scope(|scope| {
+///     for component in ["editor", "preview"] {
+///         let ctx = &ctx;
+///         scope.spawn(move || {
+///             if let Err(err) = setup_chinese_fonts_once(ctx) {
+///                 eprintln!("{}: Chinese text may not display: {}", component, err);
+///             }
+///         });
+///     }
+/// });
+/// ```
+pub fn setup_chinese_fonts_once(ctx: &Context) -> Result<LoadedFont, FontError> {
+    setup_once(ctx, || ctx.setup_chinese_fonts())
+}
+
+/// Run `setup` unless an earlier call on `ctx` did, returning the first result
+fn setup_once(ctx: &Context, setup: impl FnOnce() -> Result<LoadedFont, FontError>) -> Result<LoadedFont, FontError> {
+    let once: SetupOnce = ctx.data_mut(|data| data.get_temp_mut_or_default::<SetupOnce>(setup_once_id()).clone());
+    once.get_or_init(setup).clone()
+}
+
+fn setup_once_id() -> Id {
+    Id::new("egui_chinese_font::setup_once")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::testing::chinese_font_config;
+
+    #[test]
+    fn later_calls_share_the_first_result() {
+        let (path, config) = chinese_font_config("once-test");
+        let ctx = Context::default();
+        let setups = AtomicUsize::new(0);
+        let setup = || {
+            setups.fetch_add(1, Ordering::SeqCst);
+            crate::setup_chinese_fonts_with_config(&ctx, &config)
+        };
+
+        let loaded: Vec<_> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4).map(|_| scope.spawn(|| setup_once(&ctx, setup))).collect();
+            handles.into_iter().map(|handle| handle.join().unwrap().unwrap()).collect()
+        });
+        assert_eq!(setups.load(Ordering::SeqCst), 1);
+        assert!(loaded.windows(2).all(|pair| Arc::ptr_eq(pair[0].font_data(), pair[1].font_data())));
+
+        // Other contexts run their own setup
+        setup_once(&Context::default(), setup).unwrap();
+        assert_eq!(setups.load(Ordering::SeqCst), 2);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn failures_are_kept() {
+        let ctx = Context::default();
+        assert_eq!(setup_once(&ctx, || Err(FontError::UnsupportedPlatform)).unwrap_err(), FontError::UnsupportedPlatform);
+        let result = setup_once(&ctx, || unreachable!("setup ran again"));
+        assert_eq!(result.unwrap_err(), FontError::UnsupportedPlatform);
+    }
+}
@@ -0,0 +1,333 @@
+//! Remembering the discovered font file between runs
+//!
+//! Probing and validating a dozen multi-megabyte collections on every launch
+//! is slow on spinning disks and network home directories. The cache file
+//! records which file won, so later runs read only that file as long as it
+//! is unchanged.
+
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use egui::{Context, FontData};
+
+use crate::{FontConfig, FontError, FoundFont, LoadedFont, FONT_PATH_ENV};
+
+/// First line of a cache file, changed whenever the format changes
+const HEADER: &str = "egui-chinese-font path cache v1";
+
+/// The font file found by a previous run, and what it looked like then
+#[derive(Debug, PartialEq, Eq)]
+struct CachedPath {
+    /// The variant, style and required characters the font was found for
+    query: String,
+    path: PathBuf,
+    index: u32,
+    size: u64,
+    /// Modification time in nanoseconds since the Unix epoch
+    modified: u128,
+}
+
+impl CachedPath {
+    fn parse(contents: &str) -> Option<Self> {
+        let mut lines = contents.lines();
+        if lines.next()? != HEADER {
+            return None;
+        }
+        let cached = CachedPath {
+            query: lines.next()?.to_owned(),
+            index: lines.next()?.parse().ok()?,
+            size: lines.next()?.parse().ok()?,
+            modified: lines.next()?.parse().ok()?,
+            path: PathBuf::from(lines.next()?),
+        };
+        lines.next().is_none().then_some(cached)
+    }
+
+    fn to_contents(&self) -> Option<String> {
+        let path = self.path.to_str().filter(|path| !path.contains('\n'))?;
+        Some(format!("{}\n{}\n{}\n{}\n{}\n{}\n", HEADER, self.query, self.index, self.size, self.modified, path))
+    }
+
+    /// Describe the file at `path` as it is now
+    fn of_file(query: String, path: PathBuf, index: u32) -> Option<Self> {
+        let metadata = std::fs::metadata(&path).ok()?;
+        let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?.as_nanos();
+        Some(CachedPath {
+            query,
+            path,
+            index,
+            size: metadata.len(),
+            modified,
+        })
+    }
+}
+
+/// Setup Chinese fonts, remembering the chosen font file in `cache_file`
+///
+/// The first run searches like [`setup_chinese_fonts`](crate::setup_chinese_fonts)
+/// and records the path, size and modification time of the font it picks.
+/// Later runs load that file directly, skipping the search, as long as its
+/// size and modification time are unchanged. If the file changed or was
+/// uninstalled, or the cache file is missing or corrupted, the fonts are
+/// searched again and the cache is rewritten; a broken cache never makes
+/// setup fail. Failing to write the cache is only logged.
+///
+/// # Arguments
+/// * `ctx` - The egui context to configure
+/// * `cache_file` - Where to keep the cache, e.g. in the application's cache directory
+///
+/// # Returns
+/// * `Ok(LoadedFont)` describing the registered font
+/// * `Err(FontError)` if font loading failed
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::setup_chinese_fonts_cached;
+///
+/// let cache_file = std::env::temp_dir().join("egui-chinese-font-example.cache");
+/// # let _ = std::fs::remove_file(&cache_file);
+///
+/// // The first run searches the fonts and remembers the one it picks
+/// let ctx = egui::Context::default();
+/// if let Ok(loaded) = setup_chinese_fonts_cached(&ctx, &cache_file) {
+///     // The next run loads that file without searching
+///     let ctx = egui::Context::default();
+///     let again = setup_chinese_fonts_cached(&ctx, &cache_file)?;
+///     assert_eq!(again.font_data().font, loaded.font_data().font);
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn setup_chinese_fonts_cached(ctx: &Context, cache_file: impl AsRef<Path>) -> Result<LoadedFont, FontError> {
+    setup_chinese_fonts_cached_with_config(ctx, cache_file, &FontConfig::default())
+}
+
+/// Setup Chinese fonts using a custom [`FontConfig`], remembering the chosen font file in `cache_file`
+///
+/// The cache is bypassed when [`FONT_PATH_ENV`] is set or the config has a
+/// font path or a custom provider. A cache written for another variant, style or set of
+/// required characters is ignored. See [`setup_chinese_fonts_cached`].
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::{setup_chinese_fonts_cached_with_config, ChineseStyle, FontConfig};
+///
+/// let cache_file = std::env::temp_dir().join("egui-chinese-font-config-example.cache");
+/// let config = FontConfig::new().with_style(ChineseStyle::Kai);
+///
+/// // Remembers a Kai font; a cache written for another style is searched again
+/// let ctx = egui::Context::default();
+/// if let Err(err) = setup_chinese_fonts_cached_with_config(&ctx, &cache_file, &config) {
+///     eprintln!("no Kai font: {}", err);
+/// }
+/// ```
+pub fn setup_chinese_fonts_cached_with_config(
+    ctx: &Context,
+    cache_file: impl AsRef<Path>,
+    config: &FontConfig,
+) -> Result<LoadedFont, FontError> {
+    let cache_file = cache_file.as_ref();
+    let bypass = config.provider.is_some()
+        || config.font_path.is_some()
+        || std::env::var_os(FONT_PATH_ENV).is_some_and(|path| !path.is_empty());
+    if bypass {
+        return crate::setup_chinese_fonts_with_config(ctx, config);
+    }
+
+    let mut query = format!("{:?} {:?} {:?}", config.variant.resolve(), config.style, config.required_chars);
+    if !config.search_paths.is_empty() {
+        query = format!("{} {:?} {:?}", query, config.search_paths, config.path_mode);
+    }
+    let result = match load_cached(cache_file, &query, config) {
+        Some(font_data) => Ok(font_data),
+        None => discover(cache_file, query, config),
+    };
+
+    let result = result.map(|font_data| crate::register_chinese_font(ctx, font_data, config));
+    config.notify_loaded(result.as_ref());
+    result
+}
+
+/// The default place for the cache file of [`setup_chinese_fonts_cached`]
+///
+/// Enabled with the `path-cache` feature. `font-path.cache` in this crate's cache directory, as named by
+/// [`directories::ProjectDirs::cache_dir`]:
+///
+/// * Linux and the BSDs: `$XDG_CACHE_HOME/egui-chinese-font` (defaulting to `~/.cache/egui-chinese-font`)
+/// * macOS: `~/Library/Caches/egui-chinese-font`
+/// * Windows: `{FOLDERID_LocalAppData}\egui-chinese-font\cache`
+///
+/// Returns `None` if the directory is unknown, e.g. without a home
+/// directory. The directory is created when the cache is first written.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use egui_chinese_font::{clear_font_path_cache, default_font_path_cache_file, setup_chinese_fonts_cached};
+///
+/// let ctx = egui::Context::default();
+/// if let Some(cache_file) = default_font_path_cache_file() {
+///     let _ = setup_chinese_fonts_cached(&ctx, &cache_file);
+///
+///     // Force a search on the next run, e.g. from a "reload fonts" menu entry
+///     clear_font_path_cache(&cache_file)?;
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(feature = "path-cache")]
+pub fn default_font_path_cache_file() -> Option<PathBuf> {
+    let dirs = directories::ProjectDirs::from("", "", "egui-chinese-font")?;
+    Some(dirs.cache_dir().join("font-path.cache"))
+}
+
+/// Forget the font file remembered in `cache_file`
+///
+/// The next [`setup_chinese_fonts_cached`] searches the fonts again. A
+/// missing cache file is not an error.
+#[doc(alias = "bust_font_cache")]
+pub fn clear_font_path_cache(cache_file: impl AsRef<Path>) -> std::io::Result<()> {
+    match std::fs::remove_file(cache_file) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+/// Load the font remembered in `cache_file`, if it is still there and unchanged
+///
+/// The file is loaded like [`discover`] loads a font, as if it was the
+/// config's font path, so the read timeout and face selection apply alike.
+fn load_cached(cache_file: &Path, query: &str, config: &FontConfig) -> Option<FontData> {
+    let cached = CachedPath::parse(&std::fs::read_to_string(cache_file).ok()?);
+    let Some(cached) = cached.filter(|cached| cached.query == query) else {
+        log!(debug, "{}: ignoring unusable font path cache", cache_file.display());
+        return None;
+    };
+
+    if CachedPath::of_file(cached.query.clone(), cached.path.clone(), cached.index).as_ref() != Some(&cached) {
+        log!(info, "cached font {} changed or was removed, searching again", cached.path.display());
+        return None;
+    }
+
+    match load(&config.clone().with_font_path(&cached.path)) {
+        Ok(found) => {
+            log!(info, "using cached font path {} (face {})", cached.path.display(), found.font_data.index);
+            Some(found.font_data)
+        }
+        Err(err) => {
+            log!(info, "cached font {} is unusable, searching again: {}", cached.path.display(), err);
+            None
+        }
+    }
+}
+
+/// Search for the font and remember it in `cache_file`
+fn discover(cache_file: &Path, query: String, config: &FontConfig) -> Result<FontData, FontError> {
+    let found = load(config);
+
+    let cached = found
+        .as_ref()
+        .ok()
+        .and_then(|found| CachedPath::of_file(query, found.path.clone()?, found.font_data.index))
+        .and_then(|cached| cached.to_contents());
+    let written = match cached {
+        Some(contents) => write_cache(cache_file, &contents),
+        // Nothing worth remembering, e.g. the bundled font; drop any stale entry
+        None => clear_font_path_cache(cache_file),
+    };
+    if let Err(err) = written {
+        log!(warn, "{}: can't update font path cache ({})", cache_file.display(), err);
+    }
+
+    found.map(|found| found.font_data)
+}
+
+/// Find the font for `config`, keeping it out of the font cache if the config replaces its data
+fn load(config: &FontConfig) -> Result<FoundFont, FontError> {
+    let locate = || crate::locate_chinese_font(config.variant.resolve(), config);
+    if config.replaces_font_data() {
+        crate::cache::without_storing(locate)
+    } else {
+        locate()
+    }
+}
+
+fn write_cache(cache_file: &Path, contents: &str) -> std::io::Result<()> {
+    if let Some(dir) = cache_file.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(cache_file, contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::chinese_font_config;
+
+    fn cache_file(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("egui-chinese-font-{}-{}.cache", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn cache_contents_round_trip() {
+        let cached = CachedPath {
+            query: "Simplified None \"\"".to_owned(),
+            path: PathBuf::from("/fonts/Chinese.ttc"),
+            index: 2,
+            size: 1024,
+            modified: 1_700_000_000_000_000_000,
+        };
+        let contents = cached.to_contents().unwrap();
+        assert_eq!(CachedPath::parse(&contents), Some(cached));
+
+        assert_eq!(CachedPath::parse(&contents.replace("v1", "v0")), None);
+        assert_eq!(CachedPath::parse(&format!("{}extra\n", contents)), None);
+        assert_eq!(CachedPath::parse(&contents[..contents.len() / 2]), None);
+    }
+
+    #[test]
+    fn remembers_the_font_while_unchanged() {
+        let (font, config) = chinese_font_config("path-cache-test");
+        let cache_file = cache_file("path-cache-test");
+        let ctx = Context::default();
+
+        // The first run finds the font and remembers it
+        setup_chinese_fonts_cached_with_config(&ctx, &cache_file, &config).unwrap();
+        assert!(std::fs::read_to_string(&cache_file).unwrap().contains(&*font.to_string_lossy()));
+        let query = std::fs::read_to_string(&cache_file).unwrap().lines().nth(1).unwrap().to_owned();
+        assert!(load_cached(&cache_file, &query, &config).is_some());
+        assert!(load_cached(&cache_file, "another query", &config).is_none());
+
+        // A changed file is searched for again
+        let mut changed = crate::testing::chinese_font();
+        changed.push(0);
+        std::fs::write(&font, changed).unwrap();
+        assert!(load_cached(&cache_file, &query, &config).is_none());
+        setup_chinese_fonts_cached_with_config(&ctx, &cache_file, &config).unwrap();
+        assert!(load_cached(&cache_file, &query, &config).is_some());
+
+        // An uninstalled one is forgotten
+        std::fs::remove_file(&font).unwrap();
+        let _ = setup_chinese_fonts_cached_with_config(&ctx, &cache_file, &config);
+        let cache = std::fs::read_to_string(&cache_file).unwrap_or_default();
+        assert!(!cache.contains(&*font.to_string_lossy()));
+
+        clear_font_path_cache(&cache_file).unwrap();
+        assert!(!cache_file.exists());
+        clear_font_path_cache(&cache_file).unwrap();
+    }
+
+    #[test]
+    fn font_path_bypasses_the_cache() {
+        let (font, config) = chinese_font_config("path-cache-bypass-test");
+        let cache_file = cache_file("path-cache-bypass-test");
+
+        let config = config.with_font_path(&font);
+        setup_chinese_fonts_cached_with_config(&Context::default(), &cache_file, &config).unwrap();
+        assert!(!cache_file.exists());
+
+        std::fs::remove_file(font).unwrap();
+    }
+}
@@ -0,0 +1,850 @@
+//! Probing the current platform's well-known font paths
+
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use egui::FontData;
+
+use crate::ChineseVariant::{self, Simplified, Traditional};
+use crate::{AttemptOutcome, ChineseStyle, FontAttempt, FontError, PathMode, RejectReason};
+
+/// What a caller is looking for in a Chinese font
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FontQuery<'a> {
+    /// Variant whose fonts are tried first
+    pub variant: ChineseVariant,
+    /// Only accept fonts of this style, if set
+    pub style: Option<ChineseStyle>,
+    /// Characters every accepted font must cover
+    pub required_chars: &'a str,
+}
+
+/// The Chinese font that would be used, as reported by [`find_chinese_font`](crate::find_chinese_font)
+#[derive(Clone)]
+pub struct FoundFont {
+    pub(crate) font_data: FontData,
+    pub(crate) path: Option<PathBuf>,
+}
+
+impl FoundFont {
+    pub(crate) fn new(font_data: FontData, path: Option<PathBuf>) -> Self {
+        Self { font_data, path }
+    }
+
+    /// The file the font is read from, or `None` for custom providers and the bundled font
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// The English family name of the chosen face, e.g. `Noto Sans CJK SC`
+    pub fn family(&self) -> Option<String> {
+        let face = ttf_parser::Face::parse(&self.font_data.font, self.font_data.index).ok()?;
+        crate::faces::english_family(&face)
+    }
+
+    /// Size of the font file in bytes
+    pub fn file_size(&self) -> u64 {
+        self.font_data.font.len() as u64
+    }
+
+    /// Whether the font is a `.ttc` collection holding several faces
+    pub fn is_collection(&self) -> bool {
+        self.font_data.font.starts_with(b"ttcf")
+    }
+
+    /// Index of the chosen face within a collection, `0` for single fonts
+    pub fn face_index(&self) -> u32 {
+        self.font_data.index
+    }
+}
+
+impl std::fmt::Debug for FoundFont {
+    // `FontData` would print every byte of the font
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FoundFont")
+            .field("path", &self.path)
+            .field("family", &self.family())
+            .field("file_size", &self.file_size())
+            .field("is_collection", &self.is_collection())
+            .field("face_index", &self.face_index())
+            .finish()
+    }
+}
+
+/// A candidate font path and whether the loader would accept it, as reported by
+/// [`get_chinese_font_paths`](crate::get_chinese_font_paths)
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FontPathInfo {
+    /// The font file
+    pub path: PathBuf,
+    /// Name of the font, e.g. `"Microsoft YaHei"`, if it is one of the known candidates
+    pub display_name: Option<&'static str>,
+    /// The variant the font is designed for, guessed from the file name
+    pub variant: ChineseVariant,
+    /// The file format, from the file extension
+    pub format: Option<FontFormat>,
+    /// The search tier the path belongs to; lower tiers are tried first
+    ///
+    /// On Windows per-user fonts are tier 0 and the platform candidates tier 1.
+    /// Elsewhere the platform candidates are tier 0; on Linux they are followed
+    /// by host fonts of Flatpak and Snap sandboxes and fonts in Nix profiles,
+    /// then per-user fonts.
+    pub priority: u8,
+    /// Whether the file exists
+    pub exists: bool,
+    /// Size of the file in bytes, if it exists
+    pub size: Option<u64>,
+    /// Whether the font is valid and covers common Chinese characters
+    pub valid: bool,
+    /// Why the loader would skip the file, if it would
+    pub rejection: Option<String>,
+}
+
+/// The format of a font file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum FontFormat {
+    /// A single TrueType font (`.ttf`)
+    TrueType,
+    /// A single OpenType font with CFF outlines (`.otf`)
+    OpenType,
+    /// A TrueType or OpenType collection holding several faces (`.ttc`, `.otc`)
+    Collection,
+}
+
+impl FontFormat {
+    /// The format a file name's extension stands for
+    fn from_file_name(file_name: &str) -> Option<Self> {
+        let (_, extension) = file_name.rsplit_once('.')?;
+        match extension.to_ascii_lowercase().as_str() {
+            "ttf" => Some(FontFormat::TrueType),
+            "otf" => Some(FontFormat::OpenType),
+            "ttc" | "otc" => Some(FontFormat::Collection),
+            _ => None,
+        }
+    }
+}
+
+/// A font file and the variant and style it is designed for
+#[derive(Debug, Clone, Copy)]
+struct FontCandidate<'a> {
+    path: &'a str,
+    variant: ChineseVariant,
+    style: ChineseStyle,
+}
+
+impl<'a> FontCandidate<'a> {
+    /// Describe a font file, guessing its variant from the file name
+    ///
+    /// The style of a well-known font comes from [`known_style`]; only other
+    /// files have it guessed from their name.
+    fn from_path(path: &'a str) -> Self {
+        let file_name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+        Self {
+            path,
+            variant: variant_from_file_name(file_name),
+            style: known_style(file_name).unwrap_or_else(|| ChineseStyle::from_file_name(file_name)),
+        }
+    }
+}
+
+/// Chinese font files probed on Windows, in priority order
+pub const WINDOWS_FONT_CANDIDATES: &[&str] = &[
+    r"C:\Windows\Fonts\msyh.ttc",    // Microsoft YaHei
+    r"C:\Windows\Fonts\msyhbd.ttc",  // Microsoft YaHei Bold
+    r"C:\Windows\Fonts\simsun.ttc",  // SimSun
+    r"C:\Windows\Fonts\simhei.ttf",  // SimHei
+    r"C:\Windows\Fonts\simkai.ttf",  // KaiTi
+    r"C:\Windows\Fonts\simfang.ttf", // FangSong
+    r"C:\Windows\Fonts\msjh.ttc",    // Microsoft JhengHei
+    r"C:\Windows\Fonts\msjhbd.ttc",  // Microsoft JhengHei Bold
+    r"C:\Windows\Fonts\kaiu.ttf",    // DFKai-SB
+    r"C:\Windows\Fonts\mingliu.ttc", // MingLiU
+];
+
+/// Chinese font files probed on macOS, in priority order
+pub const MACOS_FONT_CANDIDATES: &[&str] = &[
+    "/System/Library/Fonts/PingFang.ttc",       // PingFang SC (macOS 10.14 and earlier)
+    // The system UI copy of PingFang on macOS 11+, where the regular one is a downloadable asset
+    "/System/Library/PrivateFrameworks/FontServices.framework/Resources/Reserved/PingFangUI.ttc",
+    "/System/Library/Fonts/STHeiti Light.ttc",  // STHeiti
+    "/System/Library/Fonts/STHeiti Medium.ttc",
+    "/System/Library/Fonts/Hiragino Sans GB.ttc", // Hiragino Sans GB
+    // Adobe Source Han and Google Noto CJK, installed by hand or by Homebrew casks
+    "/Library/Fonts/SourceHanSansSC-Regular.otf",
+    "/Library/Fonts/SourceHanSansTC-Regular.otf",
+    "/Library/Fonts/SourceHanSans-Regular.ttc",
+    "/Library/Fonts/NotoSansCJKsc-Regular.otf",
+    "/Library/Fonts/NotoSansCJKtc-Regular.otf",
+    "/Library/Fonts/NotoSansCJK-Regular.ttc",
+    "/Library/Fonts/Arial Unicode.ttf",         // Arial Unicode MS
+    "/System/Library/Fonts/Apple LiGothic Medium.ttf", // Apple LiGothic
+    "/System/Library/Fonts/Supplemental/Songti.ttc", // Songti SC / TC
+    "/Library/Fonts/SourceHanSerifSC-Regular.otf",
+    "/Library/Fonts/SourceHanSerifTC-Regular.otf",
+    "/Library/Fonts/NotoSerifCJKsc-Regular.otf",
+    "/Library/Fonts/NotoSerifCJK-Regular.ttc",
+];
+
+/// Chinese font files probed on iOS and iPadOS, in priority order
+///
+/// The system font directory has been reorganized between iOS releases, so
+/// each font is listed at every location it has been shipped in.
+pub const IOS_FONT_CANDIDATES: &[&str] = &[
+    "/System/Library/Fonts/LanguageSupport/PingFang.ttc", // PingFang SC / TC / HK (iOS 14+)
+    "/System/Library/Fonts/Core/PingFang.ttc",
+    "/System/Library/Fonts/PingFang.ttc",
+    "/System/Library/Fonts/Cache/STHeiti-Light.ttc", // STHeiti (iOS 8 and earlier)
+];
+
+/// Chinese font files probed on Linux, in priority order
+pub const LINUX_FONT_CANDIDATES: &[&str] = &[
+    "/usr/share/fonts/truetype/droid/DroidSansFallbackFull.ttf",
+    "/usr/share/fonts/truetype/arphic/uming.ttc",
+    "/usr/share/fonts/truetype/arphic/ukai.ttc",
+    "/usr/share/fonts/truetype/wqy/wqy-microhei.ttc",
+    "/usr/share/fonts/truetype/wqy/wqy-zenhei.ttc",
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    // Adobe Source Han Sans, the same design as Noto Sans CJK
+    "/usr/share/fonts/opentype/source-han-sans/SourceHanSans-Regular.ttc",
+    "/usr/share/fonts/adobe-source-han-sans/SourceHanSans.ttc", // Arch Linux
+    "/usr/share/fonts/adobe-source-han-sans-cn/SourceHanSansCN-Regular.otf", // Fedora
+    "/usr/share/fonts/adobe-source-han-sans-tw/SourceHanSansTW-Regular.otf",
+    "/usr/share/fonts/google-noto-sans-cjk-fonts/NotoSansCJK-Regular.ttc", // Fedora 38+
+    "/usr/share/fonts/truetype/liberation/LiberationSans-Regular.ttf",
+    // Ubuntu/Debian paths
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    // CentOS/RHEL paths
+    "/usr/share/fonts/google-droid/DroidSansFallbackFull.ttf",
+    // Arch Linux paths
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    // Serif faces, only preferred when Song style is requested
+    "/usr/share/fonts/opentype/noto/NotoSerifCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSerifCJK-Regular.ttc",
+    "/usr/share/fonts/opentype/source-han-serif/SourceHanSerif-Regular.ttc",
+    "/usr/share/fonts/adobe-source-han-serif/SourceHanSerif.ttc",
+    "/usr/share/fonts/adobe-source-han-serif-cn/SourceHanSerifCN-Regular.otf",
+    "/usr/share/fonts/adobe-source-han-serif-tw/SourceHanSerifTW-Regular.otf",
+    "/usr/share/fonts/google-noto-serif-cjk-fonts/NotoSerifCJK-Regular.ttc",
+];
+
+/// Chinese font files probed on Android, in priority order
+pub const ANDROID_FONT_CANDIDATES: &[&str] = &[
+    "/system/fonts/NotoSansCJK-Regular.ttc", // Noto Sans CJK (Android 7+)
+    "/system/fonts/DroidSansFallback.ttf",   // Droid Sans Fallback (older releases)
+    "/system/fonts/NotoSerifCJK-Regular.ttc", // Noto Serif CJK
+];
+
+/// Chinese font files probed on FreeBSD, OpenBSD and NetBSD, in priority order
+///
+/// Ports and packages install into `/usr/local` (pkgsrc into `/usr/pkg`).
+pub const BSD_FONT_CANDIDATES: &[&str] = &[
+    "/usr/local/share/fonts/noto/NotoSansCJK-Regular.ttc",
+    "/usr/local/share/fonts/noto/NotoSansSC-Regular.otf",
+    "/usr/local/share/fonts/noto/NotoSansTC-Regular.otf",
+    "/usr/local/share/fonts/wqy/wqy-microhei.ttc",
+    "/usr/local/share/fonts/wqy/wqy-zenhei.ttc",
+    "/usr/local/share/fonts/truetype/arphic/uming.ttc",
+    "/usr/local/share/fonts/truetype/arphic/ukai.ttc",
+    "/usr/local/share/fonts/TrueType/uming.ttc",
+    "/usr/local/share/fonts/TrueType/ukai.ttc",
+    "/usr/local/share/fonts/droid-fonts-ttf/DroidSansFallbackFull.ttf",
+    "/usr/pkg/share/fonts/X11/TTF/NotoSansCJK-Regular.ttc",
+    "/usr/pkg/share/fonts/X11/TTF/wqy-microhei.ttc",
+    "/usr/share/fonts/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/wqy/wqy-microhei.ttc",
+    "/usr/local/share/fonts/noto/NotoSerifCJK-Regular.ttc",
+];
+
+/// The candidate list of the platform this crate was compiled for
+///
+/// Empty on platforms without known font locations. Fonts designed for the
+/// preferred [`ChineseVariant`] are tried first, otherwise this order is kept.
+#[cfg(target_os = "windows")]
+pub const PLATFORM_FONT_CANDIDATES: &[&str] = WINDOWS_FONT_CANDIDATES;
+#[cfg(target_os = "macos")]
+pub const PLATFORM_FONT_CANDIDATES: &[&str] = MACOS_FONT_CANDIDATES;
+#[cfg(target_os = "ios")]
+pub const PLATFORM_FONT_CANDIDATES: &[&str] = IOS_FONT_CANDIDATES;
+#[cfg(target_os = "linux")]
+pub const PLATFORM_FONT_CANDIDATES: &[&str] = LINUX_FONT_CANDIDATES;
+#[cfg(target_os = "android")]
+pub const PLATFORM_FONT_CANDIDATES: &[&str] = ANDROID_FONT_CANDIDATES;
+#[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+pub const PLATFORM_FONT_CANDIDATES: &[&str] = BSD_FONT_CANDIDATES;
+#[cfg(not(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd"
+)))]
+pub const PLATFORM_FONT_CANDIDATES: &[&str] = &[];
+
+#[cfg(target_os = "windows")]
+const PLATFORM_NAME: &str = "Windows";
+#[cfg(target_os = "macos")]
+const PLATFORM_NAME: &str = "macOS";
+#[cfg(target_os = "ios")]
+const PLATFORM_NAME: &str = "iOS";
+#[cfg(target_os = "linux")]
+const PLATFORM_NAME: &str = "Linux";
+#[cfg(target_os = "android")]
+const PLATFORM_NAME: &str = "Android";
+#[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+const PLATFORM_NAME: &str = "BSD";
+#[cfg(not(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd"
+)))]
+const PLATFORM_NAME: &str = "this platform";
+
+/// Candidates skipped while searching for a font
+#[derive(Debug, Default)]
+pub(crate) struct Rejected {
+    /// Every candidate that was tried, in order
+    attempts: Vec<FontAttempt>,
+    /// The first valid font that was only rejected for its glyph coverage
+    fallback: Option<FoundFont>,
+}
+
+/// Load the first acceptable font from `extra_paths`, the platform's known paths and per-user fonts
+///
+/// `mode` decides whether `extra_paths` come first, last, or alone.
+///
+/// Fonts without common Chinese glyphs are skipped. If no candidate covers
+/// Chinese, the first valid font is used anyway, unless a style or required
+/// characters were asked for. Setup replaces it with the `bundled` font if enabled.
+///
+/// # Returns
+/// * `Ok(FoundFont)` for the first font matching `query`
+/// * `Err(FontError::StyleNotFound)` if a style was requested and no font of that style was accepted
+/// * `Err(FontError::NotFound)` listing rejected candidates otherwise
+/// * `Err(FontError::UnsupportedPlatform)` if there are no known paths for this platform
+pub(crate) fn load_platform_font(
+    query: &FontQuery<'_>,
+    extra_paths: &[String],
+    mode: PathMode,
+) -> Result<FoundFont, FontError> {
+    continue_platform_search(query, extra_paths, mode, Rejected::default())
+}
+
+/// Try only `paths`, recording the candidates they reject in `rejected`
+///
+/// Lets prepended search paths run before the system font database; pass
+/// `rejected` on to [`continue_platform_search`] afterwards.
+pub(crate) fn try_search_paths(paths: &[String], query: &FontQuery<'_>, rejected: &mut Rejected) -> Option<FoundFont> {
+    let candidates: Vec<FontCandidate<'_>> = paths.iter().map(|path| FontCandidate::from_path(path)).collect();
+    read_first_font(&candidates, query, rejected)
+}
+
+/// Like [`load_platform_font`], after candidates already rejected by [`try_search_paths`]
+pub(crate) fn continue_platform_search(
+    query: &FontQuery<'_>,
+    extra_paths: &[String],
+    mode: PathMode,
+    mut rejected: Rejected,
+) -> Result<FoundFont, FontError> {
+    if PLATFORM_FONT_CANDIDATES.is_empty() && extra_paths.is_empty() && rejected.attempts.is_empty() {
+        return Err(FontError::UnsupportedPlatform);
+    }
+
+    let groups = match mode {
+        PathMode::Prepend => std::iter::once(extra_paths.to_vec()).chain(candidate_groups()).collect(),
+        PathMode::Append => candidate_groups().into_iter().chain(std::iter::once(extra_paths.to_vec())).collect(),
+        PathMode::Replace => vec![extra_paths.to_vec()],
+    };
+    for group in groups {
+        if let Some(found) = try_search_paths(&group, query, &mut rejected) {
+            return Ok(found);
+        }
+    }
+
+    not_found(query, rejected)
+}
+
+/// The paths [`load_platform_font`] tries for `query`, in order
+pub(crate) fn probe_order(query: &FontQuery<'_>) -> Vec<String> {
+    candidate_paths(&candidate_groups(), query)
+}
+
+/// [`probe_order`] with the index of the group each path belongs to
+pub(crate) fn tiered_probe_order(query: &FontQuery<'_>) -> Vec<(u8, String)> {
+    candidate_groups()
+        .into_iter()
+        .enumerate()
+        .flat_map(|(tier, group)| {
+            let tier = u8::try_from(tier).unwrap_or(u8::MAX);
+            candidate_paths(&[group], query).into_iter().map(move |path| (tier, path))
+        })
+        .collect()
+}
+
+/// The paths [`load_candidate_font`] tries among `paths` for `query`, in order
+pub(crate) fn candidate_order(paths: &[String], query: &FontQuery<'_>) -> Vec<String> {
+    candidate_paths(&[paths.to_vec()], query)
+}
+
+fn candidate_paths(groups: &[Vec<String>], query: &FontQuery<'_>) -> Vec<String> {
+    groups
+        .iter()
+        .flat_map(|group| {
+            let candidates: Vec<FontCandidate<'_>> = group.iter().map(|path| FontCandidate::from_path(path)).collect();
+            ordered(&candidates, query)
+                .into_iter()
+                .map(|candidate| candidate.path.to_owned())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// The groups of paths the platform loader searches one after another
+// Which groups are pushed depends on the platform
+#[allow(clippy::vec_init_then_push)]
+fn candidate_groups() -> Vec<Vec<String>> {
+    let mut groups = Vec::new();
+
+    // Fonts the user installed without admin rights take precedence over system defaults
+    #[cfg(target_os = "windows")]
+    groups.push(user_font_paths());
+
+    groups.push(PLATFORM_FONT_CANDIDATES.iter().map(|path| path.to_string()).collect());
+
+    // Host fonts visible from Flatpak and Snap sandboxes, and fonts in Nix profiles
+    #[cfg(target_os = "linux")]
+    groups.push(paths_to_strings(crate::user_fonts::scan_cjk_fonts(&crate::sandbox::packaged_font_dirs())));
+
+    // Fonts macOS 10.15+ downloads on demand, such as PingFang, outside the system font directories
+    #[cfg(target_os = "macos")]
+    groups.push(paths_to_strings(crate::user_fonts::scan_cjk_fonts(&macos_font_asset_dirs())));
+
+    // Then every CJK font in the system, library and user font directories, which
+    // keeps working when a macOS release moves or renames the fonts listed above
+    #[cfg(target_os = "macos")]
+    groups.push(macos_installed_cjk_fonts());
+
+    // Then fonts installed in the user's home directory, which macOS scanned above
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd"
+    ))]
+    groups.push(user_font_paths());
+
+    groups
+}
+
+/// The font files named by [`FontConfig::with_search_paths`](crate::FontConfig::with_search_paths)
+///
+/// Directories are replaced by the font files directly inside them, sorted.
+pub(crate) fn search_path_candidates(paths: &[PathBuf]) -> Vec<String> {
+    let mut candidates = Vec::new();
+    for path in paths {
+        let Ok(entries) = std::fs::read_dir(path) else {
+            candidates.extend(path.to_str().map(str::to_owned));
+            continue;
+        };
+        let mut fonts: Vec<String> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && crate::faces::is_font_file(path))
+            .filter_map(|path| path.to_str().map(str::to_owned))
+            .collect();
+        fonts.sort();
+        candidates.extend(fonts);
+    }
+    candidates
+}
+
+/// The font asset catalogs of macOS, newest first
+///
+/// Fonts downloaded on demand live in
+/// `/System/Library/AssetsV2/com_apple_MobileAsset_Font<N>/<hash>.asset/AssetData/`,
+/// where `N` changes between macOS releases.
+#[cfg(target_os = "macos")]
+fn macos_font_asset_dirs() -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir("/System/Library/AssetsV2") else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<(u32, PathBuf)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let version = name.to_str()?.strip_prefix("com_apple_MobileAsset_Font")?.parse().ok()?;
+            Some((version, entry.path()))
+        })
+        .collect();
+    dirs.sort_by_key(|(version, _)| std::cmp::Reverse(*version));
+    dirs.into_iter().map(|(_, dir)| dir).collect()
+}
+
+/// The CJK fonts in the macOS font directories, except the known candidates
+///
+/// Files are found by file or family name, see [`scan_system_fonts`](crate::scan_system_fonts),
+/// in `/System/Library/Fonts` (including `Supplemental`), `/Library/Fonts`
+/// and `~/Library/Fonts`. Files named like a known candidate come first, in
+/// the candidates' order, e.g. `PingFang.ttc` after it moved to another
+/// directory.
+#[cfg(target_os = "macos")]
+fn macos_installed_cjk_fonts() -> Vec<String> {
+    let file_name = |path: &str| Path::new(path).file_name().map(|name| name.to_ascii_lowercase());
+    let known: Vec<_> = MACOS_FONT_CANDIDATES.iter().map(|path| file_name(path)).collect();
+
+    let mut fonts: Vec<String> = crate::scan_system_fonts()
+        .into_iter()
+        .filter_map(|font| font.path.to_str().map(str::to_owned))
+        .filter(|path| !MACOS_FONT_CANDIDATES.contains(&path.as_str()))
+        .collect();
+    // Stable sort keeps the remaining fonts sorted by path
+    fonts.sort_by_key(|path| {
+        let name = file_name(path);
+        known.iter().position(|known| *known == name).unwrap_or(known.len())
+    });
+    fonts
+}
+
+/// Load the first acceptable font among `paths`, instead of the platform's known paths
+pub(crate) fn load_candidate_font(paths: &[String], query: &FontQuery<'_>) -> Result<FoundFont, FontError> {
+    let candidates: Vec<FontCandidate<'_>> = paths.iter().map(|path| FontCandidate::from_path(path)).collect();
+    let mut rejected = Rejected::default();
+    match read_first_font(&candidates, query, &mut rejected) {
+        Some(found) => Ok(found),
+        None => not_found(query, rejected),
+    }
+}
+
+/// The CJK fonts in the user's font directories
+#[cfg(any(
+    target_os = "linux",
+    target_os = "windows",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd"
+))]
+fn user_font_paths() -> Vec<String> {
+    paths_to_strings(crate::scan_user_fonts())
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd"
+))]
+fn paths_to_strings(paths: Vec<PathBuf>) -> Vec<String> {
+    paths.iter().filter_map(|path| path.to_str()).map(str::to_owned).collect()
+}
+
+/// The candidates matching the query's style, fonts for its variant first
+fn ordered<'c, 'a>(candidates: &'c [FontCandidate<'a>], query: &FontQuery<'_>) -> Vec<&'c FontCandidate<'a>> {
+    let mut candidates: Vec<&FontCandidate<'_>> = candidates
+        .iter()
+        .filter(|candidate| query.style.map_or(true, |style| candidate.style == style))
+        .collect();
+    // Stable sort keeps the platform priority within each variant
+    candidates.sort_by_key(|candidate| candidate.variant != query.variant);
+    candidates
+}
+
+/// Outcome of checking a single candidate file
+enum Checked {
+    /// The file could not be read or is not a usable font
+    Failed(RejectReason),
+    /// The font is valid but lacks Chinese or required glyphs
+    Uncovered(FontData, String),
+    Accepted(FontData),
+}
+
+impl Checked {
+    fn outcome(&self) -> AttemptOutcome {
+        match self {
+            Checked::Failed(reason) => AttemptOutcome::Rejected(reason.clone()),
+            Checked::Uncovered(_, reason) => AttemptOutcome::Rejected(RejectReason::InsufficientCoverage(reason.clone())),
+            Checked::Accepted(_) => AttemptOutcome::Accepted,
+        }
+    }
+}
+
+/// Read the font at `path` and check it against `query`, as the loader does
+fn check_file(path: &str, query: &FontQuery<'_>) -> Checked {
+    let font_data = match crate::cache::read(Path::new(path)) {
+        Ok(font_data) => crate::font_data_for_variant(font_data, query.variant),
+        Err(err) => return Checked::Failed(RejectReason::from_read(err)),
+    };
+
+    if let Err(reason) = crate::faces::validate_font(&font_data.font, font_data.index) {
+        return Checked::Failed(RejectReason::InvalidFont(reason));
+    }
+    // egui can't draw embedded bitmaps, so such a font is no better than none
+    if !crate::faces::has_outlines(&font_data.font, font_data.index) {
+        return Checked::Failed(RejectReason::BitmapOnly);
+    }
+    match crate::coverage::check_candidate(&font_data.font, font_data.index, query.required_chars) {
+        Ok(()) => Checked::Accepted(font_data),
+        Err(reason) => Checked::Uncovered(font_data, reason),
+    }
+}
+
+/// Read the first existing Chinese font in `candidates` matching `query`, trying fonts for its variant first
+///
+/// Files that exist but fail validation (e.g. truncated by a failed update) or
+/// lack Chinese or required glyphs are skipped and added to `rejected` as
+/// `"path: reason"` entries. The first valid font is kept as its fallback.
+fn read_first_font(candidates: &[FontCandidate<'_>], query: &FontQuery<'_>, rejected: &mut Rejected) -> Option<FoundFont> {
+    for candidate in ordered(candidates, query) {
+        let font_path = candidate.path;
+        // Timed only for `setup_chinese_fonts_with_stats`
+        let start = crate::stats::collecting().then(Instant::now);
+        let checked = check_file(font_path, query);
+        if let Some(start) = start {
+            crate::stats::record_attempt(font_path, start.elapsed(), checked.outcome());
+        }
+
+        match checked {
+            Checked::Failed(RejectReason::PermissionDenied) => {
+                log!(warn, "font exists at {} but is not readable from this sandbox or user", font_path);
+                rejected.attempts.push(FontAttempt {
+                    path: font_path.into(),
+                    reason: RejectReason::PermissionDenied,
+                });
+            }
+            Checked::Failed(RejectReason::Missing) => {
+                log!(debug, "{}: not found", font_path);
+                rejected.attempts.push(FontAttempt {
+                    path: font_path.into(),
+                    reason: RejectReason::Missing,
+                });
+            }
+            Checked::Failed(reason) => {
+                log!(warn, "{}: present but skipped ({})", font_path, reason);
+                rejected.attempts.push(FontAttempt {
+                    path: font_path.into(),
+                    reason,
+                });
+            }
+            Checked::Uncovered(font_data, reason) => {
+                log!(warn, "{}: present but skipped ({})", font_path, reason);
+                rejected.attempts.push(FontAttempt {
+                    path: font_path.into(),
+                    reason: RejectReason::InsufficientCoverage(reason),
+                });
+                if rejected.fallback.is_none() {
+                    rejected.fallback = Some(FoundFont::new(font_data, Some(font_path.into())));
+                }
+            }
+            Checked::Accepted(font_data) => {
+                #[cfg(feature = "logging")]
+                {
+                    let coverage = crate::coverage::chinese_coverage(&font_data.font, font_data.index);
+                    log!(debug, "{}: accepted, covers {:.0}% of common Chinese characters", font_path, coverage * 100.0);
+                }
+                log!(info, "using {} (face {}, {} bytes)", font_path, font_data.index, font_data.font.len());
+                let font_data = crate::cache::store(Path::new(font_path), font_data);
+                return Some(FoundFont::new(font_data, Some(font_path.into())));
+            }
+        }
+    }
+
+    None
+}
+
+/// Check every path in `paths` as the loader would, reporting why each one is rejected
+pub(crate) fn check_paths(paths: Vec<(u8, String)>, query: &FontQuery<'_>) -> Vec<FontPathInfo> {
+    paths
+        .into_iter()
+        .map(|(priority, path)| {
+            let file_name = path.rsplit(['/', '\\']).next().unwrap_or(&path);
+            let display_name = display_name(file_name);
+            let variant = variant_from_file_name(file_name);
+            let format = FontFormat::from_file_name(file_name);
+            let size = std::fs::metadata(&path).ok().map(|metadata| metadata.len());
+            let rejection = match check_file(&path, query) {
+                Checked::Accepted(_) => None,
+                Checked::Failed(reason) => Some(reason.to_string()),
+                Checked::Uncovered(_, reason) => Some(reason),
+            };
+            FontPathInfo {
+                display_name,
+                variant,
+                format,
+                priority,
+                exists: size.is_some(),
+                size,
+                valid: rejection.is_none(),
+                rejection,
+                path: PathBuf::from(path),
+            }
+        })
+        .collect()
+}
+
+/// Fall back to the first valid font when no candidate was accepted, or build the error
+fn not_found(query: &FontQuery<'_>, rejected: Rejected) -> Result<FoundFont, FontError> {
+    if let Some(style) = query.style {
+        return Err(FontError::StyleNotFound(style));
+    }
+
+    // A font without Chinese glyphs still beats none
+    if let Some(FoundFont { font_data, path: Some(path) }) = rejected.fallback {
+        if query.required_chars.is_empty() {
+            log!(warn, "no installed font covers Chinese, falling back to {}", path.display());
+            return Ok(FoundFont::new(crate::cache::store(&path, font_data), Some(path)));
+        }
+    }
+
+    // Only report a timeout if no existing candidate was rejected for another reason
+    #[cfg(feature = "timeout")]
+    if let Some(timeout) = crate::cache::read_timeout() {
+        let timed_out = |attempt: &FontAttempt| matches!(attempt.reason, RejectReason::TimedOut);
+        let missing = |attempt: &FontAttempt| matches!(attempt.reason, RejectReason::Missing);
+        if rejected.attempts.iter().any(timed_out) && rejected.attempts.iter().all(|attempt| timed_out(attempt) || missing(attempt)) {
+            return Err(FontError::Timeout(timeout));
+        }
+    }
+
+    let mut message = format!("No Chinese font found on {}", PLATFORM_NAME);
+    if rejected.attempts.iter().any(|attempt| matches!(attempt.reason, RejectReason::PermissionDenied)) {
+        message = format!("{} ({})", message, crate::sandbox::unreadable_hint());
+    }
+    Err(FontError::NotFound {
+        message,
+        attempts: rejected.attempts,
+    })
+}
+
+/// The name of a well-known candidate font file
+fn display_name(file_name: &str) -> Option<&'static str> {
+    let name = match file_name.to_ascii_lowercase().as_str() {
+        "msyh.ttc" => "Microsoft YaHei",
+        "msyhbd.ttc" => "Microsoft YaHei Bold",
+        "simsun.ttc" => "SimSun",
+        "simhei.ttf" => "SimHei",
+        "simkai.ttf" => "KaiTi",
+        "simfang.ttf" => "FangSong",
+        "msjh.ttc" => "Microsoft JhengHei",
+        "msjhbd.ttc" => "Microsoft JhengHei Bold",
+        "kaiu.ttf" => "DFKai-SB",
+        "mingliu.ttc" => "MingLiU",
+        "pingfang.ttc" => "PingFang SC",
+        "stheiti light.ttc" | "stheiti-light.ttc" => "STHeiti Light",
+        "stheiti medium.ttc" => "STHeiti Medium",
+        "hiragino sans gb.ttc" => "Hiragino Sans GB",
+        "arial unicode.ttf" => "Arial Unicode MS",
+        "apple ligothic medium.ttf" => "Apple LiGothic Medium",
+        "songti.ttc" => "Songti SC",
+        "droidsansfallbackfull.ttf" | "droidsansfallback.ttf" => "Droid Sans Fallback",
+        "uming.ttc" => "AR PL UMing",
+        "ukai.ttc" => "AR PL UKai",
+        "wqy-microhei.ttc" => "WenQuanYi Micro Hei",
+        "wqy-zenhei.ttc" => "WenQuanYi Zen Hei",
+        "notosanscjk-regular.ttc" => "Noto Sans CJK",
+        "notoserifcjk-regular.ttc" => "Noto Serif CJK",
+        "notosanscjksc-regular.otf" => "Noto Sans CJK SC",
+        "notosanscjktc-regular.otf" => "Noto Sans CJK TC",
+        "notoserifcjksc-regular.otf" => "Noto Serif CJK SC",
+        "sourcehansans.ttc" | "sourcehansans-regular.ttc" => "Source Han Sans",
+        "sourcehansanssc-regular.otf" | "sourcehansanscn-regular.otf" => "Source Han Sans SC",
+        "sourcehansanstc-regular.otf" | "sourcehansanstw-regular.otf" => "Source Han Sans TC",
+        "sourcehanserif.ttc" | "sourcehanserif-regular.ttc" => "Source Han Serif",
+        "sourcehanserifsc-regular.otf" | "sourcehanserifcn-regular.otf" => "Source Han Serif SC",
+        "sourcehanseriftc-regular.otf" | "sourcehanseriftw-regular.otf" => "Source Han Serif TC",
+        "notosanssc-regular.otf" => "Noto Sans SC",
+        "notosanstc-regular.otf" => "Noto Sans TC",
+        "liberationsans-regular.ttf" => "Liberation Sans",
+        "dejavusans.ttf" => "DejaVu Sans",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// The style of a well-known candidate font file
+///
+/// Every file in the platform candidate tables is listed here.
+fn known_style(file_name: &str) -> Option<ChineseStyle> {
+    use ChineseStyle::{FangSong, Hei, Kai, Song};
+
+    let style = match file_name.to_ascii_lowercase().as_str() {
+        "msyh.ttc" | "msyhbd.ttc" | "simhei.ttf" | "msjh.ttc" | "msjhbd.ttc" => Hei,
+        "simsun.ttc" | "mingliu.ttc" => Song,
+        "simkai.ttf" | "kaiu.ttf" => Kai,
+        "simfang.ttf" => FangSong,
+        "pingfang.ttc" | "pingfangui.ttc" => Hei,
+        "stheiti light.ttc" | "stheiti-light.ttc" | "stheiti medium.ttc" | "hiragino sans gb.ttc" => Hei,
+        "arial unicode.ttf" | "apple ligothic medium.ttf" => Hei,
+        "songti.ttc" => Song,
+        "droidsansfallbackfull.ttf" | "droidsansfallback.ttf" | "wqy-microhei.ttc" | "wqy-zenhei.ttc" => Hei,
+        "uming.ttc" => Song,
+        "ukai.ttc" => Kai,
+        "notosanscjk-regular.ttc" | "notosanscjksc-regular.otf" | "notosanscjktc-regular.otf" => Hei,
+        "notosanssc-regular.otf" | "notosanstc-regular.otf" => Hei,
+        "sourcehansans.ttc" | "sourcehansans-regular.ttc" => Hei,
+        "sourcehansanssc-regular.otf" | "sourcehansanscn-regular.otf" => Hei,
+        "sourcehansanstc-regular.otf" | "sourcehansanstw-regular.otf" => Hei,
+        "notoserifcjk-regular.ttc" | "notoserifcjksc-regular.otf" => Song,
+        "sourcehanserif.ttc" | "sourcehanserif-regular.ttc" => Song,
+        "sourcehanserifsc-regular.otf" | "sourcehanserifcn-regular.otf" => Song,
+        "sourcehanseriftc-regular.otf" | "sourcehanseriftw-regular.otf" => Song,
+        "liberationsans-regular.ttf" | "dejavusans.ttf" => Hei,
+        _ => return None,
+    };
+    Some(style)
+}
+
+/// Guess the Chinese variant a font file targets from its name
+fn variant_from_file_name(file_name: &str) -> ChineseVariant {
+    let name = file_name.to_ascii_lowercase();
+    let stem = name.rsplit_once('.').map_or(name.as_str(), |(stem, _)| stem);
+
+    let traditional = ["msjh", "mingliu", "kaiu", "jhenghei", "ligothic", "hant"].iter().any(|hint| stem.contains(hint))
+        || stem
+            .split(['-', '_', ' '])
+            .any(|part| part.ends_with("tc") || part.ends_with("hk") || part.ends_with("tw"));
+
+    if traditional {
+        Traditional
+    } else {
+        Simplified
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_candidate_has_a_known_style() {
+        let tables = [
+            WINDOWS_FONT_CANDIDATES,
+            MACOS_FONT_CANDIDATES,
+            IOS_FONT_CANDIDATES,
+            LINUX_FONT_CANDIDATES,
+            ANDROID_FONT_CANDIDATES,
+            BSD_FONT_CANDIDATES,
+        ];
+        for path in tables.iter().flat_map(|table| table.iter()) {
+            let file_name = path.rsplit(['/', '\\']).next().unwrap();
+            assert!(known_style(file_name).is_some(), "no style listed for {}", path);
+        }
+    }
+
+    #[test]
+    fn pingfang_is_sans_serif() {
+        for path in IOS_FONT_CANDIDATES.iter().chain(MACOS_FONT_CANDIDATES).filter(|path| path.contains("PingFang")) {
+            assert_eq!(FontCandidate::from_path(path).style, ChineseStyle::Hei);
+        }
+    }
+}
@@ -0,0 +1,229 @@
+//! Pluggable font discovery
+
+use std::fmt;
+use std::sync::Arc;
+
+use egui::FontData;
+
+use crate::cache::{self, Reader};
+use crate::platform::{self, FontQuery};
+use crate::{ChineseStyle, ChineseVariant, FontError, PathMode};
+
+/// A source of Chinese font data
+///
+/// Implement this to load fonts from places the crate doesn't know about,
+/// such as an embedded system's flash storage, a proprietary font server or
+/// a test fixture, and install it with [`FontConfig::with_provider`](crate::FontConfig::with_provider).
+///
+/// # Example
+///
+/// ```rust
+/// use egui::FontData;
+/// use egui_chinese_font::{setup_chinese_fonts_with_config, FontConfig, FontError, FontProvider};
+///
+/// struct BundledFont(&'static [u8]);
+///
+/// impl FontProvider for BundledFont {
+///     fn load(&self) -> Result<FontData, FontError> {
+///         Ok(FontData::from_static(self.0))
+///     }
+///
+///     fn available_paths(&self) -> Vec<String> {
+///         Vec::new()
+///     }
+/// }
+///
+/// # let font: &'static [u8] = egui::FontDefinitions::default().font_data["Ubuntu-Light"].font.to_vec().leak();
+/// let ctx = egui::Context::default();
+/// let config = FontConfig::new().with_provider(Box::new(BundledFont(font)));
+/// setup_chinese_fonts_with_config(&ctx, &config)?;
+/// # Ok::<(), FontError>(())
+/// ```
+pub trait FontProvider: Send + Sync {
+    /// Load the font data
+    fn load(&self) -> Result<FontData, FontError>;
+
+    /// Paths this provider looks at, for debugging font loading issues
+    fn available_paths(&self) -> Vec<String>;
+}
+
+impl fmt::Debug for dyn FontProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FontProvider").finish_non_exhaustive()
+    }
+}
+
+/// The built-in provider probing the current platform's well-known font paths
+///
+/// Supports Windows, macOS, Linux and Android; elsewhere loading fails with
+/// [`FontError::UnsupportedPlatform`].
+#[derive(Debug, Clone)]
+pub struct PlatformFontProvider {
+    variant: ChineseVariant,
+    style: Option<ChineseStyle>,
+    required_chars: String,
+    candidates: Option<Vec<String>>,
+    reader: Option<FileReader>,
+}
+
+/// Reader set with [`PlatformFontProvider::with_reader`]
+#[derive(Clone)]
+struct FileReader(Arc<Reader>);
+
+impl fmt::Debug for FileReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("FileReader")
+    }
+}
+
+impl PlatformFontProvider {
+    /// Probe for fonts, preferring ones designed for `variant`
+    pub fn new(variant: ChineseVariant) -> Self {
+        Self {
+            variant,
+            style: None,
+            required_chars: String::new(),
+            candidates: None,
+            reader: None,
+        }
+    }
+
+    /// Only accept fonts of the given style
+    pub fn with_style(mut self, style: ChineseStyle) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    /// Only accept fonts that have glyphs for every character in `chars`
+    pub fn with_required_chars(mut self, chars: impl Into<String>) -> Self {
+        self.required_chars = chars.into();
+        self
+    }
+
+    /// Probe `paths` instead of [`PLATFORM_FONT_CANDIDATES`](crate::PLATFORM_FONT_CANDIDATES)
+    ///
+    /// Use this to reorder or extend the search. Fonts designed for the
+    /// preferred variant are still tried first; per-user fonts are not scanned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use egui_chinese_font::{ChineseVariant, PlatformFontProvider, FontProvider, PLATFORM_FONT_CANDIDATES};
+    ///
+    /// // Try the last candidate first
+    /// let mut paths: Vec<String> = PLATFORM_FONT_CANDIDATES.iter().map(|path| path.to_string()).collect();
+    /// paths.rotate_right(1);
+    /// let provider = PlatformFontProvider::new(ChineseVariant::Simplified).with_candidates(paths.clone());
+    /// // The same paths, with Traditional Chinese fonts moved behind the Simplified ones
+    /// let mut available = provider.available_paths();
+    /// available.sort();
+    /// paths.sort();
+    /// assert_eq!(available, paths);
+    /// ```
+    ///
+    /// Files that exist but are empty or corrupted are skipped like missing ones:
+    ///
+    /// ```rust
+    /// use egui_chinese_font::{ChineseVariant, FontProvider, PlatformFontProvider};
+    ///
+    /// let dir = std::env::temp_dir();
+    /// let corrupt = dir.join("egui-chinese-font-corrupt-msyh.ttc");
+    /// let valid = dir.join("egui-chinese-font-valid-example.ttf");
+    /// std::fs::write(&corrupt, b"")?;
+    /// # std::fs::write(&valid, &*egui::FontDefinitions::default().font_data["Ubuntu-Light"].font)?;
+    ///
+    /// let provider = PlatformFontProvider::new(ChineseVariant::Simplified).with_candidates(vec![
+    ///     corrupt.display().to_string(),
+    ///     valid.display().to_string(),
+    /// ]);
+    /// let font_data = provider.load()?;
+    /// assert_eq!(font_data.font.len() as u64, std::fs::metadata(&valid)?.len());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_candidates(mut self, paths: Vec<String>) -> Self {
+        self.candidates = Some(paths);
+        self
+    }
+
+    /// Read candidate files with `reader` instead of from the filesystem
+    ///
+    /// `reader` gets each candidate path and returns the file's contents, or
+    /// an error such as [`std::io::ErrorKind::NotFound`] for missing files.
+    /// Together with [`PlatformFontProvider::with_candidates`], this tests the
+    /// selection logic against synthetic files. Files read this way are not
+    /// cached.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::collections::HashMap;
+    /// use std::io::ErrorKind;
+    /// use egui_chinese_font::{ChineseVariant, FontError, FontProvider, PlatformFontProvider, RejectReason};
+    ///
+    /// let fonts = egui::FontDefinitions::default().font_data;
+    /// let files = HashMap::from([
+    ///     ("/fonts/corrupt.ttc", vec![0; 64]),
+    ///     ("/fonts/latin.ttf", fonts["Ubuntu-Light"].font.to_vec()),
+    ///     ("/fonts/mono.ttf", fonts["Hack"].font.to_vec()),
+    /// ]);
+    /// let paths = ["/fonts/missing.ttc", "/fonts/corrupt.ttc", "/fonts/latin.ttf", "/fonts/mono.ttf"];
+    /// let provider = PlatformFontProvider::new(ChineseVariant::Simplified)
+    ///     .with_candidates(paths.iter().map(|path| path.to_string()).collect())
+    ///     .with_reader(move |path| files.get(path).cloned().ok_or_else(|| ErrorKind::NotFound.into()));
+    ///
+    /// // None of the valid fonts covers Chinese, so the first of them is used
+    /// assert_eq!(provider.load()?.font.len(), fonts["Ubuntu-Light"].font.len());
+    ///
+    /// match provider.with_required_chars("你好").load() {
+    ///     Err(FontError::NotFound { attempts, .. }) => {
+    ///         let reasons: Vec<_> = attempts.into_iter().map(|attempt| attempt.reason).collect();
+    ///         assert!(matches!(
+    ///             reasons[..],
+    ///             [
+    ///                 RejectReason::Missing,
+    ///                 RejectReason::InvalidFont(_),
+    ///                 RejectReason::InsufficientCoverage(_),
+    ///                 RejectReason::InsufficientCoverage(_),
+    ///             ]
+    ///         ));
+    ///     }
+    ///     other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    /// }
+    /// # Ok::<(), FontError>(())
+    /// ```
+    pub fn with_reader(mut self, reader: impl Fn(&str) -> std::io::Result<Vec<u8>> + Send + Sync + 'static) -> Self {
+        self.reader = Some(FileReader(Arc::new(reader)));
+        self
+    }
+}
+
+impl FontProvider for PlatformFontProvider {
+    fn load(&self) -> Result<FontData, FontError> {
+        let query = FontQuery {
+            variant: self.variant,
+            style: self.style,
+            required_chars: &self.required_chars,
+        };
+        let load = || match &self.candidates {
+            Some(paths) => platform::load_candidate_font(paths, &query),
+            None => platform::load_platform_font(&query, &[], PathMode::Prepend),
+        };
+        let found = match &self.reader {
+            Some(FileReader(reader)) => cache::with_reader(Arc::clone(reader), load),
+            None => load(),
+        };
+        found.map(|found| found.font_data)
+    }
+
+    fn available_paths(&self) -> Vec<String> {
+        let query = FontQuery {
+            variant: self.variant,
+            style: self.style,
+            required_chars: &self.required_chars,
+        };
+        match &self.candidates {
+            Some(paths) => platform::candidate_order(paths, &query),
+            None => platform::probe_order(&query),
+        }
+    }
+}
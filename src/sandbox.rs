@@ -0,0 +1,96 @@
+//! Detecting application sandboxes that hide the system fonts, and where
+//! sandboxed and Nix-packaged apps find fonts instead
+
+use std::path::{Path, PathBuf};
+
+/// An application sandbox that can make system font files unreadable
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Sandbox {
+    /// A Flatpak app, which only sees the host's fonts it was granted access to
+    Flatpak,
+    /// A strictly confined Snap package
+    Snap,
+    /// A sandboxed macOS app
+    MacAppSandbox,
+}
+
+impl Sandbox {
+    /// The sandbox the process is running in, if any
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use egui_chinese_font::Sandbox;
+    ///
+    /// if let Some(sandbox) = Sandbox::detect() {
+    ///     println!("running in {:?}: {}", sandbox, sandbox.remedy());
+    /// }
+    /// ```
+    pub fn detect() -> Option<Self> {
+        if Path::new("/.flatpak-info").exists() || std::env::var_os("FLATPAK_ID").is_some() {
+            Some(Sandbox::Flatpak)
+        } else if std::env::var_os("SNAP").is_some() {
+            Some(Sandbox::Snap)
+        } else if std::env::var_os("APP_SANDBOX_CONTAINER_ID").is_some() {
+            Some(Sandbox::MacAppSandbox)
+        } else {
+            None
+        }
+    }
+
+    /// How to make the system fonts readable from this sandbox
+    pub fn remedy(&self) -> &'static str {
+        match self {
+            Sandbox::Flatpak => {
+                "grant read access to the font directories, e.g. `--filesystem=/usr/share/fonts:ro` in the manifest \
+                 or `flatpak override --user --filesystem=/usr/share/fonts:ro <app-id>`"
+            }
+            Sandbox::Snap => "connect the snap's `desktop` interface, or bundle a Chinese font with the snap",
+            Sandbox::MacAppSandbox => "bundle a Chinese font with the app, or load it from the app's resources",
+        }
+    }
+}
+
+/// Explain why the candidates that exist but couldn't be read were skipped
+pub(crate) fn unreadable_hint() -> String {
+    match Sandbox::detect() {
+        Some(sandbox) => {
+            format!("some fonts exist but are not readable from this {:?} sandbox; {}", sandbox, sandbox.remedy())
+        }
+        None => "some fonts exist but are not readable by this process; check their permissions".to_owned(),
+    }
+}
+
+/// Font directories outside the usual `/usr/share/fonts` that packaged apps can see
+///
+/// * Flatpak mounts the host's fonts at `/run/host/fonts` and `/run/host/user-fonts`
+/// * Snap exposes the host's file system under `/var/lib/snapd/hostfs`
+/// * NixOS and Nix profiles install fonts into profile directories instead of `/usr`
+#[cfg(target_os = "linux")]
+pub(crate) fn packaged_font_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    match Sandbox::detect() {
+        Some(Sandbox::Flatpak) => {
+            dirs.extend(["/run/host/fonts", "/run/host/user-fonts", "/run/host/usr/share/fonts"].map(PathBuf::from));
+        }
+        Some(Sandbox::Snap) => {
+            dirs.extend(
+                ["/var/lib/snapd/hostfs/usr/share/fonts", "/var/lib/snapd/hostfs/usr/local/share/fonts"].map(PathBuf::from),
+            );
+        }
+        _ => {}
+    }
+
+    if Path::new("/nix/store").exists() {
+        dirs.extend(["/run/current-system/sw/share/fonts", "/run/current-system/sw/share/X11/fonts"].map(PathBuf::from));
+        if let Some(home) = std::env::var_os("HOME").filter(|home| !home.is_empty()) {
+            dirs.push(PathBuf::from(home).join(".nix-profile/share/fonts"));
+        }
+        if let Some(user) = std::env::var_os("USER").filter(|user| !user.is_empty()) {
+            dirs.push(Path::new("/etc/profiles/per-user").join(user).join("share/fonts"));
+        }
+    }
+
+    dirs
+}
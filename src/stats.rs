@@ -0,0 +1,183 @@
+//! Timing of font file reads for startup profiling
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use egui::Context;
+
+use crate::{FontConfig, FontError, RejectReason};
+
+thread_local! {
+    /// What font loading on this thread did, while stats are being collected
+    static COLLECTED: RefCell<Option<Collected>> = const { RefCell::new(None) };
+}
+
+#[derive(Default)]
+struct Collected {
+    /// Time spent reading each file from disk
+    reads: HashMap<PathBuf, Duration>,
+    /// The file read last and its size, claimed by the attempt that read it
+    last_read: Option<(PathBuf, u64)>,
+    attempts: Vec<AttemptStats>,
+}
+
+/// Size and read time of the font loaded by [`setup_chinese_fonts_with_stats`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LoadStats {
+    /// The font file, or `None` for custom providers and the bundled font
+    pub path: Option<PathBuf>,
+    /// Size of the loaded font data in bytes
+    pub bytes: usize,
+    /// Time spent reading the font file from disk
+    ///
+    /// Zero if the file was already in the font cache, or wasn't read from disk.
+    pub read_duration: Duration,
+    /// Every candidate file checked, in order, ending with the loaded one
+    ///
+    /// Empty if no candidate files were probed, e.g. for custom providers or
+    /// [`FONT_PATH_ENV`](crate::FONT_PATH_ENV). Not serialized, as
+    /// [`RejectReason`] can hold an I/O error.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub attempts: Vec<AttemptStats>,
+}
+
+/// A candidate font file checked while loading, listed in [`LoadStats::attempts`]
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::{setup_chinese_fonts_with_stats, AttemptOutcome, FontConfig, RejectReason};
+///
+/// # let dir = std::env::temp_dir();
+/// # let latin_font = dir.join("egui-chinese-font-attempts-example.ttf");
+/// # std::fs::write(&latin_font, &*egui::FontDefinitions::default().font_data["Ubuntu-Light"].font)?;
+/// let missing = dir.join("egui-chinese-font-attempts-missing.ttf");
+/// let config = FontConfig::new().with_search_paths([&missing, &latin_font]);
+///
+/// let ctx = egui::Context::default();
+/// let stats = setup_chinese_fonts_with_stats(&ctx, &config)?;
+///
+/// assert_eq!(stats.attempts[0].path, missing);
+/// assert_eq!(stats.attempts[0].outcome, AttemptOutcome::Rejected(RejectReason::Missing));
+/// assert_eq!(stats.attempts[0].bytes_read, None);
+///
+/// // Read, but skipped for lacking Chinese glyphs
+/// assert_eq!(stats.attempts[1].path, latin_font);
+/// assert_eq!(stats.attempts[1].bytes_read, Some(std::fs::metadata(&latin_font)?.len()));
+/// assert!(matches!(stats.attempts[1].outcome, AttemptOutcome::Rejected(RejectReason::InsufficientCoverage(_))));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttemptStats {
+    /// The candidate file
+    pub path: PathBuf,
+    /// Time spent reading and checking the file
+    pub duration: Duration,
+    /// Bytes read or mapped from disk, or `None` if the file wasn't read,
+    /// e.g. because it is missing or was already in the font cache
+    pub bytes_read: Option<u64>,
+    /// Whether the file was used
+    pub outcome: AttemptOutcome,
+}
+
+/// What happened to a candidate font file, see [`AttemptStats`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AttemptOutcome {
+    /// The file was loaded
+    Accepted,
+    /// The file was skipped
+    Rejected(RejectReason),
+}
+
+/// Setup Chinese fonts like [`setup_chinese_fonts_with_config`](crate::setup_chinese_fonts_with_config),
+/// reporting how big the font is and how long reading it took
+///
+/// Use the numbers to decide whether to load the font asynchronously or to
+/// ship a smaller subset font. Only reading the chosen file is timed, not
+/// checking the other candidates or handing the font to egui; each
+/// candidate checked is listed in [`LoadStats::attempts`] with its own time.
+/// Nothing is measured outside this function.
+///
+/// # Returns
+/// * `Ok(LoadStats)` if fonts were successfully loaded
+/// * `Err(FontError)` if font loading failed
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::{setup_chinese_fonts_with_stats, FontConfig, FONT_PATH_ENV};
+///
+/// # let path = std::env::temp_dir().join("egui-chinese-font-stats-example.ttf");
+/// # std::fs::write(&path, &*egui::FontDefinitions::default().font_data["Ubuntu-Light"].font)?;
+/// # std::env::set_var(FONT_PATH_ENV, &path);
+/// let ctx = egui::Context::default();
+/// let stats = setup_chinese_fonts_with_stats(&ctx, &FontConfig::default())?;
+/// assert_eq!(stats.path.as_deref(), Some(path.as_path()));
+/// assert_eq!(stats.bytes as u64, std::fs::metadata(&path)?.len());
+/// println!("read {} bytes in {:?}", stats.bytes, stats.read_duration);
+/// for attempt in &stats.attempts {
+///     println!("{}: {:?} in {:?}", attempt.path.display(), attempt.outcome, attempt.duration);
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn setup_chinese_fonts_with_stats(ctx: &Context, config: &FontConfig) -> Result<LoadStats, FontError> {
+    COLLECTED.with(|collected| *collected.borrow_mut() = Some(Collected::default()));
+    let found = crate::find_chinese_font_with_config(config);
+    let Collected { reads, attempts, .. } = COLLECTED.with(|collected| collected.borrow_mut().take()).unwrap_or_default();
+
+    let result = found.map(|found| {
+        let stats = LoadStats {
+            bytes: found.font_data.font.len(),
+            read_duration: found
+                .path
+                .as_ref()
+                .and_then(|path| reads.get(path))
+                .copied()
+                .unwrap_or_default(),
+            path: found.path,
+            attempts,
+        };
+        (crate::register_chinese_font(ctx, found.font_data, config), stats)
+    });
+
+    config.notify_loaded(result.as_ref().map(|(loaded, _)| loaded));
+    result.map(|(_, stats)| stats)
+}
+
+/// Record that reading `bytes` from `path` took `duration`, if stats are being collected
+pub(crate) fn record_read(path: &Path, duration: Duration, bytes: usize) {
+    COLLECTED.with(|collected| {
+        if let Some(collected) = collected.borrow_mut().as_mut() {
+            *collected.reads.entry(path.to_path_buf()).or_default() += duration;
+            collected.last_read = Some((path.to_path_buf(), bytes as u64));
+        }
+    });
+}
+
+/// Whether stats are being collected, so attempts are worth timing
+pub(crate) fn collecting() -> bool {
+    COLLECTED.with(|collected| collected.borrow().is_some())
+}
+
+/// Record that checking the candidate `path` took `duration` and ended in `outcome`, if stats are being collected
+pub(crate) fn record_attempt(path: &str, duration: Duration, outcome: AttemptOutcome) {
+    COLLECTED.with(|collected| {
+        if let Some(collected) = collected.borrow_mut().as_mut() {
+            let path = PathBuf::from(path);
+            let bytes_read = match collected.last_read.take() {
+                Some((read, bytes)) if read == path => Some(bytes),
+                _ => None,
+            };
+            collected.attempts.push(AttemptStats {
+                path,
+                duration,
+                bytes_read,
+                outcome,
+            });
+        }
+    });
+}
@@ -0,0 +1,67 @@
+//! Chinese typeface styles
+
+/// A traditional Chinese typeface style
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChineseStyle {
+    /// Sans-serif (黑体), e.g. Microsoft YaHei, PingFang, Noto Sans CJK
+    Hei,
+    /// Serif (宋体 / 明體), e.g. SimSun, MingLiU, Noto Serif CJK
+    Song,
+    /// Brush script (楷体), e.g. KaiTi, DFKai-SB, AR PL UKai
+    Kai,
+    /// Imitation Song (仿宋), e.g. FangSong
+    FangSong,
+}
+
+impl ChineseStyle {
+    /// Guess the style of a font from its file name
+    ///
+    /// Only used for files that are not in the platform candidate tables,
+    /// which list each font's style. The name is split into words at `-`,
+    /// `_` and spaces, and only whole words naming a style count, so
+    /// e.g. PingFang is not taken for FangSong. Serif families such as Noto
+    /// Serif CJK are recognized by `serif` anywhere in a word. Names without
+    /// a recognizable style are assumed to be sans-serif.
+    pub(crate) fn from_file_name(file_name: &str) -> Self {
+        let name = file_name.to_ascii_lowercase();
+        let stem = name.rsplit_once('.').map_or(name.as_str(), |(stem, _)| stem);
+        let words: Vec<&str> = stem.split(['-', '_', ' ']).collect();
+        let any = |names: &[&str]| words.iter().any(|word| names.contains(word));
+
+        if any(&["simfang", "fangsong", "stfangso", "stfangsong", "fzfangsong"]) {
+            ChineseStyle::FangSong
+        } else if any(&["simkai", "kaiti", "stkaiti", "kai", "kaiu", "ukai"]) {
+            ChineseStyle::Kai
+        } else if any(&["simsun", "nsimsun", "songti", "stsong", "song", "mingliu", "pmingliu", "uming"])
+            || words.iter().any(|word| word.contains("serif"))
+        {
+            ChineseStyle::Song
+        } else {
+            ChineseStyle::Hei
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChineseStyle::{self, FangSong, Hei, Kai, Song};
+
+    #[test]
+    fn pingfang_is_sans_serif() {
+        assert_eq!(ChineseStyle::from_file_name("PingFang.ttc"), Hei);
+        assert_eq!(ChineseStyle::from_file_name("PingFangUI.ttc"), Hei);
+    }
+
+    #[test]
+    fn style_words_are_matched_whole() {
+        assert_eq!(ChineseStyle::from_file_name("SamsungOne.ttf"), Hei);
+        assert_eq!(ChineseStyle::from_file_name("simsun.ttc"), Song);
+        assert_eq!(ChineseStyle::from_file_name("NotoSerifCJKsc-Regular.otf"), Song);
+        assert_eq!(ChineseStyle::from_file_name("Songti.ttc"), Song);
+        assert_eq!(ChineseStyle::from_file_name("simfang.ttf"), FangSong);
+        assert_eq!(ChineseStyle::from_file_name("STFangsong.ttf"), FangSong);
+        assert_eq!(ChineseStyle::from_file_name("KaiTi.ttf"), Kai);
+        assert_eq!(ChineseStyle::from_file_name("wqy-microhei.ttc"), Hei);
+    }
+}
@@ -0,0 +1,132 @@
+//! Shrinking a font to the characters an application actually shows
+//!
+//! Pan-CJK fonts carry glyphs for tens of thousands of characters, most of
+//! which a kiosk or an embedded panel never displays. Subsetting keeps only
+//! the glyphs for the requested ranges, which saves memory on low-RAM
+//! targets. It is lossy: dropped characters fall through to the next font in
+//! the family, and layout tables such as `GSUB` are removed.
+
+use std::borrow::Cow;
+use std::ops::RangeInclusive;
+
+use ttf_parser::{Face, GlyphId};
+
+use crate::faces::{face_tables, validate_font, write_font};
+use crate::FontError;
+
+/// Reduce a font to the glyphs for the characters in `ranges`
+///
+/// Characters outside `ranges` are removed from the character map, so egui
+/// draws them with the next font in the family instead of as blanks. The
+/// glyph outlines of removed characters are dropped, and so are layout tables
+/// such as `GSUB` and `GPOS` and any bitmap or color glyphs. For a
+/// collection, the first face is subset. A warning is logged for each range
+/// the font has no glyphs for. See
+/// [`FontConfig::with_subset_ranges`](crate::FontConfig::with_subset_ranges)
+/// to subset the font chosen during setup.
+///
+/// # Arguments
+/// * `bytes` - Contents of a `.ttf`, `.otf` or `.ttc` file
+/// * `ranges` - The characters to keep
+///
+/// # Returns
+/// * `Ok(Vec<u8>)` with a standalone font that covers every character of
+///   `ranges` the original covered
+/// * `Err(FontError::InvalidFont)` if `bytes` is not a usable font or
+///   subsetting failed
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::{font_covers, subset_font};
+///
+/// let bytes = egui::FontDefinitions::default().font_data["Ubuntu-Light"].font.to_vec();
+/// let subset = subset_font(&bytes, &['A'..='Z', '0'..='9'])?;
+/// assert!(subset.len() < bytes.len());
+///
+/// let subset = egui::FontData::from_owned(subset);
+/// assert!(font_covers(&subset, "HELLO 2024").missing.is_empty());
+/// assert_eq!(font_covers(&subset, "hello").missing, vec!['h', 'e', 'l', 'o']);
+/// # Ok::<(), egui_chinese_font::FontError>(())
+/// ```
+pub fn subset_font(bytes: &[u8], ranges: &[RangeInclusive<char>]) -> Result<Vec<u8>, FontError> {
+    subset_face(bytes, 0, ranges)
+}
+
+/// Reduce face `index` of `bytes` to the glyphs for the characters in `ranges`
+pub(crate) fn subset_face(bytes: &[u8], index: u32, ranges: &[RangeInclusive<char>]) -> Result<Vec<u8>, FontError> {
+    let invalid = |reason: String| FontError::InvalidFont { path: None, reason };
+    validate_font(bytes, index).map_err(invalid)?;
+    let face = Face::parse(bytes, index).map_err(|err| invalid(format!("not a valid font file ({})", err)))?;
+
+    for range in ranges {
+        if !range.clone().any(|c| face.glyph_index(c).is_some()) {
+            log!(warn, "the font has no glyphs for {:?}, which the subset was asked to keep", range);
+        }
+    }
+
+    let mut mapping: Vec<(char, GlyphId)> = ranges
+        .iter()
+        .flat_map(|range| range.clone())
+        .filter_map(|c| Some((c, face.glyph_index(c)?)))
+        .collect();
+    mapping.sort_unstable();
+    mapping.dedup_by_key(|(c, _)| *c);
+
+    // Glyph 0 is `.notdef`, drawn for characters a font lacks
+    let mut glyphs: Vec<u16> = std::iter::once(0).chain(mapping.iter().map(|(_, glyph)| glyph.0)).collect();
+    glyphs.sort_unstable();
+    glyphs.dedup();
+
+    let subset = subsetter::subset(bytes, index, subsetter::Profile::pdf(&glyphs))
+        .map_err(|err| invalid(format!("subsetting failed ({})", err)))?;
+
+    // The subsetter keeps the original character map; replace it so removed characters fall back
+    let (version, tables) = face_tables(&subset, 0).ok_or_else(|| invalid("subsetting failed".to_owned()))?;
+    let tables = tables
+        .into_iter()
+        .filter(|(tag, _)| tag != b"cmap")
+        .map(|(tag, table)| (tag, Cow::Borrowed(table)))
+        .chain(std::iter::once((*b"cmap", Cow::Owned(cmap(&mapping)))))
+        .collect();
+    let font = write_font(version, tables);
+
+    validate_font(&font, 0).map_err(invalid)?;
+    let subset_face = Face::parse(&font, 0).map_err(|err| invalid(format!("subsetting failed ({})", err)))?;
+    if let Some((c, _)) = mapping.iter().find(|(c, glyph)| subset_face.glyph_index(*c) != Some(*glyph)) {
+        return Err(invalid(format!("subset lost the glyph for {:?}", c)));
+    }
+    Ok(font)
+}
+
+/// A `cmap` table with a single format 12 subtable for `mapping`, sorted by character
+fn cmap(mapping: &[(char, GlyphId)]) -> Vec<u8> {
+    // Runs of consecutive characters mapped to consecutive glyphs
+    let mut groups: Vec<(u32, u32, u32)> = Vec::new();
+    for &(c, glyph) in mapping {
+        let (c, glyph) = (u32::from(c), u32::from(glyph.0));
+        match groups.last_mut() {
+            Some((start, end, start_glyph)) if c == *end + 1 && glyph == *start_glyph + (c - *start) => *end = c,
+            _ => groups.push((c, c, glyph)),
+        }
+    }
+
+    let mut table = Vec::with_capacity(28 + 12 * groups.len());
+    // Header with one encoding record: Windows (3), Unicode full repertoire (10)
+    for field in [0u16, 1, 3, 10] {
+        table.extend(field.to_be_bytes());
+    }
+    table.extend(12u32.to_be_bytes());
+
+    table.extend(12u16.to_be_bytes());
+    table.extend(0u16.to_be_bytes());
+    for field in [16 + 12 * groups.len() as u32, 0, groups.len() as u32] {
+        table.extend(field.to_be_bytes());
+    }
+    for (start, end, start_glyph) in groups {
+        for field in [start, end, start_glyph] {
+            table.extend(field.to_be_bytes());
+        }
+    }
+    table
+}
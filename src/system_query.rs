@@ -0,0 +1,131 @@
+//! System font discovery backed by [`fontdb`]
+//!
+//! Enabled with the `system-query` feature. Instead of probing hardcoded
+//! paths, the OS font directories (and fontconfig on Linux) are indexed and
+//! queried for a font family with Han script coverage.
+
+use egui::FontData;
+use fontdb::{Database, Family, Language, Query, Source, ID};
+
+use crate::platform::{FontQuery, FoundFont};
+use crate::ChineseStyle::{self, FangSong, Hei, Kai, Song};
+use crate::{ChineseVariant, FontError};
+
+/// Preferred Simplified Chinese families and their styles, in priority order
+const SIMPLIFIED_FAMILIES: &[(&str, ChineseStyle)] = &[
+    ("PingFang SC", Hei),
+    ("Microsoft YaHei", Hei),
+    ("Noto Sans CJK SC", Hei),
+    ("Noto Sans SC", Hei),
+    ("Source Han Sans SC", Hei),
+    ("Hiragino Sans GB", Hei),
+    ("WenQuanYi Micro Hei", Hei),
+    ("WenQuanYi Zen Hei", Hei),
+    ("Droid Sans Fallback", Hei),
+    ("SimHei", Hei),
+    ("SimSun", Song),
+    ("STHeiti", Hei),
+    ("AR PL UMing CN", Song),
+    ("Noto Serif CJK SC", Song),
+    ("Source Han Serif SC", Song),
+    ("Songti SC", Song),
+    ("KaiTi", Kai),
+    ("STKaiti", Kai),
+    ("AR PL UKai CN", Kai),
+    ("FangSong", FangSong),
+    ("STFangsong", FangSong),
+];
+
+/// Preferred Traditional Chinese families and their styles, in priority order
+const TRADITIONAL_FAMILIES: &[(&str, ChineseStyle)] = &[
+    ("PingFang TC", Hei),
+    ("Microsoft JhengHei", Hei),
+    ("Noto Sans CJK TC", Hei),
+    ("Noto Sans TC", Hei),
+    ("Source Han Sans TC", Hei),
+    ("PingFang HK", Hei),
+    ("Noto Sans CJK HK", Hei),
+    ("MingLiU", Song),
+    ("AR PL UMing TW", Song),
+    ("Noto Serif CJK TC", Song),
+    ("Source Han Serif TC", Song),
+    ("Songti TC", Song),
+    ("DFKai-SB", Kai),
+    ("BiauKai", Kai),
+    ("AR PL UKai TW", Kai),
+];
+
+/// Query the system font database for a Chinese font and load it
+pub(crate) fn load_chinese_font(query: &FontQuery<'_>) -> Result<FoundFont, FontError> {
+    let mut db = Database::new();
+    db.load_system_fonts();
+
+    let id = find_chinese_face(&db, query.variant, query.style)
+        .ok_or_else(|| FontError::not_found("No Chinese font found in system font database"))?;
+
+    let font_data = db
+        .with_face_data(id, |data, index| FontData {
+            index,
+            ..FontData::from_owned(data.to_vec())
+        })
+        .ok_or_else(|| FontError::not_found("Failed to read font from system font database"))?;
+
+    crate::faces::validate_font(&font_data.font, font_data.index)
+        .and_then(|()| crate::coverage::check_candidate(&font_data.font, font_data.index, query.required_chars))
+        .map_err(|reason| FontError::InvalidFont { path: None, reason })?;
+
+    let face = db.face(id);
+    if let Some(face) = face {
+        log!(info, "using {:?} from the system font database (face {})", face.post_script_name, face.index);
+    }
+    let path = face.and_then(|face| match &face.source {
+        Source::File(path) => Some(path.clone()),
+        Source::SharedFile(path, _) => Some(path.clone()),
+        Source::Binary(_) => None,
+    });
+
+    Ok(FoundFont::new(font_data, path))
+}
+
+/// Find the best-matching face with Han script coverage
+fn find_chinese_face(db: &Database, variant: ChineseVariant, style: Option<ChineseStyle>) -> Option<ID> {
+    // Prefer well-known CJK families for the requested variant first
+    let (preferred, other) = match variant {
+        ChineseVariant::Simplified => (SIMPLIFIED_FAMILIES, TRADITIONAL_FAMILIES),
+        ChineseVariant::Traditional => (TRADITIONAL_FAMILIES, SIMPLIFIED_FAMILIES),
+    };
+    let families: Vec<Family<'_>> = preferred
+        .iter()
+        .chain(other)
+        .filter(|(_, family_style)| style.map_or(true, |style| *family_style == style))
+        .map(|(name, _)| Family::Name(name))
+        .collect();
+    let query = Query {
+        families: &families,
+        ..Query::default()
+    };
+
+    if let Some(id) = db.query(&query) {
+        return Some(id);
+    }
+
+    // Otherwise take any face that carries a localized Chinese family name,
+    // unless a specific style was asked for
+    if style.is_some() {
+        return None;
+    }
+    db.faces()
+        .find(|face| face.families.iter().any(|(_, language)| is_chinese(*language)))
+        .map(|face| face.id)
+}
+
+fn is_chinese(language: Language) -> bool {
+    matches!(
+        language,
+        Language::Chinese_PeoplesRepublicOfChina
+            | Language::Chinese_Taiwan
+            | Language::Chinese_HongKongSAR
+            | Language::Chinese_MacaoSAR
+            | Language::Chinese_Singapore
+    )
+}
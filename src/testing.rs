@@ -0,0 +1,250 @@
+//! Test doubles for code that sets up fonts
+//!
+//! Enabled with the `testing` feature, e.g. as a dev-dependency feature, so
+//! tests of font setup code don't depend on the fonts installed on the
+//! machine running them. [`MockFontProvider`] replaces font discovery
+//! altogether; [`MockFileSystem`] keeps it but serves the files.
+//! [`chinese_font`] and [`font_collection`] build font files to serve.
+
+use std::collections::HashMap;
+use std::io;
+use std::sync::Arc;
+
+use egui::{FontData, FontDefinitions};
+
+use crate::{FontError, FontProvider};
+
+/// A [`FontProvider`] that never touches the filesystem
+///
+/// [`MockFontProvider::new`] serves the Latin font egui ships with, which is
+/// small and passes the crate's validation, so setup succeeds without any
+/// installed font; it has no Chinese glyphs, which hardly matters in tests.
+/// [`MockFontProvider::failing`] makes every load fail, to test error
+/// handling.
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::testing::MockFontProvider;
+/// use egui_chinese_font::{setup_chinese_fonts_with_config, FontConfig, FontError};
+///
+/// let ctx = egui::Context::default();
+/// let config = FontConfig::new().with_provider(Box::new(MockFontProvider::new()));
+/// setup_chinese_fonts_with_config(&ctx, &config)?;
+/// assert!(egui_chinese_font::list_loaded_fonts(&ctx).contains(&"chinese".to_string()));
+///
+/// let config = FontConfig::new().with_provider(Box::new(MockFontProvider::failing(FontError::UnsupportedPlatform)));
+/// assert_eq!(setup_chinese_fonts_with_config(&ctx, &config).unwrap_err(), FontError::UnsupportedPlatform);
+/// # Ok::<(), FontError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct MockFontProvider {
+    result: Result<FontData, FontError>,
+}
+
+impl MockFontProvider {
+    /// A provider that loads egui's built-in Latin font
+    pub fn new() -> Self {
+        let font_data = FontDefinitions::default().font_data["Ubuntu-Light"].as_ref().clone();
+        Self { result: Ok(font_data) }
+    }
+
+    /// A provider whose loads all fail with `err`
+    pub fn failing(err: FontError) -> Self {
+        Self { result: Err(err) }
+    }
+}
+
+impl Default for MockFontProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FontProvider for MockFontProvider {
+    fn load(&self) -> Result<FontData, FontError> {
+        self.result.clone()
+    }
+
+    fn available_paths(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Font files held in memory, for testing which file the platform search picks
+///
+/// Hand [`MockFileSystem::reader`] to
+/// [`PlatformFontProvider::with_reader`](crate::PlatformFontProvider::with_reader):
+/// the provider then probes its candidate paths (the platform's built-in list,
+/// or [`with_candidates`](crate::PlatformFontProvider::with_candidates)) in its
+/// usual order, validating and rejecting files just as on a real system, but
+/// only sees the files added here. Every other path is missing. Directories
+/// scanned for fonts, such as the user's font directory, are still listed
+/// from disk.
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::testing::MockFileSystem;
+/// use egui_chinese_font::{ChineseVariant, FontError, FontProvider, PlatformFontProvider, RejectReason};
+///
+/// let files = MockFileSystem::new()
+///     .with_file("/fonts/corrupt.ttc", vec![0; 64])
+///     .with_latin_font("/fonts/latin.ttf");
+/// let provider = PlatformFontProvider::new(ChineseVariant::Simplified)
+///     .with_candidates(vec!["/fonts/missing.ttc".into(), "/fonts/corrupt.ttc".into(), "/fonts/latin.ttf".into()])
+///     .with_required_chars("你好")
+///     .with_reader(files.reader());
+///
+/// let Err(FontError::NotFound { attempts, .. }) = provider.load() else {
+///     panic!("no file covers Chinese");
+/// };
+/// let reasons: Vec<_> = attempts.into_iter().map(|attempt| attempt.reason).collect();
+/// assert!(matches!(
+///     reasons[..],
+///     [RejectReason::Missing, RejectReason::InvalidFont(_), RejectReason::InsufficientCoverage(_)]
+/// ));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MockFileSystem {
+    files: HashMap<String, Arc<[u8]>>,
+}
+
+impl MockFileSystem {
+    /// An empty file system, where every font is missing
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a file at `path` with the given contents
+    pub fn with_file(mut self, path: impl Into<String>, contents: impl Into<Arc<[u8]>>) -> Self {
+        self.files.insert(path.into(), contents.into());
+        self
+    }
+
+    /// Add egui's built-in Latin font at `path`, a valid font without Chinese glyphs
+    pub fn with_latin_font(self, path: impl Into<String>) -> Self {
+        let font = FontDefinitions::default().font_data["Ubuntu-Light"].font.to_vec();
+        self.with_file(path, font)
+    }
+
+    /// Read the file at `path`, failing with [`io::ErrorKind::NotFound`] if it wasn't added
+    pub fn read(&self, path: &str) -> io::Result<Vec<u8>> {
+        self.files
+            .get(path)
+            .map(|contents| contents.to_vec())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("{} not in the mock file system", path)))
+    }
+
+    /// A reader for [`PlatformFontProvider::with_reader`](crate::PlatformFontProvider::with_reader)
+    pub fn reader(&self) -> impl Fn(&str) -> io::Result<Vec<u8>> + Send + Sync + 'static {
+        let files = self.clone();
+        move |path| files.read(path)
+    }
+}
+
+/// Combine single fonts into a font collection (`.ttc`), one face per font in order
+///
+/// Useful for testing face selection without a real collection installed.
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::list_ttc_faces;
+/// use egui_chinese_font::testing::font_collection;
+///
+/// let defaults = egui::FontDefinitions::default();
+/// let ttc = font_collection(&[&defaults.font_data["Ubuntu-Light"].font, &defaults.font_data["Hack"].font]);
+///
+/// let path = std::env::temp_dir().join("egui-chinese-font-collection-example.ttc");
+/// std::fs::write(&path, ttc)?;
+/// assert_eq!(list_ttc_faces(&path)?.len(), 2);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+///
+/// # Panics
+/// If one of `fonts` is not a single TrueType or OpenType font.
+pub fn font_collection(fonts: &[&[u8]]) -> Vec<u8> {
+    let read = |data: &[u8], at: usize, len: usize| data[at..at + len].iter().fold(0, |acc, byte| acc << 8 | *byte as usize);
+    let directory_len = |font: &[u8]| 12 + 16 * read(font, 4, 2);
+
+    let mut out = b"ttcf\0\x01\0\0".to_vec();
+    out.extend((fonts.len() as u32).to_be_bytes());
+    let mut directory_at = out.len() + 4 * fonts.len();
+    for font in fonts {
+        out.extend((directory_at as u32).to_be_bytes());
+        directory_at += directory_len(font);
+    }
+
+    // Table records point past all table directories, into the tables copied after them
+    let mut tables = Vec::new();
+    for font in fonts {
+        out.extend(&font[..12]);
+        for record in font[12..directory_len(font)].chunks(16) {
+            let (offset, len) = (read(record, 8, 4), read(record, 12, 4));
+            out.extend(&record[..8]);
+            out.extend(((directory_at + tables.len()) as u32).to_be_bytes());
+            out.extend(&record[12..]);
+            tables.extend(&font[offset..offset + len]);
+            tables.resize((tables.len() + 3) & !3, 0);
+        }
+    }
+    out.extend(tables);
+    out
+}
+
+/// A small font that passes the crate's Chinese coverage checks
+///
+/// The Latin font egui ships with, given a `cmap` that maps CJK punctuation,
+/// the CJK Unified Ideographs and full-width forms to a single glyph. Unlike
+/// [`MockFontProvider::new`], it is picked over installed fonts without
+/// Chinese glyphs and is never replaced by a last-resort font.
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::testing::chinese_font;
+/// use egui_chinese_font::{find_chinese_font_with_config, FontConfig, PathMode};
+///
+/// let path = std::env::temp_dir().join("egui-chinese-font-testing-example.ttf");
+/// std::fs::write(&path, chinese_font())?;
+///
+/// let config = FontConfig::new().with_search_paths([&path]).with_path_mode(PathMode::Replace);
+/// // Found even with the `bundled` font to fall back on
+/// let found = find_chinese_font_with_config(&config)?;
+/// assert_eq!(found.path(), Some(path.as_path()));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn chinese_font() -> Vec<u8> {
+    let mut font = FontDefinitions::default().font_data["Ubuntu-Light"].font.to_vec();
+    let groups: [(u32, u32); 3] = [(0x3000, 0x303F), (0x4E00, 0x9FFF), (0xFF00, 0xFFEF)];
+
+    // One Windows Unicode full-repertoire subtable in format 13 (many-to-one)
+    let mut cmap = vec![0, 0, 0, 1, 0, 3, 0, 10, 0, 0, 0, 12, 0, 13, 0, 0];
+    for value in [16 + 12 * groups.len() as u32, 0, groups.len() as u32] {
+        cmap.extend(value.to_be_bytes());
+    }
+    for (start, end) in groups {
+        for value in [start, end, 3] {
+            cmap.extend(value.to_be_bytes());
+        }
+    }
+
+    // Point the `cmap` record at the new table, appended to the end of the font
+    let tables = u16::from_be_bytes([font[4], font[5]]) as usize;
+    let record = (0..tables).map(|i| 12 + 16 * i).find(|&record| &font[record..record + 4] == b"cmap").unwrap();
+    let offset = font.len() as u32;
+    font[record + 8..record + 12].copy_from_slice(&offset.to_be_bytes());
+    font[record + 12..record + 16].copy_from_slice(&(cmap.len() as u32).to_be_bytes());
+    font.extend(cmap);
+    font
+}
+
+/// Write [`chinese_font`] to a temporary file named after `name`, returning it and a config that only searches it
+#[cfg(test)]
+pub(crate) fn chinese_font_config(name: &str) -> (std::path::PathBuf, crate::FontConfig) {
+    let path = std::env::temp_dir().join(format!("egui-chinese-font-{}-{}.ttf", name, std::process::id()));
+    std::fs::write(&path, chinese_font()).unwrap();
+    let config = crate::FontConfig::new().with_search_paths([&path]).with_path_mode(crate::PathMode::Replace);
+    (path, config)
+}
@@ -0,0 +1,109 @@
+//! Recommended size and baseline corrections for common CJK fonts
+
+use egui::{FontData, FontTweak};
+
+/// Recommended `(family prefix, scale, y_offset_factor)` per font family, most specific first
+///
+/// CJK fonts are measured against their full vertical metrics, so fonts with
+/// tall ascenders and descenders (Noto Sans CJK) or tight ones (SimSun) come
+/// out visibly smaller or shifted next to egui's Latin font.
+const RECOMMENDED_TWEAKS: &[(&str, f32, f32)] = &[
+    ("Microsoft YaHei", 1.0, -0.05),
+    ("Microsoft JhengHei", 1.0, -0.05),
+    ("SimSun", 1.1, 0.0),
+    ("NSimSun", 1.1, 0.0),
+    ("MingLiU", 1.1, 0.0),
+    ("PMingLiU", 1.1, 0.0),
+    ("SimHei", 1.05, 0.0),
+    ("PingFang", 0.95, -0.05),
+    ("Hiragino Sans GB", 0.95, -0.05),
+    ("Noto Sans CJK", 1.12, -0.04),
+    ("Noto Serif CJK", 1.12, -0.04),
+    ("Noto Sans SC", 1.12, -0.04),
+    ("Noto Sans TC", 1.12, -0.04),
+    ("Noto Sans HK", 1.12, -0.04),
+    ("Source Han Sans", 1.12, -0.04),
+    ("Source Han Serif", 1.12, -0.04),
+    ("WenQuanYi", 0.95, -0.05),
+];
+
+/// The recommended [`FontTweak`] for a known CJK font, if any
+///
+/// The font is identified by its family name, e.g. `Noto Sans CJK SC`. Fonts
+/// loaded from the system get this tweak automatically unless
+/// [`FontConfig::with_tweak`](crate::FontConfig::with_tweak) or one of the
+/// individual tweak setters is used.
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::recommended_tweak;
+///
+/// // egui's built-in Latin font needs no correction
+/// let ubuntu = &egui::FontDefinitions::default().font_data["Ubuntu-Light"];
+/// assert!(recommended_tweak(ubuntu).is_none());
+/// ```
+pub fn recommended_tweak(font_data: &FontData) -> Option<FontTweak> {
+    let face = ttf_parser::Face::parse(&font_data.font, font_data.index).ok()?;
+    let family = crate::faces::english_family(&face)?;
+
+    let (_, scale, y_offset_factor) = RECOMMENDED_TWEAKS.iter().find(|(prefix, _, _)| family.starts_with(prefix))?;
+    log!(debug, "using the recommended tweak for {}", family);
+    Some(FontTweak {
+        scale: *scale,
+        y_offset_factor: *y_offset_factor,
+        ..FontTweak::default()
+    })
+}
+
+/// Serde support for an optional [`FontTweak`] whose fields may each be left out
+///
+/// egui's own implementation requires every field, so a settings file
+/// setting only `scale` would fail to load.
+#[cfg(feature = "serde")]
+pub(crate) mod optional_tweak {
+    use egui::FontTweak;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(default)]
+    struct Tweak {
+        scale: f32,
+        y_offset_factor: f32,
+        y_offset: f32,
+    }
+
+    impl Default for Tweak {
+        fn default() -> Self {
+            FontTweak::default().into()
+        }
+    }
+
+    impl From<FontTweak> for Tweak {
+        fn from(tweak: FontTweak) -> Self {
+            Tweak {
+                scale: tweak.scale,
+                y_offset_factor: tweak.y_offset_factor,
+                y_offset: tweak.y_offset,
+            }
+        }
+    }
+
+    impl From<Tweak> for FontTweak {
+        fn from(tweak: Tweak) -> Self {
+            FontTweak {
+                scale: tweak.scale,
+                y_offset_factor: tweak.y_offset_factor,
+                y_offset: tweak.y_offset,
+            }
+        }
+    }
+
+    pub(crate) fn serialize<S: Serializer>(tweak: &Option<FontTweak>, serializer: S) -> Result<S::Ok, S::Error> {
+        tweak.map(Tweak::from).serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<FontTweak>, D::Error> {
+        Ok(Option::<Tweak>::deserialize(deserializer)?.map(FontTweak::from))
+    }
+}
@@ -0,0 +1,93 @@
+//! Discovery of fonts installed per user rather than system-wide
+
+use std::path::{Path, PathBuf};
+
+/// Scan the user's font directories for CJK fonts
+///
+/// Recursively searches the per-user font directories for `.ttf`, `.ttc`,
+/// `.otf` and `.otc` files whose names contain a known CJK font identifier, such as
+/// `NotoSansCJK`, `wqy-microhei` or `SourceHanSans`:
+///
+/// * Linux and the BSDs: `$XDG_DATA_HOME/fonts` (defaulting to `~/.local/share/fonts`) and `~/.fonts`
+/// * macOS: `~/Library/Fonts`, where Font Book and Homebrew casks install fonts
+/// * Windows: `%LOCALAPPDATA%\Microsoft\Windows\Fonts`, where Windows 10 1809+
+///   installs fonts for users without administrator rights
+///
+/// The returned paths are sorted so results are stable between runs.
+pub fn scan_user_fonts() -> Vec<PathBuf> {
+    scan_cjk_fonts(&user_font_dirs())
+}
+
+/// The CJK font files under `dirs`, sorted
+pub(crate) fn scan_cjk_fonts(dirs: &[PathBuf]) -> Vec<PathBuf> {
+    let mut fonts = Vec::new();
+    for dir in dirs {
+        collect_cjk_fonts(dir, &mut fonts);
+    }
+
+    fonts.sort();
+    fonts.dedup();
+    fonts
+}
+
+/// The per-user font directories following the XDG convention
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub(crate) fn user_font_dirs() -> Vec<PathBuf> {
+    let home = std::env::var_os("HOME").filter(|home| !home.is_empty()).map(PathBuf::from);
+
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home.as_ref().map(|home| home.join(".local/share")));
+
+    data_home
+        .map(|dir| dir.join("fonts"))
+        .into_iter()
+        .chain(home.map(|home| home.join(".fonts")))
+        .collect()
+}
+
+/// The per-user font directory of macOS
+#[cfg(target_os = "macos")]
+pub(crate) fn user_font_dirs() -> Vec<PathBuf> {
+    std::env::var_os("HOME")
+        .filter(|home| !home.is_empty())
+        .map(|home| PathBuf::from(home).join("Library/Fonts"))
+        .into_iter()
+        .collect()
+}
+
+/// The per-user font directory, registered under `HKCU` rather than `HKLM`
+#[cfg(target_os = "windows")]
+pub(crate) fn user_font_dirs() -> Vec<PathBuf> {
+    std::env::var_os("LOCALAPPDATA")
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| PathBuf::from(dir).join(r"Microsoft\Windows\Fonts"))
+        .into_iter()
+        .collect()
+}
+
+fn collect_cjk_fonts(dir: &Path, fonts: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_cjk_fonts(&path, fonts);
+        } else if is_cjk_font_file(&path) {
+            fonts.push(path);
+        }
+    }
+}
+
+fn is_cjk_font_file(path: &Path) -> bool {
+    if !crate::faces::is_font_file(path) {
+        return false;
+    }
+
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(crate::faces::is_cjk_name)
+}
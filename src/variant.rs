@@ -0,0 +1,128 @@
+//! Simplified / Traditional Chinese variant selection
+
+/// A written variant of Chinese
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChineseVariant {
+    /// Simplified Chinese (mainland China, Singapore)
+    Simplified,
+    /// Traditional Chinese (Taiwan, Hong Kong, Macau)
+    Traditional,
+}
+
+impl ChineseVariant {
+    /// Parse a locale identifier into a Chinese variant
+    ///
+    /// Accepts BCP 47 tags and POSIX locale names. A script subtag (`Hans` /
+    /// `Hant`) takes precedence over the region; Chinese locales with neither
+    /// default to Simplified. Returns `None` for non-Chinese locales.
+    ///
+    /// ```
+    /// use egui_chinese_font::ChineseVariant;
+    ///
+    /// assert_eq!(ChineseVariant::from_locale("zh-CN"), Some(ChineseVariant::Simplified));
+    /// assert_eq!(ChineseVariant::from_locale("zh_SG"), Some(ChineseVariant::Simplified));
+    /// assert_eq!(ChineseVariant::from_locale("zh-TW"), Some(ChineseVariant::Traditional));
+    /// assert_eq!(ChineseVariant::from_locale("zh_HK.UTF-8"), Some(ChineseVariant::Traditional));
+    /// assert_eq!(ChineseVariant::from_locale("zh-MO"), Some(ChineseVariant::Traditional));
+    /// assert_eq!(ChineseVariant::from_locale("zh_Hant_TW"), Some(ChineseVariant::Traditional));
+    /// assert_eq!(ChineseVariant::from_locale("zh-Hans"), Some(ChineseVariant::Simplified));
+    /// assert_eq!(ChineseVariant::from_locale("zh-Hans-HK"), Some(ChineseVariant::Simplified));
+    /// assert_eq!(ChineseVariant::from_locale("cmn-Hans-CN"), Some(ChineseVariant::Simplified));
+    /// assert_eq!(ChineseVariant::from_locale("zh"), Some(ChineseVariant::Simplified));
+    /// assert_eq!(ChineseVariant::from_locale("en-US"), None);
+    /// assert_eq!(ChineseVariant::from_locale("C"), None);
+    /// ```
+    pub fn from_locale(locale: &str) -> Option<Self> {
+        // Drop POSIX encoding and modifier suffixes, e.g. `zh_TW.UTF-8@stroke`
+        let locale = locale.split(['.', '@']).next().unwrap_or_default();
+        let mut subtags = locale.split(['-', '_']).map(str::to_ascii_lowercase);
+
+        let language = subtags.next()?;
+        if !matches!(language.as_str(), "zh" | "cmn" | "yue" | "wuu" | "nan" | "hak") {
+            return None;
+        }
+
+        let mut region_variant = None;
+        for subtag in subtags {
+            match subtag.as_str() {
+                "hans" => return Some(Self::Simplified),
+                "hant" => return Some(Self::Traditional),
+                "tw" | "hk" | "mo" => region_variant = region_variant.or(Some(Self::Traditional)),
+                "cn" | "sg" | "my" => region_variant = region_variant.or(Some(Self::Simplified)),
+                _ => {}
+            }
+        }
+
+        Some(region_variant.unwrap_or(Self::Simplified))
+    }
+}
+
+/// Which Chinese variant to prefer when choosing a system font
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VariantPreference {
+    /// Detect the variant from the system locale, falling back to Simplified
+    #[default]
+    Auto,
+    /// Prefer Simplified Chinese fonts
+    Simplified,
+    /// Prefer Traditional Chinese fonts
+    Traditional,
+}
+
+impl VariantPreference {
+    /// Resolve the preference to a concrete variant
+    pub fn resolve(self) -> ChineseVariant {
+        match self {
+            VariantPreference::Auto => detect_chinese_variant().unwrap_or(ChineseVariant::Simplified),
+            VariantPreference::Simplified => ChineseVariant::Simplified,
+            VariantPreference::Traditional => ChineseVariant::Traditional,
+        }
+    }
+}
+
+/// Detect the Chinese variant of the user's preferred locale
+///
+/// Inspects the system's preferred locales in order and returns the variant
+/// of the first Chinese one, or `None` if none of them is Chinese. On Linux
+/// and the BSDs these come from `$LANGUAGE`, `$LC_ALL`, `$LC_MESSAGES` and
+/// `$LANG`. On macOS a Chinese `$LC_ALL` or `$LANG`, e.g. set in the shell an
+/// app was launched from, wins over the languages chosen in System Settings.
+///
+/// [`VariantPreference::Auto`], the default of [`FontConfig`](crate::FontConfig),
+/// uses this; [`FontConfig::with_variant`](crate::FontConfig::with_variant)
+/// overrides it.
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::{detect_chinese_variant, ChineseVariant};
+///
+/// # #[cfg(any(target_os = "linux", target_os = "macos"))] {
+/// std::env::remove_var("LANGUAGE");
+/// std::env::set_var("LC_ALL", "zh_TW.UTF-8");
+/// assert_eq!(detect_chinese_variant(), Some(ChineseVariant::Traditional));
+///
+/// std::env::set_var("LC_ALL", "zh_CN.UTF-8");
+/// assert_eq!(detect_chinese_variant(), Some(ChineseVariant::Simplified));
+/// # }
+/// ```
+#[doc(alias = "detect_script_from_locale")]
+pub fn detect_chinese_variant() -> Option<ChineseVariant> {
+    // The system languages of macOS ignore the POSIX locale variables
+    #[cfg(target_os = "macos")]
+    if let Some(variant) = posix_locale().and_then(|locale| ChineseVariant::from_locale(&locale)) {
+        return Some(variant);
+    }
+
+    sys_locale::get_locales().find_map(|locale| ChineseVariant::from_locale(&locale))
+}
+
+/// The locale named by `$LC_ALL`, or else `$LANG`
+#[cfg(target_os = "macos")]
+fn posix_locale() -> Option<String> {
+    ["LC_ALL", "LANG"]
+        .into_iter()
+        .find_map(|name| std::env::var(name).ok().filter(|locale| !locale.is_empty()))
+}
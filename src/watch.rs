@@ -0,0 +1,178 @@
+//! Reloading a font file when it changes on disk
+//!
+//! Meant for iterating on a custom font: regenerate the file and the running
+//! application picks it up without a restart.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::Duration;
+
+use egui::{Context, FontData};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::{faces, FontError, VariantPreference};
+
+/// How long the file must stay unchanged before it is reloaded
+///
+/// Font tools often write a file in several steps; reloading after each
+/// would apply half-written fonts or rebuild egui's font atlas repeatedly.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Watches a font file registered by [`watch_chinese_font`]
+///
+/// Watching stops when this guard is dropped; the last loaded font stays
+/// registered.
+pub struct FontWatcher {
+    path: PathBuf,
+    // Dropping the watcher closes the event channel, which ends the reload thread
+    _watcher: RecommendedWatcher,
+}
+
+impl FontWatcher {
+    /// The watched font file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl fmt::Debug for FontWatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FontWatcher").field("path", &self.path).finish_non_exhaustive()
+    }
+}
+
+/// Setup the font file at `path` as the Chinese font and reload it whenever it changes
+///
+/// The font is registered like [`setup_chinese_font_from_path`](crate::setup_chinese_font_from_path),
+/// merging into the context's current fonts. When the file is written,
+/// replaced or recreated, it is read again once writes have settled,
+/// validated, and re-registered under the same name, and a repaint is
+/// requested. Other fonts registered meanwhile are kept. If the new file
+/// can't be read or is not a valid font, e.g. while a font tool is still
+/// writing it, the previous font stays in use.
+///
+/// Changed files are read directly rather than through the font cache, so
+/// every version is picked up.
+///
+/// # Arguments
+/// * `ctx` - The egui context to configure
+/// * `path` - Path to a `.ttf`, `.otf` or `.ttc` file
+///
+/// # Returns
+/// * `Ok(FontWatcher)` guard that keeps watching until it is dropped
+/// * `Err(FontError)` if the file can't be loaded or watched
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::watch_chinese_font;
+///
+/// # let path = std::env::temp_dir().join("egui-chinese-font-watch-example.ttf");
+/// # let fonts = egui::FontDefinitions::default().font_data;
+/// # std::fs::write(&path, &*fonts["Ubuntu-Light"].font)?;
+/// let ctx = egui::Context::default();
+/// let watcher = watch_chinese_font(&ctx, &path)?;
+///
+/// // Regenerating the font updates the running application
+/// std::fs::write(&path, &*fonts["Hack"].font)?;
+/// # let chinese_font_len = || {
+/// #     let _ = ctx.run(Default::default(), |_| {});
+/// #     ctx.fonts(|fonts| fonts.definitions().font_data["chinese"].font.len())
+/// # };
+/// # let start = std::time::Instant::now();
+/// # while chinese_font_len() != fonts["Hack"].font.len() && start.elapsed().as_secs() < 10 {
+/// #     std::thread::sleep(std::time::Duration::from_millis(50));
+/// # }
+/// # assert_eq!(chinese_font_len(), fonts["Hack"].font.len());
+///
+/// // Stop watching, keeping the current font
+/// drop(watcher);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn watch_chinese_font(ctx: &Context, path: impl AsRef<Path>) -> Result<FontWatcher, FontError> {
+    let path = path.as_ref().to_path_buf();
+    let font_data = read(&path)?;
+    register(ctx, Arc::clone(&font_data));
+
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |event| {
+        let _ = sender.send(event);
+    })
+    .map_err(watch_error)?;
+    // Watch the directory, as editors and font tools often replace the file
+    // instead of writing it in place, which would end a watch on the file itself
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    watcher.watch(dir, RecursiveMode::NonRecursive).map_err(watch_error)?;
+
+    let thread_ctx = ctx.clone();
+    let thread_path = path.clone();
+    std::thread::spawn(move || reload_on_change(&thread_ctx, &thread_path, &receiver, font_data));
+
+    log!(info, "watching {} for changes", path.display());
+    Ok(FontWatcher { path, _watcher: watcher })
+}
+
+/// Reload the font after each burst of changes to its file, until the watcher is dropped
+fn reload_on_change(ctx: &Context, path: &Path, events: &Receiver<notify::Result<notify::Event>>, mut current: Arc<FontData>) {
+    let affects_font = |event: &notify::Result<notify::Event>| match event {
+        Ok(event) => event.paths.iter().any(|changed| changed.file_name() == path.file_name()),
+        Err(_) => false,
+    };
+
+    while let Ok(event) = events.recv() {
+        if !affects_font(&event) {
+            continue;
+        }
+        // Wait until the file has been quiet for a moment
+        loop {
+            match events.recv_timeout(DEBOUNCE) {
+                Ok(_) => continue,
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+
+        match read(path) {
+            Ok(font_data) if font_data.font == current.font && font_data.index == current.index => {}
+            Ok(font_data) => {
+                log!(info, "{} changed, reloading", path.display());
+                current = font_data;
+                register(ctx, Arc::clone(&current));
+                ctx.request_repaint();
+            }
+            Err(err) => log!(warn, "{} changed but can't be used, keeping the previous font ({})", path.display(), err),
+        }
+    }
+}
+
+/// Read and validate the current contents of `path`, bypassing the font cache
+fn read(path: &Path) -> Result<Arc<FontData>, FontError> {
+    let bytes = std::fs::read(path).map_err(|err| FontError::from_read(path, err))?;
+    let index = faces::select_face_index(&bytes, VariantPreference::Auto.resolve());
+    faces::validate_font(&bytes, index).map_err(|reason| FontError::InvalidFont {
+        path: Some(path.to_path_buf()),
+        reason,
+    })?;
+
+    let font_data = FontData {
+        index,
+        ..FontData::from_owned(bytes)
+    };
+    let tweak = crate::recommended_tweak(&font_data).unwrap_or_default();
+    Ok(Arc::new(font_data.tweak(tweak)))
+}
+
+fn register(ctx: &Context, font_data: Arc<FontData>) {
+    crate::apply_chinese_font(ctx, "chinese", font_data);
+}
+
+fn watch_error(err: notify::Error) -> FontError {
+    let kind = match &err.kind {
+        notify::ErrorKind::PathNotFound => std::io::ErrorKind::NotFound,
+        notify::ErrorKind::Io(err) => err.kind(),
+        _ => std::io::ErrorKind::Other,
+    };
+    FontError::ReadError(std::io::Error::new(kind, err))
+}
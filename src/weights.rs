@@ -0,0 +1,351 @@
+//! Loading the light and bold weights of the Chinese font
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use egui::{Context, FontData, FontFamily, FontTweak};
+use ttf_parser::Face;
+
+use crate::{faces, FontConfig, FontError, FontOrigin, FontPreference, LoadedFont};
+
+/// Key and family name of the bold Chinese font
+const BOLD_NAME: &str = "chinese-bold";
+
+/// Key and family name of the light Chinese font
+const LIGHT_NAME: &str = "chinese-light";
+
+/// The weight of a loaded font
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FontWeight {
+    /// Lighter than the regular weight
+    Light,
+    /// The weight used for body text
+    #[default]
+    Regular,
+    /// Heavier than the regular weight
+    Bold,
+}
+
+/// Setup the Chinese font together with its light and bold weights
+///
+/// egui has no font weights of its own and only fakes bold text, which smears
+/// dense Han glyphs. This loads the regular Chinese font like
+/// [`setup_chinese_fonts`](crate::setup_chinese_fonts), then looks for heavier
+/// and lighter faces of the same family, both inside the same collection and
+/// in neighboring files such as `msyhbd.ttc` next to `msyh.ttc`.
+///
+/// The regular font is registered as `"chinese"`. The bold and light fonts are
+/// registered as `"chinese-bold"` and `"chinese-light"`, each in a font family
+/// of the same name ([`chinese_bold_family`], [`chinese_light_family`]) that
+/// falls back to the proportional fonts. Both families are registered even if
+/// the weight isn't installed, so using them never panics.
+///
+/// # Arguments
+/// * `ctx` - The egui context to configure
+///
+/// # Returns
+/// * `Ok(Vec<LoadedFont>)` with one entry per weight found, regular first;
+///   check [`LoadedFont::weight`] to see whether a bold or light font was found
+/// * `Err(FontError)` if no regular Chinese font could be loaded
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use egui::FontId;
+/// use egui_chinese_font::{chinese_bold_family, setup_chinese_font_weights, FontWeight};
+///
+/// let ctx = egui::Context::default();
+/// let fonts = setup_chinese_font_weights(&ctx)?;
+/// if !fonts.iter().any(|font| font.weight() == FontWeight::Bold) {
+///     eprintln!("no bold Chinese font installed");
+/// }
+///
+/// let heading = FontId::new(20.0, chinese_bold_family());
+/// # Ok::<(), egui_chinese_font::FontError>(())
+/// ```
+pub fn setup_chinese_font_weights(ctx: &Context) -> Result<Vec<LoadedFont>, FontError> {
+    setup_chinese_font_weights_with_config(ctx, &FontConfig::default())
+}
+
+/// Setup the Chinese font and its weights using a custom [`FontConfig`]
+///
+/// The config's tweak applies to every weight. See [`setup_chinese_font_weights`].
+pub fn setup_chinese_font_weights_with_config(ctx: &Context, config: &FontConfig) -> Result<Vec<LoadedFont>, FontError> {
+    let result = load_weights(ctx, config);
+    config.notify_loaded(result.as_ref().map(|loaded| &loaded[0]));
+    result
+}
+
+fn load_weights(ctx: &Context, config: &FontConfig) -> Result<Vec<LoadedFont>, FontError> {
+    let found = crate::find_chinese_font_with_config(config)?;
+    let weights = find_weights(&found.font_data, found.path.as_deref());
+
+    let system = |found: Option<WeightMatch>| found.map(|found| (found.into_font_data(), FontOrigin::System));
+    let others = [(FontWeight::Bold, system(weights.bold)), (FontWeight::Light, system(weights.light))];
+    Ok(register_weights(ctx, config, (found.font_data, FontOrigin::System), others))
+}
+
+/// Setup the Chinese font weights from the given sources, e.g. `msyhbd.ttc` for bold
+///
+/// Like [`setup_chinese_font_weights`], but each weight's font can be chosen
+/// explicitly. Without an entry for [`FontWeight::Regular`], or with
+/// [`FontPreference::System`], the regular font is searched like
+/// [`setup_chinese_fonts`](crate::setup_chinese_fonts). For bold and light,
+/// [`FontPreference::System`] looks for the weight next to the regular font
+/// like [`setup_chinese_font_weights`], and a missing entry leaves the weight
+/// out; its family still falls back to the proportional fonts.
+///
+/// # Arguments
+/// * `ctx` - The egui context to configure
+/// * `sources` - Where each weight's font comes from, e.g. a `HashMap<FontWeight, FontPreference>`
+///
+/// # Returns
+/// * `Ok(Vec<LoadedFont>)` with one entry per registered weight, regular first
+/// * `Err(FontError)` if the regular font or an explicitly given font could
+///   not be loaded; nothing is registered then
+///
+/// # Example
+///
+/// ```rust
+/// use egui_chinese_font::{chinese_bold_family, setup_chinese_font_weights_from, FontPreference, FontWeight};
+///
+/// # let dir = std::env::temp_dir();
+/// # let fonts = egui::FontDefinitions::default().font_data;
+/// # std::fs::write(dir.join("egui-chinese-font-msyh.ttf"), &*fonts["Ubuntu-Light"].font)?;
+/// # std::fs::write(dir.join("egui-chinese-font-msyhbd.ttf"), &*fonts["Hack"].font)?;
+/// let ctx = egui::Context::default();
+/// let loaded = setup_chinese_font_weights_from(&ctx, [
+///     (FontWeight::Regular, FontPreference::Path(dir.join("egui-chinese-font-msyh.ttf"))),
+///     (FontWeight::Bold, FontPreference::Path(dir.join("egui-chinese-font-msyhbd.ttf"))),
+/// ])?;
+/// assert_eq!(loaded.iter().map(|font| font.weight()).collect::<Vec<_>>(), [FontWeight::Regular, FontWeight::Bold]);
+///
+/// let heading = egui::FontId::new(20.0, chinese_bold_family());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn setup_chinese_font_weights_from(
+    ctx: &Context,
+    sources: impl IntoIterator<Item = (FontWeight, FontPreference)>,
+) -> Result<Vec<LoadedFont>, FontError> {
+    let mut sources: HashMap<FontWeight, FontPreference> = sources.into_iter().collect();
+    let config = FontConfig::default();
+
+    let (regular, path) = match sources.remove(&FontWeight::Regular) {
+        None | Some(FontPreference::System) => {
+            let found = crate::find_chinese_font_with_config(&config)?;
+            ((found.font_data, FontOrigin::System), found.path)
+        }
+        Some(preference) => {
+            let path = match &preference {
+                FontPreference::Path(path) => Some(path.clone()),
+                _ => None,
+            };
+            (load_source(preference)?, path)
+        }
+    };
+
+    let mut detected = None;
+    let mut others = Vec::new();
+    for weight in [FontWeight::Bold, FontWeight::Light] {
+        let font = match sources.remove(&weight) {
+            None => None,
+            Some(FontPreference::System) => {
+                let weights = detected.get_or_insert_with(|| find_weights(&regular.0, path.as_deref()));
+                let found = match weight {
+                    FontWeight::Bold => weights.bold.take(),
+                    _ => weights.light.take(),
+                };
+                found.map(|found| (found.into_font_data(), FontOrigin::System))
+            }
+            Some(preference) => Some(load_source(preference)?),
+        };
+        others.push((weight, font));
+    }
+
+    Ok(register_weights(ctx, &config, regular, others))
+}
+
+/// Load a font given as bytes or a file
+fn load_source(preference: FontPreference) -> Result<(FontData, FontOrigin), FontError> {
+    match preference {
+        FontPreference::Path(path) => Ok((crate::read_font_file(&path, None)?, FontOrigin::File(path))),
+        FontPreference::Custom(font_data) => Ok((crate::validated_font_data(font_data, 0)?, FontOrigin::Memory)),
+        FontPreference::System => crate::find_chinese_font().map(|found| (found.font_data, FontOrigin::System)),
+    }
+}
+
+/// Register `regular` like the discovered Chinese font, and each of `others` under its weight's family
+fn register_weights(
+    ctx: &Context,
+    config: &FontConfig,
+    (regular, origin): (FontData, FontOrigin),
+    others: impl IntoIterator<Item = (FontWeight, Option<(FontData, FontOrigin)>)>,
+) -> Vec<LoadedFont> {
+    let regular = Arc::new(config.configure(regular));
+    crate::apply_configured_fonts(ctx, vec![(config.font_name().to_owned(), Arc::clone(&regular))], config);
+    let mut loaded = vec![LoadedFont::new(config.font_name(), origin, regular).placed_like(config)];
+
+    let mut definitions = crate::current_font_definitions(ctx);
+    let proportional = definitions
+        .families
+        .get(&FontFamily::Proportional)
+        .cloned()
+        .unwrap_or_default();
+
+    for (weight, font) in others {
+        let name = match weight {
+            FontWeight::Bold => BOLD_NAME,
+            FontWeight::Light => LIGHT_NAME,
+            FontWeight::Regular => continue,
+        };
+        let mut names = proportional.clone();
+
+        if let Some((font_data, origin)) = font {
+            let tweak = config.tweak_for(&font_data);
+            let font_data = Arc::new(font_data.tweak(tweak));
+            definitions.font_data.insert(name.to_owned(), Arc::clone(&font_data));
+            names.insert(0, name.to_owned());
+            loaded.push(
+                LoadedFont::new(name, origin, font_data)
+                    .with_weight(weight)
+                    .placed_in(FontFamily::Name(name.into())),
+            );
+        } else {
+            log!(info, "no {:?} weight of the Chinese font found", weight);
+        }
+
+        definitions.families.insert(FontFamily::Name(name.into()), names);
+    }
+
+    crate::set_font_definitions(ctx, definitions);
+
+    loaded
+}
+
+/// The font family holding the bold Chinese font
+///
+/// Registered by [`setup_chinese_font_weights`]. Use it with `FontId` or
+/// `TextFormat` for bold Chinese text.
+pub fn chinese_bold_family() -> FontFamily {
+    FontFamily::Name(BOLD_NAME.into())
+}
+
+/// The font family holding the light Chinese font
+///
+/// Registered by [`setup_chinese_font_weights`].
+pub fn chinese_light_family() -> FontFamily {
+    FontFamily::Name(LIGHT_NAME.into())
+}
+
+/// The closest lighter and heavier faces found so far
+#[derive(Default)]
+struct Weights {
+    light: Option<WeightMatch>,
+    bold: Option<WeightMatch>,
+}
+
+/// A face of another weight, and how far it is from the ideal light or bold weight
+struct WeightMatch {
+    distance: u16,
+    font: Cow<'static, [u8]>,
+    index: u32,
+    path: Option<PathBuf>,
+}
+
+impl WeightMatch {
+    fn into_font_data(self) -> FontData {
+        let font_data = FontData {
+            font: self.font,
+            index: self.index,
+            tweak: FontTweak::default(),
+        };
+        match &self.path {
+            Some(path) => crate::cache::store(path, font_data),
+            None => font_data,
+        }
+    }
+}
+
+impl Weights {
+    /// Consider the upright faces in `data`, read from `path`, that share the family and width of `regular`
+    // Taking the `Cow` lets a match share cached bytes instead of copying them
+    #[allow(clippy::ptr_arg)]
+    fn consider(&mut self, data: &Cow<'static, [u8]>, path: Option<&Path>, regular: &Face<'_>, family: &str) {
+        for (index, face) in faces::faces(data) {
+            if face.is_italic() || face.width() != regular.width() || faces::english_family(&face).as_deref() != Some(family) {
+                continue;
+            }
+
+            let weight = face.weight().to_number();
+            let (slot, target) = match weight.cmp(&regular.weight().to_number()) {
+                Ordering::Greater => (&mut self.bold, 700),
+                Ordering::Less => (&mut self.light, 300),
+                Ordering::Equal => continue,
+            };
+
+            let distance = weight.abs_diff(target);
+            if slot.as_ref().map_or(true, |best| distance < best.distance) && faces::validate_font(data, index).is_ok() {
+                *slot = Some(WeightMatch {
+                    distance,
+                    font: data.clone(),
+                    index,
+                    path: path.map(Path::to_path_buf),
+                });
+            }
+        }
+    }
+}
+
+/// Find the light and bold faces of the family `regular` belongs to
+///
+/// Looks inside `regular` itself, for collections holding several weights,
+/// and in the files next to `path`.
+fn find_weights(regular: &FontData, path: Option<&Path>) -> Weights {
+    let mut weights = Weights::default();
+
+    let Ok(face) = Face::parse(&regular.font, regular.index) else {
+        return weights;
+    };
+    let Some(family) = faces::english_family(&face) else {
+        return weights;
+    };
+
+    weights.consider(&regular.font, path, &face, &family);
+    for sibling in path.map(sibling_files).unwrap_or_default() {
+        match crate::cache::read(&sibling) {
+            Ok(data) => weights.consider(&data, Some(&sibling), &face, &family),
+            Err(err) => log!(debug, "{}: skipped ({})", sibling.display(), err),
+        }
+    }
+
+    weights
+}
+
+/// Font files next to `path` whose names start like its own, e.g. `msyhbd.ttc` for `msyh.ttc`
+fn sibling_files(path: &Path) -> Vec<PathBuf> {
+    let (Some(dir), Some(stem)) = (path.parent(), path.file_stem().and_then(|stem| stem.to_str())) else {
+        return Vec::new();
+    };
+    let prefix = stem.split(['-', '_', ' ']).next().unwrap_or(stem).to_ascii_lowercase();
+
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut siblings: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|candidate| candidate != path && faces::is_font_file(candidate))
+        .filter(|candidate| {
+            candidate
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.to_ascii_lowercase().starts_with(&prefix))
+        })
+        .collect();
+    siblings.sort();
+    siblings
+}